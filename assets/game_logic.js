@@ -0,0 +1,34 @@
+// QuickJS 回退后端（--js-fallback）执行的游戏逻辑
+//
+// 与 game-logic/src/index.ts 的 processKeyboard / updateGame / onUiEvent 保持一致，
+// 但只能调用 bevyApi.log：回退路径下没有 UI 等其他 bevy-api。
+
+var GAME_CONFIG = {
+    boundaryX: 300.0,
+    boundaryY: 200.0
+};
+
+function clamp(value, limit) {
+    return Math.max(-limit, Math.min(limit, value));
+}
+
+function processKeyboard(input) {
+    return { right: input.right, left: input.left, up: input.up, down: input.down };
+}
+
+function updateGame(keyboard, state, delta) {
+    var vx = 0;
+    var vy = 0;
+    if (keyboard.right) vx += state.speed;
+    if (keyboard.left)  vx -= state.speed;
+    if (keyboard.up)    vy += state.speed;
+    if (keyboard.down)  vy -= state.speed;
+    return {
+        x: clamp(state.x + vx * delta, GAME_CONFIG.boundaryX),
+        y: clamp(state.y + vy * delta, GAME_CONFIG.boundaryY)
+    };
+}
+
+function onUiEvent(eventType) {
+    bevyApi.log("收到 UI 事件（JS 回退后端不支持 UI）: " + eventType);
+}
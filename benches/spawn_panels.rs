@@ -0,0 +1,61 @@
+// ─── spawn-panel 入队开销基准 ─────────────────────────────────────────────────
+//
+// 以 10×10 网格为例，对比 100 次 spawn-panel 与一次 spawn-panels 的 Host 侧开销（不含 Guest 调用与 ECS 消费）。
+// 运行：cargo bench --bench spawn_panels
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use test_game::game::logic::bevy_api::{Host, PanelConfig};
+use test_game::HostState;
+
+fn grid_configs(columns: usize, rows: usize) -> Vec<PanelConfig> {
+    (0..columns * rows)
+        .map(|i| PanelConfig {
+            key:        format!("grid.cell{}", i),
+            parent_key: Some("grid".to_string()),
+            x:          (i % columns) as f32 * 32.0,
+            y:          (i / columns) as f32 * 32.0,
+            width:      30.0,
+            height:     30.0,
+            color_r:    0.2,
+            color_g:    0.2,
+            color_b:    0.25,
+            color_a:    1.0,
+            z_index:    None,
+            border:     None,
+            corner_radius: None,
+        })
+        .collect()
+}
+
+fn bench_spawn_panels(c: &mut Criterion) {
+    let mut group = c.benchmark_group("spawn_panels");
+    let configs = grid_configs(10, 10);
+
+    group.bench_function("singular_100", |b| {
+        b.iter_batched(
+            || (HostState::new(None), configs.clone()),
+            |(mut host, configs)| {
+                for config in configs {
+                    host.spawn_panel(config).unwrap();
+                }
+                black_box(host)
+            },
+            BatchSize::SmallInput,
+        );
+    });
+
+    group.bench_function("batch_100", |b| {
+        b.iter_batched(
+            || (HostState::new(None), configs.clone()),
+            |(mut host, configs)| {
+                host.spawn_panels(configs).unwrap();
+                black_box(host)
+            },
+            BatchSize::SmallInput,
+        );
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_spawn_panels);
+criterion_main!(benches);
@@ -0,0 +1,59 @@
+// ─── spawn-text 入队开销基准 ──────────────────────────────────────────────────
+//
+// 对比逐个调用 spawn-text 与一次 batch-spawn-text 的 Host 侧开销（不含 Guest 调用与 ECS 消费）。
+// 运行：cargo bench --bench spawn_text
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use test_game::game::logic::bevy_api::{Host, TextConfig};
+use test_game::HostState;
+
+fn text_configs(count: usize) -> Vec<TextConfig> {
+    (0..count)
+        .map(|i| TextConfig {
+            key:        format!("inventory.slot{}", i),
+            parent_key: "inventory".to_string(),
+            text:       format!("物品 #{}", i),
+            font_size:  14.0,
+            color_r:    0.9,
+            color_g:    0.9,
+            color_b:    0.9,
+            font:       None,
+            font_path:  None,
+        })
+        .collect()
+}
+
+fn bench_spawn_text(c: &mut Criterion) {
+    let mut group = c.benchmark_group("spawn_text");
+    for count in [1, 10, 100] {
+        let configs = text_configs(count);
+
+        group.bench_with_input(BenchmarkId::new("singular", count), &configs, |b, configs| {
+            b.iter_batched(
+                || (HostState::new(None), configs.clone()),
+                |(mut host, configs)| {
+                    for config in configs {
+                        host.spawn_text(config).unwrap();
+                    }
+                    black_box(host)
+                },
+                BatchSize::SmallInput,
+            );
+        });
+
+        group.bench_with_input(BenchmarkId::new("batch", count), &configs, |b, configs| {
+            b.iter_batched(
+                || (HostState::new(None), configs.clone()),
+                |(mut host, configs)| {
+                    host.batch_spawn_text(configs).unwrap();
+                    black_box(host)
+                },
+                BatchSize::SmallInput,
+            );
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_spawn_text);
+criterion_main!(benches);
@@ -7,9 +7,68 @@
 //   - wasmtime Component 的调用胶水代码
 //
 // 这完全替代了原来手写的 build_args / parse_output 桥接代码。
+//
+// 另外计算 wit/game.wit 的 SHA-256，以 GAME_WIT_HASH 环境变量嵌入宿主，
+// 实例化组件时与组件导出的 wit-hash 比对（TS 侧由 game-logic/scripts/wit-hash.mjs 生成同一个值）。
+//
+// 测试夹具：tests/fixtures/game_logic.wat 是手写的 core module（canonical ABI 展平后的签名），
+// 这里填入当前的 wit-hash，再编码为 WASM Component，写入 $OUT_DIR/fixtures/：
+//   - game_logic.wasm          按 game-world-reloadable 编码，导出 hot-reload
+//   - game_logic_minimal.wasm  去掉 hot-reload 段后按 game-world 编码，用于测试缺少可选导出的组件
+// 不依赖 Node 与 jco，cargo test 总能拿到与当前 game.wit 一致的组件。
+
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// 夹具 WAT 中 wit-hash 返回值的占位符，长度与 SHA-256 十六进制串不同，漏替换时编码前即可发现
+const WIT_HASH_PLACEHOLDER: &str = "@WIT_HASH@";
+/// 夹具 WAT 中 hot-reload 导出所在区段的首尾标记
+const HOT_RELOAD_BEGIN: &str = ";; @HOT_RELOAD_BEGIN";
+const HOT_RELOAD_END: &str = ";; @HOT_RELOAD_END";
 
 fn main() {
     // 告知 cargo：wit 目录变化时重新运行 build.rs
     println!("cargo:rerun-if-changed=wit/");
     println!("cargo:rerun-if-changed=assets/game_logic.wasm");
+    println!("cargo:rerun-if-changed=tests/fixtures/");
+
+    let wit = std::fs::read("wit/game.wit").expect("读取 wit/game.wit 失败");
+    let hash: String = Sha256::digest(&wit).iter().map(|byte| format!("{:02x}", byte)).collect();
+    println!("cargo:rustc-env=GAME_WIT_HASH={}", hash);
+
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").expect("缺少 OUT_DIR"));
+    let wat_path = Path::new("tests/fixtures/game_logic.wat");
+    let source = std::fs::read_to_string(wat_path)
+        .unwrap_or_else(|e| panic!("读取夹具 {} 失败: {}", wat_path.display(), e));
+    assert!(source.contains(WIT_HASH_PLACEHOLDER), "夹具 {} 缺少 wit-hash 占位符 {}", wat_path.display(), WIT_HASH_PLACEHOLDER);
+    let source = source.replace(WIT_HASH_PLACEHOLDER, &hash);
+
+    build_fixture(wat_path, &source, "game-world-reloadable", &out_dir.join("fixtures/game_logic.wasm"));
+    build_fixture(wat_path, &without_hot_reload(&source), "game-world", &out_dir.join("fixtures/game_logic_minimal.wasm"));
+}
+
+/// 删除首尾标记之间（含标记行）的 hot-reload 导出
+fn without_hot_reload(source: &str) -> String {
+    let begin = source.find(HOT_RELOAD_BEGIN).expect("夹具缺少 hot-reload 起始标记");
+    let end = source.find(HOT_RELOAD_END).expect("夹具缺少 hot-reload 结束标记") + HOT_RELOAD_END.len();
+    format!("{}{}", &source[..begin], &source[end..])
+}
+
+/// 把夹具 core module 编码为实现 world 的组件；任何一步失败都中止构建，测试不会在缺少夹具时静默通过
+fn build_fixture(wat_path: &Path, source: &str, world: &str, out_path: &Path) {
+
+    let mut module = wat::parse_str(source)
+        .unwrap_or_else(|e| panic!("解析夹具 {} 失败: {}", wat_path.display(), e));
+    let mut resolve = wit_parser::Resolve::default();
+    let (package, _) = resolve.push_dir(Path::new("wit")).unwrap_or_else(|e| panic!("解析 wit/ 失败: {:?}", e));
+    let world = resolve.select_world(package, Some(world)).unwrap_or_else(|e| panic!("{:?}", e));
+    wit_component::embed_component_metadata(&mut module, &resolve, world, wit_component::StringEncoding::UTF8)
+        .unwrap_or_else(|e| panic!("嵌入夹具的组件元数据失败: {:?}", e));
+    let component = wit_component::ComponentEncoder::default()
+        .module(&module)
+        .and_then(|encoder| encoder.validate(true).encode())
+        .unwrap_or_else(|e| panic!("夹具 {} 编码为组件失败: {:?}", wat_path.display(), e));
+
+    std::fs::create_dir_all(out_path.parent().unwrap()).expect("创建夹具输出目录失败");
+    std::fs::write(out_path, component).unwrap_or_else(|e| panic!("写入 {} 失败: {}", out_path.display(), e));
 }
@@ -0,0 +1,11 @@
+// 生成 src/wit-hash.ts：../wit/game.wit 的 SHA-256，与 Rust 侧 build.rs 的算法一致。
+// compile 前自动执行；宿主实例化时调用 wit-hash 比对，不一致则拒绝加载本组件。
+import { createHash } from 'node:crypto';
+import { readFileSync, writeFileSync } from 'node:fs';
+
+const wit = readFileSync(new URL('../../wit/game.wit', import.meta.url));
+const hash = createHash('sha256').update(wit).digest('hex');
+writeFileSync(
+    new URL('../src/wit-hash.ts', import.meta.url),
+    `// 由 scripts/wit-hash.mjs 生成，请勿手动修改\nexport const WIT_HASH = '${hash}';\n`,
+);
@@ -21,8 +21,10 @@ import {
     spawnText,
     despawn,
     setVisible,
+    setPaused,
     log,
 } from 'bevy:api/bevy-api';
+import { WIT_HASH } from './wit-hash.js';
 
 // ─── WIT 生成的类型（jco 自动推导，无需手写）────────────────────────────────
 // 这些类型与 wit/game.wit 中的 record 定义完全对应
@@ -46,8 +48,16 @@ export interface PlayerState {
     speed: number;
 }
 
+export interface TextInput {
+    typedChars: string;
+    backspaces: number;
+    enter:      boolean;
+    focusedKey?: string;
+}
+
 export interface PanelConfig {
     key:     string;
+    parentKey?: string;  // WIT 的 option<string> → 可选字段
     x:       number;
     y:       number;
     width:   number;
@@ -56,6 +66,17 @@ export interface PanelConfig {
     colorG:  number;
     colorB:  number;
     colorA:  number;
+    zIndex?: number;     // WIT 的 option<s32> → 可选字段
+    border?: PanelBorder; // WIT 的 option<panel-border> → 可选字段
+    cornerRadius?: number; // WIT 的 option<float32> → 可选字段
+}
+
+export interface PanelBorder {
+    width:  number;
+    colorR: number;
+    colorG: number;
+    colorB: number;
+    colorA: number;
 }
 
 export interface TextConfig {
@@ -66,6 +87,7 @@ export interface TextConfig {
     colorR:    number;
     colorG:    number;
     colorB:    number;
+    font?:     string;   // WIT 的 option<string> → 可选字段
 }
 
 // ─── 游戏配置（TypeScript 原生，无需任何桥接）───────────────────────────────
@@ -200,6 +222,26 @@ class UiManager {
         log('UI 面板已销毁');
     }
 
+    /** 场景切换后 Bevy 已销毁全部 UI 实体，重置为初始状态 */
+    reset(): void {
+        this.isCreated = false;
+        this.isVisible = false;
+    }
+
+    /** 导出当前状态，供热重载时 save-state 使用 */
+    snapshot(): { isCreated: boolean; isVisible: boolean } {
+        return { isCreated: this.isCreated, isVisible: this.isVisible };
+    }
+
+    /**
+     * 恢复 snapshot() 导出的状态
+     * 热重载后 Bevy 侧的 UI 实体仍然存在，必须恢复 isCreated，否则会重复 spawnPanel
+     */
+    restore(snapshot: { isCreated: boolean; isVisible: boolean }): void {
+        this.isCreated = snapshot.isCreated;
+        this.isVisible = snapshot.isVisible;
+    }
+
     togglePanel(playerState: PlayerState): void {
         if (this.isVisible) {
             this.hidePanel();
@@ -277,6 +319,16 @@ export function updateGame(
     return { x: newX, y: newY };
 }
 
+/**
+ * 碰撞回调：玩家开始接触墙体等碰撞体时调用
+ * 对应 WIT: on-collision: func(normal-x: float32, normal-y: float32)
+ *
+ * 位置积分与碰撞响应由 Bevy 的物理引擎完成，这里只做游戏性反馈
+ */
+export function onCollision(normalX: number, normalY: number): void {
+    log(`玩家发生碰撞，法线: (${normalX.toFixed(2)}, ${normalY.toFixed(2)})`);
+}
+
 /**
  * UI 事件回调：Bevy 检测到按键等事件时调用
  * 对应 WIT: on-ui-event: func(event-type: string)
@@ -286,6 +338,11 @@ export function updateGame(
 export function onUiEvent(eventType: string): void {
     log(`收到 UI 事件: ${eventType}`);
 
+    // 补间完成 / 实体销毁 / 焦点控件被销毁通知：当前没有需要衔接的逻辑，仅记录
+    if (eventType.startsWith('tween-done:') || eventType.startsWith('despawned:') || eventType === 'focus-lost') {
+        return;
+    }
+
     switch (eventType) {
         case 'toggle_panel':
             uiManager.togglePanel(lastPlayerState);
@@ -300,3 +357,126 @@ export function onUiEvent(eventType: string): void {
             log(`未知事件类型: ${eventType}`);
     }
 }
+
+/**
+ * 非循环精灵表动画播放完毕
+ * 对应 WIT: on-animation-complete: func(key: string)
+ */
+export function onAnimationComplete(key: string): void {
+    log(`动画结束: ${key}`);
+}
+
+/**
+ * 点击回调：带 key 的面板被按下时调用
+ * 对应 WIT: on-entity-clicked: func(key: string)
+ */
+export function onEntityClicked(key: string): void {
+    log(`点击: ${key}`);
+}
+
+/**
+ * 焦点变化回调：Tab / Shift+Tab 切换焦点时调用
+ * 对应 WIT: on-focus-changed: func(old-key: option<string>, new-key: option<string>)
+ */
+export function onFocusChanged(oldKey: string | undefined, newKey: string | undefined): void {
+    log(`焦点: ${oldKey ?? '(无)'} → ${newKey ?? '(无)'}`);
+}
+
+/**
+ * 文本输入回调：本帧有字符输入、退格或回车时调用
+ * 对应 WIT: on-text-input: func(input: text-input)
+ */
+export function onTextInput(input: TextInput): void {
+    if (input.typedChars.length > 0) {
+        log(`输入: ${input.typedChars}（焦点: ${input.focusedKey ?? '无'}）`);
+    }
+}
+
+/**
+ * 窗口尺寸变化回调（已去抖）
+ * 对应 WIT: on-resize: func(width: u32, height: u32)
+ */
+export function onResize(width: number, height: number): void {
+    log(`窗口尺寸: ${width}x${height}`);
+}
+
+/**
+ * 窗口焦点变化回调：失焦时暂停，重新获得焦点时恢复
+ * 对应 WIT: on-window-focus-changed: func(focused: bool)
+ */
+export function onWindowFocusChanged(focused: boolean): void {
+    setPaused(!focused);
+}
+
+/**
+ * 场景加载完成回调
+ * 对应 WIT: on-scene-load: func(name: string)
+ *
+ * 旧场景的 UI 实体已被 Bevy 销毁，这里同步重置 UiManager
+ */
+export function onSceneLoad(name: string): void {
+    uiManager.reset();
+    lastPlayerState = { x: 0, y: 0, speed: GAME_CONFIG.playerSpeed };
+    log(`场景已加载: ${name}`);
+}
+
+/**
+ * 事件总线回调：组件间事件与已订阅的 "bevy:" 宿主事件
+ * 对应 WIT: on-event: func(event-type: string, payload: list<u8>)
+ */
+export function onEvent(eventType: string, payload: Uint8Array): void {
+    log(`收到事件: ${eventType}（${payload.length} 字节）`);
+}
+
+/**
+ * 应用退出前的清理回调
+ * 对应 WIT: on-shutdown: func()
+ *
+ * 超时会被 Bevy 强制中断，这里只做必须的收尾，不要执行耗时操作
+ */
+export function onShutdown(): void {
+    uiManager.reset();
+    log('收到退出通知');
+}
+
+/**
+ * 构建时 game.wit 的哈希，供宿主检查两侧接口版本一致
+ * 对应 WIT: wit-hash: func() -> string
+ */
+export function witHash(): string {
+    return WIT_HASH;
+}
+
+// ─── 热重载状态（对应 WIT 的可选导出 hot-reload，按 game-world-reloadable 构建）──
+
+/**
+ * 热重载前导出状态
+ * 对应 WIT: hot-reload.save-state: func() -> list<u8>
+ *
+ * 以 JSON 编码，字段缺失时 restoreState 使用默认值，保证新旧版本兼容
+ */
+export function saveState(): Uint8Array {
+    const snapshot = {
+        player: lastPlayerState,
+        ui:     uiManager.snapshot(),
+    };
+    return new TextEncoder().encode(JSON.stringify(snapshot));
+}
+
+/**
+ * 热重载后恢复状态
+ * 对应 WIT: hot-reload.restore-state: func(bytes: list<u8>)
+ */
+export function restoreState(bytes: Uint8Array): void {
+    const snapshot = JSON.parse(new TextDecoder().decode(bytes));
+    if (snapshot.player) {
+        lastPlayerState = snapshot.player;
+    }
+    if (snapshot.ui) {
+        uiManager.restore(snapshot.ui);
+    }
+    log(`状态已恢复，${bytes.length} 字节`);
+}
+
+/** game-logic 之外的导出接口以接口名（驼峰）导出为对象 */
+export const hotReload = { saveState, restoreState };
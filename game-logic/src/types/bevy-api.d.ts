@@ -12,6 +12,8 @@ declare module 'bevy:api/bevy-api' {
     /** 对应 WIT: record panel-config */
     export interface PanelConfig {
         key:     string;
+        /** 对应 WIT: option<string>；设置后面板参与父容器 flex 布局 */
+        parentKey?: string;
         x:       number;
         y:       number;
         width:   number;
@@ -20,6 +22,21 @@ declare module 'bevy:api/bevy-api' {
         colorG:  number;
         colorB:  number;
         colorA:  number;
+        /** 对应 WIT: option<s32>；初始层级 */
+        zIndex?: number;
+        /** 对应 WIT: option<panel-border>；宽度超过短边一半时截断 */
+        border?: PanelBorder;
+        /** 对应 WIT: option<float32>；圆角半径，超过短边一半时截断，边框仍为直角 */
+        cornerRadius?: number;
+    }
+
+    /** 对应 WIT: record panel-border */
+    export interface PanelBorder {
+        width:  number;
+        colorR: number;
+        colorG: number;
+        colorB: number;
+        colorA: number;
     }
 
     /** 对应 WIT: record text-config */
@@ -31,20 +48,448 @@ declare module 'bevy:api/bevy-api' {
         colorR:    number;
         colorG:    number;
         colorB:    number;
+        /** 对应 WIT: option<string>；load-font 注册的字体别名 */
+        font?:     string;
+        /** 对应 WIT: option<string>；字体文件路径，优先于 font */
+        fontPath?: string;
+    }
+
+    /** 对应 WIT: record image-config */
+    export interface ImageConfig {
+        key:          string;
+        parentKey?:   string;
+        width:        number;
+        height:       number;
+        texturePath:  string;
+    }
+
+    /** 对应 WIT: enum segment-kind */
+    export type SegmentKind = 'text' | 'icon';
+
+    /** 对应 WIT: record text-segment；icon 段的 content 为图标路径、size 为边长 */
+    export interface TextSegment {
+        kind:    SegmentKind;
+        content: string;
+        size:    number;
+        colorR:  number;
+        colorG:  number;
+        colorB:  number;
+    }
+
+    /** 对应 WIT: record rich-text-config */
+    export interface RichTextConfig {
+        key:        string;
+        parentKey?: string;
+        segments:   TextSegment[];
+    }
+
+    /** 对应 WIT: record animated-sprite-config */
+    export interface AnimatedSpriteConfig {
+        key:          string;
+        x:            number;
+        y:            number;
+        texturePath:  string;
+        tileWidth:    number;
+        tileHeight:   number;
+        columns:      number;
+        rows:         number;
+        fps:          number;
+    }
+
+    /** 对应 WIT: record tilemap-config；tiles 行优先、第 0 行在最上方，65535 为空格子 */
+    export interface TilemapConfig {
+        key:            string;
+        x:              number;
+        y:              number;
+        cols:           number;
+        rows:           number;
+        tileSize:       number;
+        tilesetPath:    string;
+        tilesetColumns: number;
+        tilesetRows:    number;
+        tiles:          Uint16Array | number[];
+    }
+
+    /** 对应 WIT: record input-field-config */
+    export interface InputFieldConfig {
+        key:         string;
+        x:           number;
+        y:           number;
+        width:       number;
+        height:      number;
+        placeholder: string;
+        maxLength:   number;
+    }
+
+    /** 对应 WIT: record world-text-config；x/y 为世界坐标 */
+    export interface WorldTextConfig {
+        key:      string;
+        x:        number;
+        y:        number;
+        text:     string;
+        fontSize: number;
+        colorR:   number;
+        colorG:   number;
+        colorB:   number;
+        font?:    string;
+    }
+
+    /** 对应 WIT: record progress-bar-config；value 超出 0~1 时由宿主钳制 */
+    export interface ProgressBarConfig {
+        key:        string;
+        parentKey?: string;
+        x:          number;
+        y:          number;
+        width:      number;
+        height:     number;
+        bgR:        number;
+        bgG:        number;
+        bgB:        number;
+        bgA:        number;
+        fillR:      number;
+        fillG:      number;
+        fillB:      number;
+        fillA:      number;
+        value:      number;
+    }
+
+    /** 对应 WIT: record ui-rect */
+    export interface UiRect {
+        x:      number;
+        y:      number;
+        width:  number;
+        height: number;
+    }
+
+    /** 对应 WIT: enum flex-dir */
+    export type FlexDir = 'row' | 'column';
+
+    /** 对应 WIT: enum align-mode */
+    export type AlignMode = 'start' | 'center' | 'end' | 'stretch';
+
+    /** 对应 WIT: enum justify-mode */
+    export type JustifyMode = 'start' | 'center' | 'end' | 'space-between' | 'space-around';
+
+    /** 对应 WIT: record container-config */
+    export interface ContainerConfig {
+        key:            string;
+        parentKey?:     string;
+        flexDirection:  FlexDir;
+        gap:            number;
+        padding:        number;
+        alignItems:     AlignMode;
+        justifyContent: JustifyMode;
+    }
+
+    /** 对应 WIT: enum tween-prop */
+    export type TweenProp = 'position-x' | 'position-y' | 'opacity' | 'scale';
+
+    /** 对应 WIT: enum easing-kind */
+    export type EasingKind = 'linear' | 'ease-in' | 'ease-out' | 'ease-in-out';
+
+    /** 对应 WIT: enum permission-type */
+    export type PermissionType = 'read-file' | 'write-file' | 'network' | 'clipboard' | 'audio';
+
+    /** 对应 WIT: enum permission-result */
+    export type PermissionResult = 'granted' | 'denied';
+
+    /** 对应 WIT: record vec2 */
+    export interface Vec2 {
+        x: number;
+        y: number;
+    }
+
+    /** 对应 WIT: record game-time（u64 由 jco 映射为 bigint） */
+    export interface GameTime {
+        elapsedSecs: number;
+        deltaSecs:   number;
+        frameCount:  bigint;
     }
 
     /** 对应 WIT: spawn-panel: func(config: panel-config) */
     export function spawnPanel(config: PanelConfig): void;
 
+    /** 对应 WIT: spawn-persistent-panel: func(config: panel-config)；场景切换时保留 */
+    export function spawnPersistentPanel(config: PanelConfig): void;
+
     /** 对应 WIT: spawn-text: func(config: text-config) */
     export function spawnText(config: TextConfig): void;
 
+    /** 对应 WIT: spawn-container: func(config: container-config) */
+    export function spawnContainer(config: ContainerConfig): void;
+
+    /** 对应 WIT: spawn-image: func(config: image-config) */
+    export function spawnImage(config: ImageConfig): void;
+
+    /** 对应 WIT: spawn-text-with-icons: func(config: rich-text-config) */
+    export function spawnTextWithIcons(config: RichTextConfig): void;
+
+    /** 对应 WIT: spawn-animated-sprite: func(config: animated-sprite-config) */
+    export function spawnAnimatedSprite(config: AnimatedSpriteConfig): void;
+
+    /** 对应 WIT: spawn-tilemap: func(config: tilemap-config) */
+    export function spawnTilemap(config: TilemapConfig): void;
+
+    /** 对应 WIT: spawn-input-field: func(config: input-field-config) */
+    export function spawnInputField(config: InputFieldConfig): void;
+
+    /** 对应 WIT: spawn-world-text: func(config: world-text-config) */
+    export function spawnWorldText(config: WorldTextConfig): void;
+
+    /** 对应 WIT: request-input-value: func(key: string) */
+    export function requestInputValue(key: string): void;
+
+    /** 对应 WIT: get-input-value: func(key: string) -> option<string> */
+    export function getInputValue(key: string): string | undefined;
+
+    /** 对应 WIT: spawn-progress-bar: func(config: progress-bar-config) */
+    export function spawnProgressBar(config: ProgressBarConfig): void;
+
+    /** 对应 WIT: set-progress: func(key: string, value: float32) */
+    export function setProgress(key: string, value: number): void;
+
+    /** 对应 WIT: spawn-tooltip: func(key: string, anchor-key: string, text: string, max-width: float32)；锚点需已完成布局 */
+    export function spawnTooltip(key: string, anchorKey: string, text: string, maxWidth: number): void;
+
+    /** 对应 WIT: set-animation: func(key: string, start-index: u32, end-index: u32, fps: float32) */
+    export function setAnimation(key: string, startIndex: number, endIndex: number, fps: number): void;
+
+    /** 对应 WIT: set-tile: func(map-key: string, col: u32, row: u32, tile-index: u16)；tileIndex 传 65535 清空该格 */
+    export function setTile(mapKey: string, col: number, row: number, tileIndex: number): void;
+
+    /** 对应 WIT: play-sprite-animation: func(key, sheet-path, rows: u32, cols: u32, fps: float32, looping: bool) */
+    export function playSpriteAnimation(key: string, sheetPath: string, rows: number, cols: number, fps: number, looping: boolean): void;
+
+    /** 对应 WIT: stop-animation: func(key: string) */
+    export function stopAnimation(key: string): void;
+
+    /** 对应 WIT: batch-spawn-text: func(configs: list<text-config>) */
+    export function batchSpawnText(configs: TextConfig[]): void;
+
+    /** 对应 WIT: spawn-texts: func(configs: list<text-config>)；同 batchSpawnText */
+    export function spawnTexts(configs: TextConfig[]): void;
+
+    /** 对应 WIT: spawn-panels: func(configs: list<panel-config>) */
+    export function spawnPanels(configs: PanelConfig[]): void;
+
     /** 对应 WIT: despawn: func(key: string) */
     export function despawn(key: string): void;
 
     /** 对应 WIT: set-visible: func(key: string, visible: bool) */
     export function setVisible(key: string, visible: boolean): void;
 
+    /** 对应 WIT: set-opacity: func(key: string, alpha: float32) */
+    export function setOpacity(key: string, alpha: number): void;
+
+    /** 对应 WIT: set-position: func(key: string, x: float32, y: float32)；UI 用 panel-config 坐标，世界实体用世界坐标 */
+    export function setPosition(key: string, x: number, y: number): void;
+
+    /** 对应 WIT: set-text-style；option<float32> 传 undefined 表示不修改 */
+    export function setTextStyle(key: string, fontSize?: number, r?: number, g?: number, b?: number): void;
+
+    /** 对应 WIT: set-font: func(key: string, font-alias: string, font-size: option<float32>) */
+    export function setFont(key: string, fontAlias: string, fontSize?: number): void;
+
+    /** 对应 WIT: set-focusable: func(key: string, focusable: bool) */
+    export function setFocusable(key: string, focusable: boolean): void;
+
+    /** 对应 WIT: set-draggable: func(key: string, draggable: bool)；拖动时回调 onUiEvent("drag:<key>:<x>:<y>") */
+    export function setDraggable(key: string, draggable: boolean): void;
+
+    /** 对应 WIT: set-focus: func(key: string)；传 "" 清除焦点 */
+    export function setFocus(key: string): void;
+
+    /** 对应 WIT: set-z-index: func(key: string, z: s32) */
+    export function setZIndex(key: string, z: number): void;
+
+    /** 对应 WIT: bring-to-front: func(key: string) */
+    export function bringToFront(key: string): void;
+
+    /** 对应 WIT: send-to-back: func(key: string) */
+    export function sendToBack(key: string): void;
+
+    /** 对应 WIT: reorder-child: func(parent-key: string, child-key: string, new-index: u32) */
+    export function reorderChild(parentKey: string, childKey: string, newIndex: number): void;
+
+    /** 对应 WIT: set-parent: func(key: string, new-parent-key: string)；传 "" 移到根节点 */
+    export function setParent(key: string, newParentKey: string): void;
+
+    /** 对应 WIT: tween: func(key: string, property: tween-prop, to: float32, duration-secs: float32, easing: easing-kind) */
+    export function tween(key: string, property: TweenProp, to: number, durationSecs: number, easing: EasingKind): void;
+
+    /** 对应 WIT: load-font: func(path: string, alias: string) */
+    export function loadFont(path: string, alias: string): void;
+
+    /** 对应 WIT: measure-text: func(text: string, font-alias: string, font-size: float32) -> vec2；近似值，字体未加载时为粗略估算；fontAlias 传 "" 使用默认字体 */
+    export function measureText(text: string, fontAlias: string, fontSize: number): Vec2;
+
+    /** 对应 WIT: query-ui-bounds: func(key: string) -> option<ui-rect> */
+    export function queryUiBounds(key: string): UiRect | undefined;
+
+    /** 对应 WIT: enum layout-warning-kind */
+    export type LayoutWarningKind = 'zero-size' | 'position-negative' | 'partially-off-screen' | 'off-screen';
+
+    /** 对应 WIT: record layout-warning */
+    export interface LayoutWarning {
+        key:  string;
+        kind: LayoutWarningKind;
+    }
+
+    /** 对应 WIT: get-layout-warnings: func() -> list<layout-warning>；取出后清空 */
+    export function getLayoutWarnings(): LayoutWarning[];
+
+    /** 对应 WIT: asset-ready: func(key: string) -> bool；读取上一帧快照 */
+    export function assetReady(key: string): boolean;
+
+    /** 对应 WIT: overlaps: func(key-a: string, key-b: string) -> bool；读取上一帧快照 */
+    export function overlaps(keyA: string, keyB: string): boolean;
+
+    /** 对应 WIT: tag: func(key: string, tag: string) */
+    export function tag(key: string, tag: string): void;
+
+    /** 对应 WIT: nearest: func(from-key: string, tag: string) -> option<string> */
+    export function nearest(fromKey: string, tag: string): string | undefined;
+
+    /** 对应 WIT: dump-ui: func() -> string；返回上一帧的 UI 树 JSON，调试时可 JSON.parse 后打印 */
+    export function dumpUi(): string;
+
+    /** 对应 WIT: load-scene: func(name: string) */
+    export function loadScene(name: string): void;
+
+    /** 对应 WIT: get-current-scene: func() -> string */
+    export function getCurrentScene(): string;
+
+    /** 对应 WIT: get-random-f32: func() -> float32 */
+    export function getRandomF32(): number;
+
+    /** 对应 WIT: get-random-range: func(min: float32, max: float32) -> float32 */
+    export function getRandomRange(min: number, max: number): number;
+
+    /** 对应 WIT: save-to-local-storage: func(slot: string, data: list<u8>) -> result<_, string>；err 时 jco 抛出异常 */
+    export function saveToLocalStorage(slot: string, data: Uint8Array): void;
+
+    /** 对应 WIT: load-from-local-storage: func(slot: string) -> option<list<u8>> */
+    export function loadFromLocalStorage(slot: string): Uint8Array | undefined;
+
+    /** 对应 WIT: set-paused: func(paused: bool) */
+    export function setPaused(paused: boolean): void;
+
+    /** 对应 WIT: paused: func() -> bool */
+    export function paused(): boolean;
+
+    /** 对应 WIT: request-permission: func(permission: permission-type) -> permission-result */
+    export function requestPermission(permission: PermissionType): PermissionResult;
+
+    /** 对应 WIT: get-player-position: func() -> vec2 */
+    export function getPlayerPosition(): Vec2;
+
+    /** 对应 WIT: record game-state-snapshot */
+    export interface GameStateSnapshot {
+        x:     number;
+        y:     number;
+        speed: number;
+        health: number;
+    }
+
+    /** 对应 WIT: query-game-state: func() -> game-state-snapshot */
+    export function queryGameState(): GameStateSnapshot;
+
+    /** 对应 WIT: set-player-position: func(x: float32, y: float32) */
+    export function setPlayerPosition(x: number, y: number): void;
+
+    /** 对应 WIT: get-player-speed: func() -> float32 */
+    export function getPlayerSpeed(): number;
+
+    /** 对应 WIT: set-player-speed: func(speed: float32) */
+    export function setPlayerSpeed(speed: number): void;
+
+    /** 对应 WIT: set-locale: func(locale: string)；加载完成后收到 on-ui-event("locale-changed:<locale>") */
+    export function setLocale(locale: string): void;
+
+    /** 对应 WIT: translate: func(key: string) -> string；未收录时返回 key */
+    export function translate(key: string): string;
+
+    /** 对应 WIT: set-var: func(name: string, value: float64)；与宿主原生系统共享 */
+    export function setVar(name: string, value: number): void;
+
+    /** 对应 WIT: get-var: func(name: string) -> option<float64> */
+    export function getVar(name: string): number | undefined;
+
+    /** 对应 WIT: set-gravity: func(x: float32, y: float32) */
+    export function setGravity(x: number, y: number): void;
+
+    /** 对应 WIT: apply-impulse: func(entity-key: string, ix: float32, iy: float32) */
+    export function applyImpulse(entityKey: string, ix: number, iy: number): void;
+
+    /** 对应 WIT: type request-handle = u32 */
+    export type RequestHandle = number;
+
+    /** 对应 WIT: record http-response */
+    export interface HttpResponse {
+        status: number;
+        body:   Uint8Array;
+    }
+
+    /** 对应 WIT: http-request: func(method: string, url: string, body: option<list<u8>>) -> request-handle */
+    export function httpRequest(method: string, url: string, body: Uint8Array | undefined): RequestHandle;
+
+    /** 对应 WIT: poll-request: func(handle: request-handle) -> option<result<http-response, string>>；未完成时为 undefined */
+    export function pollRequest(handle: RequestHandle):
+        | { tag: 'ok'; val: HttpResponse }
+        | { tag: 'err'; val: string }
+        | undefined;
+
+    /** 对应 WIT: post-event: func(event-type: string, payload: list<u8>)；下一帧经 on-event 广播 */
+    export function postEvent(eventType: string, payload: Uint8Array): void;
+
+    /** 对应 WIT: subscribe-to-bevy-event: func(event-type: string)；如 "scene-loaded" */
+    export function subscribeToBevyEvent(eventType: string): void;
+
+    /** 对应 WIT: get-time: func() -> game-time */
+    export function getTime(): GameTime;
+
+    /** 对应 WIT: current-frame: func() -> u64 */
+    export function currentFrame(): bigint;
+
+    /** 对应 WIT: set-timer: func(name: string, seconds: float32, repeating: bool)；到期回调 onUiEvent("timer:<name>") */
+    export function setTimer(name: string, seconds: number, repeating: boolean): void;
+
+    /** 对应 WIT: cancel-timer: func(name: string) */
+    export function cancelTimer(name: string): void;
+
+    /** 对应 WIT: play-sound: func(path: string, volume: float32) */
+    export function playSound(path: string, volume: number): void;
+
+    /** 对应 WIT: play-music: func(key: string, path: string, looped: bool, volume: float32) */
+    export function playMusic(key: string, path: string, looped: boolean, volume: number): void;
+
+    /** 对应 WIT: stop-music: func(key: string) */
+    export function stopMusic(key: string): void;
+
+    /** 对应 WIT: set-camera: func(x: float32, y: float32, zoom: float32) */
+    export function setCamera(x: number, y: number, zoom: number): void;
+
+    /** 对应 WIT: set-window-title: func(title: string) */
+    export function setWindowTitle(title: string): void;
+
+    /** 对应 WIT: set-window-icon: func(path: string) */
+    export function setWindowIcon(path: string): void;
+
+    /** 对应 WIT: clipboard-write: func(text: string) */
+    export function clipboardWrite(text: string): void;
+
+    /** 对应 WIT: clipboard-read: func() -> option<string> */
+    export function clipboardRead(): string | undefined;
+
+    /** 对应 WIT: tuple<float32, float32, float32, float32>，(r, g, b, a) */
+    export type DebugColor = [number, number, number, number];
+
+    /** 对应 WIT: draw-debug-line；立即模式，需每帧调用 */
+    export function drawDebugLine(x1: number, y1: number, x2: number, y2: number, color: DebugColor): void;
+
+    /** 对应 WIT: draw-debug-circle；立即模式，需每帧调用 */
+    export function drawDebugCircle(x: number, y: number, radius: number, color: DebugColor): void;
+
     /** 对应 WIT: log: func(msg: string) */
     export function log(msg: string): void;
 }
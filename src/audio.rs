@@ -0,0 +1,64 @@
+// ─── 音频 ─────────────────────────────────────────────────────────────────────
+//
+// wasm_tick 把 HostState 中的 AudioCommand 转存到 PendingAudio，由 process_audio 播放。
+// 音效播放完自动销毁；音乐实体记录在 MusicEntityMap 中，供 stop-music 按 key 停止。
+
+use super::*;
+use bevy::audio::Volume;
+
+#[derive(Resource, Default)]
+pub(crate) struct PendingAudio {
+    pub(crate) commands: Vec<AudioCommand>,
+}
+
+/// 音乐 key → 播放实体
+#[derive(Resource, Default)]
+pub(crate) struct MusicEntityMap {
+    pub(crate) map: HashMap<String, Entity>,
+}
+
+/// 消费音频命令：音效即播即弃，音乐按 key 替换或停止
+pub(crate) fn process_audio(
+    mut commands: Commands,
+    mut pending: ResMut<PendingAudio>,
+    asset_server: Res<AssetServer>,
+    mut music: ResMut<MusicEntityMap>,
+) {
+    for cmd in pending.commands.drain(..) {
+        match cmd {
+            AudioCommand::PlaySound { path, volume } => {
+                commands.spawn(AudioBundle {
+                    source: asset_server.load(path),
+                    settings: PlaybackSettings::DESPAWN.with_volume(Volume::new(volume)),
+                });
+            }
+
+            AudioCommand::PlayMusic { key, path, looped, volume } => {
+                // 音乐实体可能已被其他途径销毁，使用 get_entity 避免 panic
+                if let Some(old) = music.map.remove(&key) {
+                    if let Some(mut entity) = commands.get_entity(old) {
+                        entity.despawn();
+                    }
+                }
+                let settings = if looped { PlaybackSettings::LOOP } else { PlaybackSettings::ONCE };
+                let entity = commands.spawn(AudioBundle {
+                    source: asset_server.load(path.clone()),
+                    settings: settings.with_volume(Volume::new(volume)),
+                }).id();
+                music.map.insert(key.clone(), entity);
+                println!("[音频] 播放音乐 key={} path={}", key, path);
+            }
+
+            AudioCommand::StopMusic { key } => {
+                let Some(entity) = music.map.remove(&key) else {
+                    warn!("[音频] StopMusic 失败：找不到 key={}", key);
+                    continue;
+                };
+                if let Some(mut entity) = commands.get_entity(entity) {
+                    entity.despawn();
+                }
+                println!("[音频] 停止音乐 key={}", key);
+            }
+        }
+    }
+}
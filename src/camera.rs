@@ -0,0 +1,49 @@
+// ─── 相机控制 ─────────────────────────────────────────────────────────────────
+//
+// TS 调用 set-camera 后，wasm_tick 把请求转存到 PendingCamera，
+// process_camera 更新 setup 中创建的 MainCamera 的位置与缩放。
+
+use super::*;
+
+/// zoom 允许范围：超出时截断并输出警告
+pub(crate) const CAMERA_ZOOM_RANGE: (f32, f32) = (0.1, 10.0);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct CameraRequest {
+    /// 相机中心的世界坐标
+    pub(crate) position: Vec2,
+    /// 1.0 为原始大小，大于 1 放大（看到的范围变小）
+    pub(crate) zoom: f32,
+}
+
+/// 多组件同帧请求时，按 tick 顺序最后一个生效
+#[derive(Resource, Default)]
+pub(crate) struct PendingCamera {
+    pub(crate) request: Option<CameraRequest>,
+}
+
+/// 标记：setup 中创建的主相机，set-camera 只作用于它
+#[derive(Component)]
+pub(crate) struct MainCamera;
+
+/// 应用 set-camera 请求：平移相机并按 zoom 设置正交投影缩放
+pub(crate) fn process_camera(
+    mut pending: ResMut<PendingCamera>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+) {
+    let Some(CameraRequest { position, zoom }) = pending.request.take() else { return; };
+    let Ok((mut transform, mut projection)) = cameras.get_single_mut() else {
+        warn!("[相机] set-camera 失败：找不到主相机");
+        return;
+    };
+
+    let (min, max) = CAMERA_ZOOM_RANGE;
+    let clamped = zoom.clamp(min, max);
+    if clamped != zoom {
+        warn!("[相机] zoom={} 超出范围 [{}, {}]，已截断为 {}", zoom, min, max, clamped);
+    }
+    // z 保持不变，避免相机越过 2D 精灵所在的深度
+    transform.translation.x = position.x;
+    transform.translation.y = position.y;
+    projection.scale = 1.0 / clamped;
+}
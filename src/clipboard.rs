@@ -0,0 +1,60 @@
+// ─── 剪贴板 ───────────────────────────────────────────────────────────────────
+//
+// 部分平台要求在主线程访问剪贴板，因此 arboard::Clipboard 以 NonSend 资源持有。
+// 写入：wasm_tick 把 ClipboardCommand 转存到 PendingClipboard，由 process_clipboard_commands 执行。
+// 读取：refresh_clipboard_cache 在 wasm_tick 之前把系统剪贴板读入 ClipboardCache，
+// TS 的 clipboard-read 同步返回该快照。
+
+use super::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ClipboardCommand {
+    Write(String),
+}
+
+#[derive(Resource, Default)]
+pub(crate) struct PendingClipboard {
+    pub(crate) commands: Vec<ClipboardCommand>,
+}
+
+/// 最近一次读到的剪贴板文本；剪贴板为空、不是文本或不可用时为 None
+#[derive(Resource, Default, PartialEq)]
+pub(crate) struct ClipboardCache(pub(crate) Option<String>);
+
+/// 系统剪贴板句柄；初始化失败（如无图形环境）时为 None，读写都变为空操作
+pub(crate) struct ClipboardHandle(pub(crate) Option<arboard::Clipboard>);
+
+impl ClipboardHandle {
+    pub(crate) fn new() -> Self {
+        match arboard::Clipboard::new() {
+            Ok(clipboard) => ClipboardHandle(Some(clipboard)),
+            Err(e) => {
+                eprintln!("[剪贴板] 初始化失败，剪贴板功能不可用: {}", e);
+                ClipboardHandle(None)
+            }
+        }
+    }
+}
+
+/// 把系统剪贴板的文本读入 ClipboardCache，内容不变时不触发变更检测
+pub(crate) fn refresh_clipboard_cache(mut handle: NonSendMut<ClipboardHandle>, mut cache: ResMut<ClipboardCache>) {
+    let Some(clipboard) = handle.0.as_mut() else { return; };
+    let text = clipboard.get_text().ok();
+    cache.set_if_neq(ClipboardCache(text));
+}
+
+/// 执行 TS 请求的剪贴板写入
+pub(crate) fn process_clipboard_commands(
+    mut pending: ResMut<PendingClipboard>,
+    mut handle: NonSendMut<ClipboardHandle>,
+    mut cache: ResMut<ClipboardCache>,
+) {
+    for command in pending.commands.drain(..) {
+        let ClipboardCommand::Write(text) = command;
+        let Some(clipboard) = handle.0.as_mut() else { continue; };
+        match clipboard.set_text(text.clone()) {
+            Ok(()) => cache.0 = Some(text),
+            Err(e) => eprintln!("[剪贴板] 写入失败: {}", e),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+// ─── 调试绘制（feature = "debug-draw"）───────────────────────────────────────
+//
+// TS 通过 draw-debug-line / draw-debug-circle 在游戏世界中绘制调试图形
+// （路点、AI 视线、碰撞盒等）。Gizmos 为立即模式，TS 需要每帧重新提交。
+//
+// 未启用 feature 时 Host 函数为空实现，不分配、不注册任何 system；
+// WIT 接口保持不变，同一份 wasm 在两种构建下都能实例化。
+
+use bevy::prelude::*;
+
+/// 一条调试绘制命令，坐标为世界空间
+#[derive(Debug, Clone, PartialEq)]
+pub enum DebugDrawCommand {
+    Line { start: Vec2, end: Vec2, color: Color },
+    Circle { center: Vec2, radius: f32, color: Color },
+}
+
+/// wasm_tick 从各组件收集的本帧调试绘制命令
+#[derive(Resource, Default)]
+pub struct PendingDebugDraws {
+    pub commands: Vec<DebugDrawCommand>,
+}
+
+/// WIT 的 tuple<float32, float32, float32, float32> → Color
+pub fn debug_color((r, g, b, a): (f32, f32, f32, f32)) -> Color {
+    Color::rgba(r, g, b, a)
+}
+
+fn process_debug_draws(mut pending: ResMut<PendingDebugDraws>, mut gizmos: Gizmos) {
+    for cmd in pending.commands.drain(..) {
+        match cmd {
+            DebugDrawCommand::Line { start, end, color } => gizmos.line_2d(start, end, color),
+            DebugDrawCommand::Circle { center, radius, color } => {
+                gizmos.circle_2d(center, radius, color);
+            }
+        }
+    }
+}
+
+pub struct DebugDrawPlugin;
+
+impl Plugin for DebugDrawPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingDebugDraws>()
+            .add_systems(Update, process_debug_draws.after(super::WasmSchedule::WasmTick));
+    }
+}
@@ -0,0 +1,71 @@
+// ─── 固定步长 ─────────────────────────────────────────────────────────────────
+//
+// fixed_hz 为 Some 时 update-game 以固定 delta 调用：每帧累加真实耗时，够几步就调用几次（0..N），
+// 余量留到下一帧。其余回调（键盘映射、UI 事件等）仍每帧一次。
+// 渲染插值：逻辑位置停在最近一步，Transform 目标取最后两步之间按余量比例插值的位置，
+// 因此画面比逻辑最多落后一步，但移动平滑、不随帧率抖动。
+
+use super::*;
+
+/// 单帧最多补跑的步数，防止卡顿后一次性追赶过多（余量直接丢弃）
+pub(crate) const MAX_FIXED_STEPS_PER_FRAME: u32 = 5;
+
+/// wasm_tick 的更新频率配置；默认 None，即每帧以可变 delta 调用一次 update-game
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
+pub struct TickConfig {
+    pub fixed_hz: Option<f32>,
+}
+
+/// 固定步长模式下的累加器与插值端点（全部为世界坐标）
+#[derive(Resource, Default)]
+pub(crate) struct FixedTickState {
+    /// 尚未够一步的剩余时间（秒）
+    pub(crate) accumulator: f32,
+    /// 最后一步之前 / 之后的逻辑位置
+    pub(crate) previous: Vec2,
+    pub(crate) current: Vec2,
+    /// 上一帧交给物理引擎的插值目标
+    pub(crate) rendered: Vec2,
+}
+
+impl FixedTickState {
+    /// 累加本帧耗时，返回应调用 update-game 的次数
+    pub(crate) fn advance(&mut self, delta: f32, hz: f32) -> u32 {
+        let step = 1.0 / hz;
+        self.accumulator += delta;
+        let steps = (self.accumulator / step).floor() as u32;
+        if steps > MAX_FIXED_STEPS_PER_FRAME {
+            self.accumulator = 0.0;
+            return MAX_FIXED_STEPS_PER_FRAME;
+        }
+        self.accumulator -= steps as f32 * step;
+        steps
+    }
+
+    /// 物理引擎实际位置与上一帧插值目标之差即碰撞修正（或场景重置等外部移动），同步到逻辑位置
+    pub(crate) fn resync(&mut self, physical: Vec2) -> Vec2 {
+        let correction = physical - self.rendered;
+        self.previous += correction;
+        self.current += correction;
+        self.current
+    }
+
+    /// 瞬移：丢弃插值区间，避免下一帧 resync 把跳变当作碰撞修正
+    pub(crate) fn teleport(&mut self, position: Vec2) {
+        self.previous = position;
+        self.current = position;
+        self.rendered = position;
+    }
+
+    pub(crate) fn step_completed(&mut self, previous: Vec2, current: Vec2) {
+        self.previous = previous;
+        self.current = current;
+    }
+
+    /// 按剩余时间占一步的比例在最后两步之间插值，并记为本帧渲染目标
+    pub(crate) fn interpolated(&mut self, hz: f32) -> Vec2 {
+        let alpha = (self.accumulator * hz).clamp(0.0, 1.0);
+        self.rendered = self.previous.lerp(self.current, alpha);
+        self.rendered
+    }
+}
@@ -0,0 +1,126 @@
+// ─── 键盘焦点 ─────────────────────────────────────────────────────────────────
+//
+// TS 通过 set-focusable 标记可聚焦实体，navigate_focus 在 Tab / Shift+Tab 时
+// 按实体创建顺序循环切换焦点，给焦点实体加上 Outline 高亮，并发出 FocusChanged；
+// 下一帧 wasm_tick 据此调用 on-focus-changed。TS 也可用 set-focus 直接指定焦点，
+// 焦点 key 随 on-text-input 一并传给 Guest，供 TS 把键入的字符路由给对应控件。
+
+use super::*;
+
+/// 标记：参与 Tab 焦点切换
+#[derive(Component)]
+pub(crate) struct Focusable;
+
+/// 焦点实体的描边颜色
+#[derive(Resource, Clone, Copy, Debug)]
+pub(crate) struct FocusHighlightColor(pub(crate) Color);
+
+impl Default for FocusHighlightColor {
+    fn default() -> Self {
+        FocusHighlightColor(Color::rgb(1.0, 0.85, 0.2))
+    }
+}
+
+/// 当前焦点实体及其 key
+#[derive(Resource, Default)]
+pub(crate) struct FocusState {
+    pub(crate) focused: Option<(Entity, String)>,
+}
+
+/// 焦点变化事件：key 为全局 key，None 表示无焦点
+#[derive(Event, Debug, Clone, PartialEq)]
+pub(crate) struct FocusChanged {
+    pub(crate) old: Option<String>,
+    pub(crate) new: Option<String>,
+}
+
+/// Tab / Shift+Tab 在 Focusable 实体间循环切换焦点；焦点实体失去 Focusable 或被销毁时清除焦点
+pub(crate) fn navigate_focus(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focusables: Query<Entity, With<Focusable>>,
+    entity_map: Res<UiEntityMap>,
+    highlight: Res<FocusHighlightColor>,
+    mut state: ResMut<FocusState>,
+    mut focus_changed: EventWriter<FocusChanged>,
+) {
+    let mut candidates: Vec<Entity> = focusables.iter().collect();
+    candidates.sort();
+
+    let current = state.focused.as_ref().map(|(entity, _)| *entity);
+    // 焦点实体被销毁或失去 Focusable 时同样走到这里，新焦点为 None
+    let next = if keyboard_input.just_pressed(KeyCode::Tab) && !candidates.is_empty() {
+        let backward = keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        let position = current.and_then(|entity| candidates.iter().position(|&e| e == entity));
+        let index = match (position, backward) {
+            (Some(i), false) => (i + 1) % candidates.len(),
+            (Some(i), true) => (i + candidates.len() - 1) % candidates.len(),
+            (None, false) => 0,
+            (None, true) => candidates.len() - 1,
+        };
+        Some(candidates[index])
+    } else if current.is_some_and(|entity| !candidates.contains(&entity)) {
+        None
+    } else {
+        return;
+    };
+    if next == current {
+        return;
+    }
+    focus_changed.send(move_focus(&mut commands, &mut state, next, &entity_map, highlight.0));
+}
+
+/// 把焦点移到 next（None 为清除焦点），同步 Outline 高亮，返回待发出的 FocusChanged
+fn move_focus(
+    commands: &mut Commands,
+    state: &mut FocusState,
+    next: Option<Entity>,
+    entity_map: &UiEntityMap,
+    highlight: Color,
+) -> FocusChanged {
+    let old = state.focused.take();
+    if let Some((entity, _)) = &old {
+        if let Some(mut entity_commands) = commands.get_entity(*entity) {
+            entity_commands.remove::<Outline>();
+        }
+    }
+    // 没有 key 的实体（不是 TS 创建的）不参与焦点
+    state.focused = next.and_then(|entity| {
+        let key = entity_map.key_for(entity)?.to_string();
+        commands.entity(entity).insert(Outline::new(Val::Px(2.0), Val::Px(1.0), highlight));
+        Some((entity, key))
+    });
+
+    let event = FocusChanged {
+        old: old.map(|(_, key)| key),
+        new: state.focused.as_ref().map(|(_, key)| key.clone()),
+    };
+    println!("[UI] 焦点切换 {:?} → {:?}", event.old, event.new);
+    event
+}
+
+/// 应用 set-focus 请求：目标同时成为 Focusable（否则下一帧 navigate_focus 会清除焦点），空请求清除焦点
+pub(crate) fn apply_focus_request(
+    mut commands: Commands,
+    mut pending: ResMut<PendingFocusRequest>,
+    entity_map: Res<UiEntityMap>,
+    highlight: Res<FocusHighlightColor>,
+    mut state: ResMut<FocusState>,
+    mut focus_changed: EventWriter<FocusChanged>,
+) {
+    let Some(request) = pending.0.take() else { return; };
+    let next = match request {
+        Some(key) => match entity_map.get_or_warn("SetFocus", &key) {
+            Some(entity) => Some(entity),
+            None => return,
+        },
+        None => None,
+    };
+    if next == state.focused.as_ref().map(|(entity, _)| *entity) {
+        return;
+    }
+    if let Some(entity) = next {
+        commands.entity(entity).insert(Focusable);
+    }
+    focus_changed.send(move_focus(&mut commands, &mut state, next, &entity_map, highlight.0));
+}
@@ -0,0 +1,148 @@
+// ─── 游戏逻辑后端 ─────────────────────────────────────────────────────────────
+//
+// wasm_tick 每帧必须调用的三个入口抽象为 GameLogicBackend：WASM 组件实例是默认实现；
+// 开启 js-fallback feature 并以 `--js-fallback` 启动时，改用 QuickJsRuntime 直接执行
+// assets/game_logic.js，供无法运行 WASM 的环境使用。
+//
+// JS 后端只是回退路径：脚本以全局函数 processKeyboard / updateGame / onUiEvent 实现
+// 与 WIT 导出同名的三个入口，只能调用 bevyApi.log，不支持创建 UI 等其他 bevy-api。
+
+use super::*;
+
+pub(crate) trait GameLogicBackend {
+    /// 对应 WIT: process-keyboard
+    fn call_process_keyboard(&mut self, input: KeyboardInput) -> wasmtime::Result<KeyboardInput>;
+    /// 对应 WIT: update-game
+    fn call_update_game(&mut self, keyboard: KeyboardInput, state: PlayerState, delta: f32) -> wasmtime::Result<UpdateResult>;
+    /// 对应 WIT: on-ui-event
+    fn call_on_ui_event(&mut self, event_type: &str) -> wasmtime::Result<()>;
+}
+
+impl GameLogicBackend for ComponentInstance {
+    fn call_process_keyboard(&mut self, input: KeyboardInput) -> wasmtime::Result<KeyboardInput> {
+        self.game_world.interface0.call_process_keyboard(&mut self.store, input)
+    }
+
+    fn call_update_game(&mut self, keyboard: KeyboardInput, state: PlayerState, delta: f32) -> wasmtime::Result<UpdateResult> {
+        self.game_world.interface0.call_update_game(&mut self.store, keyboard, state, delta)
+    }
+
+    fn call_on_ui_event(&mut self, event_type: &str) -> wasmtime::Result<()> {
+        self.game_world.interface0.call_on_ui_event(&mut self.store, event_type)
+    }
+}
+
+/// 启动参数中带 `--js-fallback` 时改用 JS 后端
+pub fn js_fallback_requested() -> bool {
+    std::env::args().any(|arg| arg == "--js-fallback")
+}
+
+// ─── QuickJS 后端 ────────────────────────────────────────────────────────────
+
+#[cfg(feature = "js-fallback")]
+pub use quickjs::QuickJsRuntime;
+
+#[cfg(feature = "js-fallback")]
+mod quickjs {
+    use super::*;
+    use rquickjs::{Context, Ctx, Function, Object, Runtime};
+
+    /// JS 回退后端使用的默认脚本
+    pub const DEFAULT_JS_PATH: &str = "assets/game_logic.js";
+
+    /// Context 内部持有 Runtime 的引用，无需单独保存 Runtime
+    pub struct QuickJsRuntime {
+        context: Context,
+    }
+
+    impl QuickJsRuntime {
+        pub fn from_file(path: impl AsRef<Path>) -> wasmtime::Result<Self> {
+            let path = path.as_ref();
+            let source = std::fs::read_to_string(path)
+                .map_err(|e| wasmtime::Error::msg(format!("无法读取 {}: {}", path.display(), e)))?;
+            Self::from_source(&source)
+        }
+
+        pub fn from_default_file() -> wasmtime::Result<Self> {
+            Self::from_file(DEFAULT_JS_PATH)
+        }
+
+        /// 执行脚本并注册 bevyApi.log；脚本顶层抛出异常时返回错误
+        pub fn from_source(source: &str) -> wasmtime::Result<Self> {
+            let runtime = Runtime::new()?;
+            let context = Context::full(&runtime)?;
+            context.with(|ctx| -> wasmtime::Result<()> {
+                let api = Object::new(ctx.clone())?;
+                api.set("log", Function::new(ctx.clone(), |msg: String| debug!("[JS] {}", msg))?)?;
+                ctx.globals().set("bevyApi", api)?;
+                ctx.eval::<(), _>(source).map_err(|e| js_error(&ctx, e))
+            })?;
+            Ok(QuickJsRuntime { context })
+        }
+    }
+
+    /// JS 异常只在 Error::Exception 中留下标记，需从上下文取出异常对象才能得到消息
+    fn js_error(ctx: &Ctx<'_>, error: rquickjs::Error) -> wasmtime::Error {
+        if error.is_exception() {
+            let exception = ctx.catch();
+            let message = match exception.as_exception() {
+                Some(exception) => exception.message().unwrap_or_default(),
+                None => format!("{:?}", exception),
+            };
+            wasmtime::Error::msg(format!("JS 异常: {}", message))
+        } else {
+            wasmtime::Error::msg(error.to_string())
+        }
+    }
+
+    fn keyboard_object<'js>(ctx: &Ctx<'js>, input: KeyboardInput) -> rquickjs::Result<Object<'js>> {
+        let object = Object::new(ctx.clone())?;
+        object.set("right", input.right)?;
+        object.set("left", input.left)?;
+        object.set("up", input.up)?;
+        object.set("down", input.down)?;
+        Ok(object)
+    }
+
+    fn global_function<'js>(ctx: &Ctx<'js>, name: &str) -> wasmtime::Result<Function<'js>> {
+        ctx.globals()
+            .get::<_, Function>(name)
+            .map_err(|_| wasmtime::Error::msg(format!("脚本未定义全局函数 {}", name)))
+    }
+
+    impl GameLogicBackend for QuickJsRuntime {
+        fn call_process_keyboard(&mut self, input: KeyboardInput) -> wasmtime::Result<KeyboardInput> {
+            self.context.with(|ctx| {
+                let process = global_function(&ctx, "processKeyboard")?;
+                let mapped: Object = process.call((keyboard_object(&ctx, input)?,)).map_err(|e| js_error(&ctx, e))?;
+                Ok(KeyboardInput {
+                    right: mapped.get("right")?,
+                    left:  mapped.get("left")?,
+                    up:    mapped.get("up")?,
+                    down:  mapped.get("down")?,
+                })
+            })
+        }
+
+        fn call_update_game(&mut self, keyboard: KeyboardInput, state: PlayerState, delta: f32) -> wasmtime::Result<UpdateResult> {
+            self.context.with(|ctx| {
+                let update = global_function(&ctx, "updateGame")?;
+                let js_state = Object::new(ctx.clone())?;
+                js_state.set("x", state.x)?;
+                js_state.set("y", state.y)?;
+                js_state.set("speed", state.speed)?;
+                let result: Object = update
+                    .call((keyboard_object(&ctx, keyboard)?, js_state, delta))
+                    .map_err(|e| js_error(&ctx, e))?;
+                Ok(UpdateResult { x: result.get("x")?, y: result.get("y")? })
+            })
+        }
+
+        fn call_on_ui_event(&mut self, event_type: &str) -> wasmtime::Result<()> {
+            self.context.with(|ctx| {
+                let on_ui_event = global_function(&ctx, "onUiEvent")?;
+                on_ui_event.call::<_, ()>((event_type,)).map_err(|e| js_error(&ctx, e))
+            })
+        }
+    }
+}
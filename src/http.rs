@@ -0,0 +1,114 @@
+// ─── HTTP 请求 ────────────────────────────────────────────────────────────────
+//
+// http-request 只在 HostState 中登记请求并立即返回句柄；wasm_tick 把请求转存到 PendingHttpRequests，
+// start_http_requests 在 AsyncComputeTaskPool 上用 reqwest 的阻塞客户端发出请求，
+// poll_http_tasks 把完成的任务移入 HttpResponseMap.completed，下一次 wasm_tick 交给发起请求的组件，
+// TS 通过 poll-request 轮询结果。未启用 networking feature 时 start_http_requests 直接以错误完成请求，
+// 不会发出网络请求。
+
+use super::*;
+use bevy::tasks::Task;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// http-request 接受的方法（大写）
+pub(crate) const HTTP_METHODS: [&str; 6] = ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD"];
+
+/// 单个请求（含读取响应体）的超时
+#[cfg(feature = "networking")]
+const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct HttpRequest {
+    pub(crate) handle: u32,
+    pub(crate) method: String,
+    pub(crate) url:    String,
+    #[cfg_attr(not(feature = "networking"), allow(dead_code))]
+    pub(crate) body:   Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body:   Vec<u8>,
+}
+
+/// 连接失败、超时等没有拿到响应的情况为 Err；HTTP 4xx / 5xx 仍是 Ok，由 TS 检查 status
+pub type HttpResult = Result<HttpResponse, String>;
+
+static NEXT_REQUEST_HANDLE: AtomicU32 = AtomicU32::new(1);
+
+/// 句柄全局递增，多个组件的请求不会冲突
+pub(crate) fn next_request_handle() -> u32 {
+    NEXT_REQUEST_HANDLE.fetch_add(1, Ordering::Relaxed)
+}
+
+/// wasm_tick 转存的待发送请求，start_http_requests 消费
+#[derive(Resource, Default)]
+pub(crate) struct PendingHttpRequests(pub(crate) Vec<HttpRequest>);
+
+/// 进行中的请求任务，以及已完成、尚未交给组件的结果
+#[derive(Resource, Default)]
+pub struct HttpResponseMap {
+    #[cfg_attr(not(feature = "networking"), allow(dead_code))]
+    pub(crate) pending:   HashMap<u32, Task<HttpResult>>,
+    pub(crate) completed: Vec<(u32, HttpResult)>,
+}
+
+pub(crate) fn add_http(app: &mut App) {
+    app.init_resource::<PendingHttpRequests>()
+        .init_resource::<HttpResponseMap>()
+        .add_systems(Update, start_http_requests.after(WasmSchedule::WasmTick));
+
+    #[cfg(feature = "networking")]
+    app.add_systems(Update, poll_http_tasks.in_set(WasmSchedule::PreWasm));
+}
+
+pub(crate) fn start_http_requests(mut pending: ResMut<PendingHttpRequests>, mut map: ResMut<HttpResponseMap>) {
+    for request in pending.0.drain(..) {
+        let handle = request.handle;
+        println!("[HTTP] #{} {} {}", handle, request.method, request.url);
+        #[cfg(feature = "networking")]
+        {
+            let task = bevy::tasks::AsyncComputeTaskPool::get().spawn(async move { send_request(request) });
+            map.pending.insert(handle, task);
+        }
+        #[cfg(not(feature = "networking"))]
+        map.completed.push((handle, Err("宿主未启用 networking feature".to_string())));
+    }
+}
+
+#[cfg(feature = "networking")]
+fn send_request(request: HttpRequest) -> HttpResult {
+    // 阻塞客户端内部自带运行时线程，全局共用一个
+    static CLIENT: std::sync::OnceLock<reqwest::blocking::Client> = std::sync::OnceLock::new();
+    let client = CLIENT.get_or_init(|| {
+        reqwest::blocking::Client::builder().timeout(HTTP_TIMEOUT).build().expect("HTTP 客户端初始化失败")
+    });
+
+    let method = reqwest::Method::from_bytes(request.method.as_bytes()).map_err(|e| e.to_string())?;
+    let mut builder = client.request(method, &request.url);
+    if let Some(body) = request.body {
+        builder = builder.body(body);
+    }
+    let response = builder.send().map_err(|e| e.to_string())?;
+    let status = response.status().as_u16();
+    let body = response.bytes().map_err(|e| e.to_string())?.to_vec();
+    Ok(HttpResponse { status, body })
+}
+
+#[cfg(feature = "networking")]
+fn poll_http_tasks(mut map: ResMut<HttpResponseMap>) {
+    use bevy::tasks::{block_on, futures_lite::future};
+
+    let HttpResponseMap { pending, completed } = &mut *map;
+    pending.retain(|&handle, task| match block_on(future::poll_once(task)) {
+        Some(result) => {
+            if let Err(e) = &result {
+                eprintln!("[HTTP] #{} 失败: {}", handle, e);
+            }
+            completed.push((handle, result));
+            false
+        }
+        None => true,
+    });
+}
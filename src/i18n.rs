@@ -0,0 +1,161 @@
+// ─── 本地化 ───────────────────────────────────────────────────────────────────
+//
+// TS 调用 set-locale 后，wasm_tick 把请求转存到 PendingLocale，apply_locale_request 从
+// I18nConfig.dir（默认 assets/i18n/）读取 `<locale>.ftl`，不存在时读取 `<locale>.json`，
+// 成功后替换 I18nBundle 并发出 LocaleChanged；下一帧 wasm_tick 把新字符串拷贝进 HostState
+// 并以 on-ui-event("locale-changed:<locale>") 通知各组件刷新文字，translate 同步读取快照。
+// 文件缺失或无法解析时回退到 FALLBACK_LOCALE 并输出警告。
+//
+// .ftl 只支持 Fluent 的简单消息（`key = value`，缩进行续接为多行），占位符与选择器原样保留；
+// .json 为扁平的 `{ "key": "value" }` 对象。
+
+use super::*;
+
+/// 请求的语言无法加载时使用的语言
+pub const FALLBACK_LOCALE: &str = "en";
+
+/// locale 名的最大字节长度
+pub(crate) const MAX_LOCALE_LEN: usize = 32;
+
+#[derive(Resource, Clone, Debug)]
+pub struct I18nConfig {
+    /// 语言文件目录
+    pub dir: PathBuf,
+}
+
+impl Default for I18nConfig {
+    fn default() -> Self {
+        I18nConfig { dir: PathBuf::from("assets/i18n") }
+    }
+}
+
+/// 当前语言的全部字符串；locale 为空表示尚未加载任何语言
+#[derive(Resource, Default, Clone, Debug)]
+pub struct I18nBundle {
+    pub locale:  String,
+    pub strings: HashMap<String, String>,
+}
+
+impl I18nBundle {
+    /// 未收录的 key 原样返回，界面上能直接看出缺了哪条翻译
+    pub fn translate(&self, key: &str) -> String {
+        self.strings.get(key).cloned().unwrap_or_else(|| key.to_string())
+    }
+}
+
+/// 语言加载成功后发出，携带实际加载的语言（回退时为 FALLBACK_LOCALE）
+#[derive(Event, Clone, Debug, PartialEq)]
+pub struct LocaleChanged(pub String);
+
+/// 多组件同帧请求时，按 tick 顺序最后一个生效
+#[derive(Resource, Default)]
+pub(crate) struct PendingLocale(pub(crate) Option<String>);
+
+/// locale 用作文件名，只允许字母、数字、`-`、`_`，防止读取目录之外的文件
+pub(crate) fn validate_locale(locale: &str) -> Result<(), BevyApiError> {
+    let valid_char = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+    if (1..=MAX_LOCALE_LEN).contains(&locale.len()) && locale.chars().all(valid_char) {
+        Ok(())
+    } else {
+        Err(BevyApiError::InvalidLocale)
+    }
+}
+
+/// 依次尝试 `<locale>.ftl` 与 `<locale>.json`
+pub(crate) fn load_locale(dir: &Path, locale: &str) -> Result<HashMap<String, String>, String> {
+    let ftl = dir.join(format!("{}.ftl", locale));
+    if let Ok(source) = std::fs::read_to_string(&ftl) {
+        return Ok(parse_ftl(&source));
+    }
+    let json = dir.join(format!("{}.json", locale));
+    let source = std::fs::read_to_string(&json)
+        .map_err(|_| format!("找不到 {} 或 {}", ftl.display(), json.display()))?;
+    parse_json(&source).map_err(|e| format!("{} 解析失败: {}", json.display(), e))
+}
+
+pub(crate) fn parse_ftl(source: &str) -> HashMap<String, String> {
+    let mut strings = HashMap::new();
+    let mut current: Option<String> = None;
+    for line in source.lines() {
+        // 缩进行是上一条消息的续行
+        if line.starts_with([' ', '\t']) && !line.trim().is_empty() {
+            if let Some(value) = current.as_ref().and_then(|key| strings.get_mut(key)) {
+                if !value.is_empty() {
+                    value.push('\n');
+                }
+                value.push_str(line.trim());
+            }
+            continue;
+        }
+        current = None;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            let key = key.trim();
+            // `-term` 与 `.attribute` 不是可直接引用的消息
+            if key.is_empty() || key.starts_with(['-', '.']) {
+                continue;
+            }
+            strings.insert(key.to_string(), value.trim().to_string());
+            current = Some(key.to_string());
+        }
+    }
+    strings
+}
+
+pub(crate) fn parse_json(source: &str) -> Result<HashMap<String, String>, String> {
+    let value: serde_json::Value = serde_json::from_str(source).map_err(|e| e.to_string())?;
+    let serde_json::Value::Object(object) = value else {
+        return Err("顶层必须是对象".to_string());
+    };
+    Ok(object
+        .into_iter()
+        .filter_map(|(key, value)| match value {
+            serde_json::Value::String(text) => Some((key, text)),
+            other => {
+                warn!("[本地化] 忽略非字符串的条目 {} = {}", key, other);
+                None
+            }
+        })
+        .collect())
+}
+
+pub(crate) fn apply_locale_request(
+    mut pending: ResMut<PendingLocale>,
+    config: Res<I18nConfig>,
+    mut bundle: ResMut<I18nBundle>,
+    mut changed: EventWriter<LocaleChanged>,
+) {
+    let Some(locale) = pending.0.take() else { return; };
+    let loaded = match load_locale(&config.dir, &locale) {
+        Ok(strings) => Some((locale, strings)),
+        Err(e) if locale != FALLBACK_LOCALE => {
+            warn!("[本地化] 无法加载 {}（{}），回退到 {}", locale, e, FALLBACK_LOCALE);
+            match load_locale(&config.dir, FALLBACK_LOCALE) {
+                Ok(strings) => Some((FALLBACK_LOCALE.to_string(), strings)),
+                Err(e) => {
+                    warn!("[本地化] 回退语言也无法加载: {}", e);
+                    None
+                }
+            }
+        }
+        Err(e) => {
+            warn!("[本地化] 无法加载 {}: {}", locale, e);
+            None
+        }
+    };
+    let Some((locale, strings)) = loaded else { return; };
+    println!("[本地化] 切换到 {}，共 {} 条", locale, strings.len());
+    *bundle = I18nBundle { locale: locale.clone(), strings };
+    changed.send(LocaleChanged(locale));
+}
+
+pub(crate) fn add_i18n(app: &mut App) {
+    app.init_resource::<I18nConfig>()
+        .init_resource::<I18nBundle>()
+        .init_resource::<PendingLocale>()
+        .add_event::<LocaleChanged>()
+        .add_systems(Update, apply_locale_request.after(WasmSchedule::WasmTick));
+}
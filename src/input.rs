@@ -0,0 +1,305 @@
+// ─── 输入 ─────────────────────────────────────────────────────────────────────
+//
+// 鼠标点击、拖拽与文本输入的采集系统。点击与拖拽结果、本帧键入的文本都先放入队列或事件，
+// 由同一帧的 wasm_tick 转给 Guest；输入框的编辑则完全在宿主侧完成，TS 按需读取结果。
+
+use super::*;
+
+// ─── 点击 ─────────────────────────────────────────────────────────────────────
+
+/// 上一帧被点击（Interaction 变为 Pressed）的 UI key，wasm_tick 通知所属组件后清空
+#[derive(Resource, Default)]
+pub(crate) struct ClickEventQueue(pub(crate) Vec<String>);
+
+/// 收集本帧刚被按下的 UI 实体，反查 key 后放入 ClickEventQueue
+pub(crate) fn detect_ui_clicks(
+    interactions: Query<(Entity, &Interaction), Changed<Interaction>>,
+    entity_map: Res<UiEntityMap>,
+    mut clicks: ResMut<ClickEventQueue>,
+) {
+    for (entity, interaction) in interactions.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some(key) = entity_map.key_for(entity) else {
+            continue;
+        };
+        println!("[UI] 点击 key={}", key);
+        clicks.0.push(key.to_string());
+    }
+}
+
+// ─── 拖拽 ─────────────────────────────────────────────────────────────────────
+//
+// TS 通过 set-draggable 标记节点，handle_drag 在 PreWasm 中处理：在带 Draggable 的节点上按下左键开始拖拽，
+// 按住期间把光标位移（窗口坐标 y 轴向下，换算为向上）累加到 Style.left / bottom，松开左键结束。
+// 位置变化的帧发出 DragMoved，同一帧的 wasm_tick 以 on-ui-event("drag:<key>:<x>:<y>") 通知 key 所属的组件，
+// x/y 与 panel-config 相同（已减去 UI_ORIGIN），TS 可在回调中 set-position 吸附网格。
+// 只有绝对定位的节点可以拖拽，flex 子节点的位置由布局决定。
+
+/// 标记：可用鼠标拖动
+#[derive(Component)]
+pub(crate) struct Draggable;
+
+/// 正在拖拽的实体及上次处理时的光标位置（窗口坐标）
+#[derive(Resource, Default)]
+pub(crate) struct DragState {
+    pub(crate) active: Option<(Entity, Vec2)>,
+}
+
+/// 拖拽中位置变化，key 为全局 key，position 为新的左下角（panel-config 坐标）
+#[derive(Event, Debug, Clone, PartialEq)]
+pub(crate) struct DragMoved {
+    pub(crate) key: String,
+    pub(crate) position: Vec2,
+}
+
+pub(crate) fn handle_drag(
+    mouse: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    pressed: Query<(Entity, &Interaction), (With<Draggable>, Changed<Interaction>)>,
+    mut styles: Query<&mut Style, With<Draggable>>,
+    entity_map: Res<UiEntityMap>,
+    mut state: ResMut<DragState>,
+    mut moved: EventWriter<DragMoved>,
+) {
+    if !mouse.pressed(MouseButton::Left) {
+        state.active = None;
+        return;
+    }
+    let Some(cursor) = windows.get_single().ok().and_then(|window| window.cursor_position()) else { return; };
+    let Some((entity, last)) = state.active else {
+        let Some((entity, _)) = pressed.iter().find(|(_, interaction)| **interaction == Interaction::Pressed) else { return; };
+        match styles.get(entity) {
+            Ok(style) if style.position_type == PositionType::Absolute => state.active = Some((entity, cursor)),
+            _ => warn!("[UI] 拖拽失败：key={} 不是绝对定位的节点", entity_map.key_for(entity).unwrap_or("?")),
+        }
+        return;
+    };
+    // 拖拽中节点被销毁或取消了可拖拽
+    let Ok(mut style) = styles.get_mut(entity) else {
+        state.active = None;
+        return;
+    };
+    let delta = cursor - last;
+    if delta == Vec2::ZERO {
+        return;
+    }
+    state.active = Some((entity, cursor));
+    let (Val::Px(left), Val::Px(bottom)) = (style.left, style.bottom) else { return; };
+    let (left, bottom) = (left + delta.x, bottom - delta.y);
+    style.left = Val::Px(left);
+    style.bottom = Val::Px(bottom);
+    if let Some(key) = entity_map.key_for(entity) {
+        moved.send(DragMoved { key: key.to_string(), position: Vec2::new(left, bottom) - UI_ORIGIN });
+    }
+}
+
+// ─── 文本输入 ─────────────────────────────────────────────────────────────────
+//
+// collect_text_input 把 ReceivedCharacter 与输入法提交的文本汇总到 TextInputQueue，
+// wasm_tick 通过 on-text-input 连同焦点 key 一并转给每个组件。
+
+/// collect_text_input 汇总的本帧文本输入，wasm_tick 通过 on-text-input 转给每个组件后清空
+#[derive(Resource, Default, Debug, PartialEq)]
+pub(crate) struct TextInputQueue {
+    pub(crate) typed: String,
+    pub(crate) backspaces: u32,
+    pub(crate) enter: bool,
+}
+
+impl TextInputQueue {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.typed.is_empty() && self.backspaces == 0 && !self.enter
+    }
+}
+
+/// 把 ReceivedCharacter 与输入法提交的文本累加到 TextInputQueue。
+/// 退格 / 回车以控制字符形式到达，随系统按键重复，因此按字符而非 KeyCode 计数；
+/// 只按 char 拼接 String，多字节 UTF-8 不会被截断
+pub(crate) fn collect_text_input(
+    mut characters: EventReader<ReceivedCharacter>,
+    mut ime: EventReader<Ime>,
+    mut queue: ResMut<TextInputQueue>,
+) {
+    for event in characters.read() {
+        for ch in event.char.chars() {
+            match ch {
+                // macOS 的退格键产生 DEL
+                '\u{8}' | '\u{7f}' => queue.backspaces += 1,
+                '\r' | '\n' => queue.enter = true,
+                ch if ch.is_control() => {}
+                ch => queue.typed.push(ch),
+            }
+        }
+    }
+    for event in ime.read() {
+        if let Ime::Commit { value, .. } = event {
+            queue.typed.push_str(value);
+        }
+    }
+}
+
+// ─── 文本输入框 ───────────────────────────────────────────────────────────────
+//
+// 点击输入框获得输入焦点（与 Tab 焦点相互独立），process_input_field_typing 把 ReceivedCharacter
+// 写入唯一获得焦点的输入框。TS 通过 request-input-value 请求读取，process_ui_mutations 写入
+// InputValueReadback，下一帧 wasm_tick 拷贝给各组件。
+
+pub(crate) const INPUT_TEXT_COLOR: Color = Color::WHITE;
+pub(crate) const INPUT_PLACEHOLDER_COLOR: Color = Color::GRAY;
+
+/// 输入框状态；cursor_pos 按字符（而非字节）计数
+#[derive(Component, Debug, Default)]
+pub(crate) struct InputFieldState {
+    pub(crate) text: String,
+    pub(crate) cursor_pos: usize,
+    pub(crate) focused: bool,
+    pub(crate) placeholder: String,
+    pub(crate) max_length: usize,
+}
+
+impl InputFieldState {
+    pub(crate) fn insert(&mut self, ch: char) {
+        if self.text.chars().count() >= self.max_length {
+            return;
+        }
+        let byte = self.byte_index(self.cursor_pos);
+        self.text.insert(byte, ch);
+        self.cursor_pos += 1;
+    }
+
+    pub(crate) fn backspace(&mut self) {
+        if self.cursor_pos == 0 {
+            return;
+        }
+        self.cursor_pos -= 1;
+        let byte = self.byte_index(self.cursor_pos);
+        self.text.remove(byte);
+    }
+
+    pub(crate) fn move_cursor(&mut self, offset: isize) {
+        let len = self.text.chars().count();
+        self.cursor_pos = self.cursor_pos.saturating_add_signed(offset).min(len);
+    }
+
+    fn byte_index(&self, char_index: usize) -> usize {
+        self.text.char_indices().nth(char_index).map_or(self.text.len(), |(byte, _)| byte)
+    }
+
+    /// 显示内容：获得焦点时在光标处插入 `|`；未获得焦点且为空时显示灰色占位文字
+    pub(crate) fn display(&self) -> (String, Color) {
+        if self.focused {
+            let mut shown = self.text.clone();
+            shown.insert(self.byte_index(self.cursor_pos), '|');
+            (shown, INPUT_TEXT_COLOR)
+        } else if self.text.is_empty() {
+            (self.placeholder.clone(), INPUT_PLACEHOLDER_COLOR)
+        } else {
+            (self.text.clone(), INPUT_TEXT_COLOR)
+        }
+    }
+}
+
+/// request-input-value 的读取结果（全局 key → 文本）
+#[derive(Resource, Default)]
+pub(crate) struct InputValueReadback {
+    pub(crate) values: HashMap<String, String>,
+}
+
+/// process_ui_mutations 处理 GetInputValue 所需的参数
+#[derive(SystemParam)]
+pub(crate) struct InputFieldReads<'w, 's> {
+    pub(crate) fields:   Query<'w, 's, &'static InputFieldState>,
+    pub(crate) readback: ResMut<'w, InputValueReadback>,
+}
+
+/// 点击输入框获得输入焦点；点击其他 UI 或按 Esc 时失去焦点
+pub(crate) fn focus_input_fields(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    pressed: Query<(Entity, &Interaction), Changed<Interaction>>,
+    mut fields: Query<(Entity, &mut InputFieldState)>,
+) {
+    let clicked = pressed.iter().find(|(_, interaction)| **interaction == Interaction::Pressed).map(|(entity, _)| entity);
+    let target = match clicked {
+        Some(entity) => Some(entity).filter(|entity| fields.contains(*entity)),
+        None if keyboard_input.just_pressed(KeyCode::Escape) => None,
+        None => return,
+    };
+    for (entity, mut state) in fields.iter_mut() {
+        let focused = Some(entity) == target;
+        if state.focused != focused {
+            state.focused = focused;
+            state.cursor_pos = state.text.chars().count();
+        }
+    }
+}
+
+/// 把输入的字符写入获得焦点的输入框；退格与左右方向键按 KeyCode 处理
+pub(crate) fn process_input_field_typing(
+    mut characters: EventReader<ReceivedCharacter>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut fields: Query<&mut InputFieldState>,
+) {
+    let Some(mut state) = fields.iter_mut().find(|state| state.focused) else {
+        characters.clear();
+        return;
+    };
+    for event in characters.read() {
+        // 控制字符（退格、回车等）由下方的 KeyCode 分支处理或忽略
+        for ch in event.char.chars().filter(|ch| !ch.is_control()) {
+            state.insert(ch);
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::Backspace) {
+        state.backspace();
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
+        state.move_cursor(-1);
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowRight) {
+        state.move_cursor(1);
+    }
+}
+
+/// 输入框状态变化后刷新显示文字
+pub(crate) fn update_input_field_text(mut fields: Query<(&InputFieldState, &mut Text), Changed<InputFieldState>>) {
+    for (state, mut text) in fields.iter_mut() {
+        let (value, color) = state.display();
+        text.sections[0].value = value;
+        text.sections[0].style.color = color;
+    }
+}
+
+// ─── UI 事件按键绑定 ──────────────────────────────────────────────────────────
+//
+// wasm_tick 对每个 just_pressed 的绑定按键调用 on-ui-event(事件名)，
+// 集成方替换此 Resource 即可增删按键，无需修改事件循环。
+
+#[derive(Resource)]
+pub(crate) struct UiEventConfig {
+    /// (按键, 事件名)；按声明顺序触发，同一按键可绑定多个事件
+    pub(crate) bindings: Vec<(KeyCode, String)>,
+    /// 在控制台打印 UI 树快照的调试按键，不转发给 Guest；None 表示关闭
+    pub(crate) dump_key: Option<KeyCode>,
+}
+
+impl Default for UiEventConfig {
+    fn default() -> Self {
+        UiEventConfig {
+            bindings: vec![(KeyCode::KeyE, "toggle_panel".to_string())],
+            dump_key: Some(KeyCode::F3),
+        }
+    }
+}
+
+impl UiEventConfig {
+    /// 本帧刚按下的按键对应的事件名
+    pub(crate) fn triggered(&self, input: &ButtonInput<KeyCode>) -> Vec<String> {
+        self.bindings
+            .iter()
+            .filter(|(key, _)| input.just_pressed(*key))
+            .map(|(_, event)| event.clone())
+            .collect()
+    }
+}
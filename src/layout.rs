@@ -0,0 +1,256 @@
+// ─── UI 布局 ──────────────────────────────────────────────────────────────────
+//
+// UI 的几何信息：布局完成后的包围盒缓存、spawn 前的布局校验，以及汇总二者的 UI 树诊断快照，
+// 都由下一帧 wasm_tick 拷贝进 HostState。
+
+use super::*;
+
+// ─── UI 布局缓存 Resource ─────────────────────────────────────────────────────
+//
+// 由 update_ui_bounds_cache 在 PostUpdate（布局计算与 Transform 传播之后）写入，
+// 下一帧 wasm_tick 开始时拷贝进 HostState，因此 TS 读到的是最近一次完成的布局。
+// 坐标与 panel-config 一致：相对屏幕中心的偏移，y 轴向上，(x, y) 为左下角。
+
+#[derive(Resource, Default)]
+pub(crate) struct UiBoundsCache {
+    pub(crate) map: HashMap<String, Rect>,
+}
+
+/// 带 key 的精灵的世界坐标 AABB，与 UiBoundsCache 同在 PostUpdate 刷新、下一帧拷贝进 HostState。
+/// 只收录设置了 Sprite.custom_size 的精灵，尺寸会乘上 GlobalTransform 的缩放（忽略旋转）
+#[derive(Resource, Default)]
+pub(crate) struct SpriteBoundsCache {
+    pub(crate) map: HashMap<String, Rect>,
+}
+
+/// wasm_tick 读取的布局 / 碰撞快照
+#[derive(SystemParam)]
+pub(crate) struct BoundsSnapshots<'w> {
+    pub(crate) ui:      Res<'w, UiBoundsCache>,
+    pub(crate) sprites: Res<'w, SpriteBoundsCache>,
+}
+
+/// 读取 Bevy 计算出的 UI 布局（Node 尺寸 + GlobalTransform 中心点），
+/// 转换为 panel-config 坐标系后写入 UiBoundsCache
+pub(crate) fn update_ui_bounds_cache(
+    entity_map: Res<UiEntityMap>,
+    nodes: Query<(&Node, &GlobalTransform)>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut cache: ResMut<UiBoundsCache>,
+) {
+    let Ok(window) = windows.get_single() else { return; };
+    let window_height = window.height();
+
+    cache.map.clear();
+    for (key, &entity) in entity_map.map.iter() {
+        let Ok((node, transform)) = nodes.get(entity) else { continue; };
+        let size = node.size();
+        let center = transform.translation().truncate();
+        // UI 坐标原点在左上角、y 轴向下；转换为 left / bottom 再减去原点偏移
+        let left = center.x - size.x / 2.0;
+        let bottom = window_height - (center.y + size.y / 2.0);
+        let min = Vec2::new(left, bottom) - UI_ORIGIN;
+        cache.map.insert(key.clone(), Rect::from_corners(min, min + size));
+    }
+}
+
+/// 把带 key 的精灵的世界坐标 AABB 写入 SpriteBoundsCache
+pub(crate) fn update_sprite_bounds_cache(
+    entity_map: Res<UiEntityMap>,
+    sprites: Query<(&Sprite, &GlobalTransform)>,
+    mut cache: ResMut<SpriteBoundsCache>,
+) {
+    cache.map.clear();
+    for (key, &entity) in entity_map.map.iter() {
+        let Ok((sprite, transform)) = sprites.get(entity) else { continue; };
+        let Some(size) = sprite.custom_size else { continue; };
+        let (scale, _, translation) = transform.to_scale_rotation_translation();
+        let size = size * scale.truncate();
+        cache.map.insert(key.clone(), Rect::from_center_size(translation.truncate(), size));
+    }
+}
+
+// ─── 布局校验 ─────────────────────────────────────────────────────────────────
+//
+// process_ui_spawn 创建绝对定位的面板前，UiLayoutEngine 按 ScreenSize 检查其屏幕矩形；
+// 面板照常创建，问题记入 UiLayoutWarnings，wasm_tick 转给 key 所属的组件，
+// TS 通过 get-layout-warnings 轮询。flex 子节点的位置由布局决定，不做检查。
+
+/// spawn-panel 的坐标原点：x/y 加上此偏移即为 Style.left / Style.bottom
+pub(crate) const UI_ORIGIN: Vec2 = Vec2::new(400.0, 300.0);
+
+/// 主窗口的逻辑尺寸，update_screen_size 每帧同步；没有窗口时保持默认的 800×600
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct ScreenSize(pub Vec2);
+
+impl Default for ScreenSize {
+    fn default() -> Self {
+        ScreenSize(UI_ORIGIN * 2.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UiLayoutWarning {
+    /// 宽或高为 0，节点不可见
+    ZeroSize,
+    /// 左边缘或下边缘超出屏幕（Style.left / bottom 为负）
+    PositionNegative,
+    /// 右边缘或上边缘超出屏幕
+    PartiallyOffScreen,
+    /// 完全位于屏幕之外
+    OffScreen,
+}
+
+/// enabled 为 false 时跳过全部检查
+#[derive(Resource, Debug, Clone)]
+pub struct UiLayoutEngine {
+    pub enabled: bool,
+}
+
+impl Default for UiLayoutEngine {
+    fn default() -> Self {
+        UiLayoutEngine { enabled: true }
+    }
+}
+
+impl UiLayoutEngine {
+    /// 只检查无父节点的 SpawnPanel，其余命令返回空列表；OffScreen 时不再重复报告边缘越界
+    pub(crate) fn validate_spawn(&self, cmd: &UiSpawnCommand, screen: &ScreenSize) -> Vec<UiLayoutWarning> {
+        let UiSpawnCommand::SpawnPanel { parent_key: None, x, y, width, height, .. } = cmd else { return Vec::new(); };
+        if !self.enabled {
+            return Vec::new();
+        }
+        let mut warnings = Vec::new();
+        if *width <= 0.0 || *height <= 0.0 {
+            warnings.push(UiLayoutWarning::ZeroSize);
+        }
+        let min = Vec2::new(*x, *y) + UI_ORIGIN;
+        let max = min + Vec2::new(*width, *height);
+        if max.x <= 0.0 || max.y <= 0.0 || min.x >= screen.0.x || min.y >= screen.0.y {
+            warnings.push(UiLayoutWarning::OffScreen);
+            return warnings;
+        }
+        if min.x < 0.0 || min.y < 0.0 {
+            warnings.push(UiLayoutWarning::PositionNegative);
+        }
+        if max.x > screen.0.x || max.y > screen.0.y {
+            warnings.push(UiLayoutWarning::PartiallyOffScreen);
+        }
+        warnings
+    }
+}
+
+/// 尚未转交给组件的布局警告（全局 key），wasm_tick 转存后清空
+#[derive(Resource, Default, Debug)]
+pub struct UiLayoutWarnings(pub Vec<(String, UiLayoutWarning)>);
+
+/// process_ui_spawn 的布局校验参数
+#[derive(SystemParam)]
+pub(crate) struct LayoutChecks<'w> {
+    pub(crate) engine:   Res<'w, UiLayoutEngine>,
+    pub(crate) screen:   Res<'w, ScreenSize>,
+    pub(crate) warnings: ResMut<'w, UiLayoutWarnings>,
+}
+
+pub(crate) fn update_screen_size(windows: Query<&Window, With<PrimaryWindow>>, mut screen: ResMut<ScreenSize>) {
+    if let Ok(window) = windows.get_single() {
+        screen.set_if_neq(ScreenSize(Vec2::new(window.width(), window.height())));
+    }
+}
+
+// ─── UI 树诊断快照 ────────────────────────────────────────────────────────────
+//
+// update_ui_tree_dump 在 PostUpdate 紧跟两个包围盒缓存之后，把 UiEntityMap 中每个 key 的
+// 父节点、组件类型、布局与可见性序列化为 JSON 数组；下一帧拷贝进 HostState 供 dump-ui 读取，
+// 按 UiEventConfig.dump_key 也会在控制台打印同一份内容，用于排查"面板没有出现"一类问题。
+
+/// 最近一次 PostUpdate 的 UI 树 JSON；内容不变时不触发变更检测，wasm_tick 不会重复拷贝
+#[derive(Resource, Default, PartialEq)]
+pub(crate) struct UiTreeDump(pub(crate) String);
+
+/// UI 树快照中每个 key 的组件类型，按最具体的组件判定
+pub(crate) type UiDumpKinds = (
+    Has<ProgressBar>,
+    Has<InputFieldState>,
+    Has<Text>,
+    Has<UiImage>,
+    Has<RoundedCorners>,
+    Has<Node>,
+    Has<Sprite>,
+);
+
+/// 汇总 UiEntityMap 中每个 key 的父 key、组件类型、包围盒与可见性，按 key 排序后写入 UiTreeDump。
+/// 包围盒取自 UiBoundsCache / SpriteBoundsCache，坐标系与 query-ui-bounds / overlaps 一致
+pub(crate) fn update_ui_tree_dump(
+    entity_map: Res<UiEntityMap>,
+    kinds: Query<(UiDumpKinds, Option<&InheritedVisibility>)>,
+    ui_bounds: Res<UiBoundsCache>,
+    sprite_bounds: Res<SpriteBoundsCache>,
+    mut dump: ResMut<UiTreeDump>,
+) {
+    let mut keys: Vec<(&String, &Entity)> = entity_map.map.iter().collect();
+    keys.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut entries = Vec::with_capacity(keys.len());
+    for (key, &entity) in keys {
+        // 映射仍在但实体已不存在时标为 missing，便于发现未同步清理的 key
+        let (kind, visible) = match kinds.get(entity) {
+            Ok(((bar, input, text, image, rounded, node, sprite), visibility)) => {
+                let kind = match (bar, input, text, image && !rounded, node, sprite) {
+                    (true, ..) => "progress-bar",
+                    (_, true, ..) => "input-field",
+                    (_, _, true, ..) => "text",
+                    (_, _, _, true, ..) => "image",
+                    (_, _, _, _, true, _) => "node",
+                    (.., true) => "sprite",
+                    _ => "entity",
+                };
+                (kind, visibility.is_some_and(|v| v.get()))
+            }
+            Err(_) => ("missing", false),
+        };
+        let parent = match entity_map.parents.get(key) {
+            Some(parent) => json_string(parent),
+            None => "null".to_string(),
+        };
+        let bounds = match ui_bounds.map.get(key).or_else(|| sprite_bounds.map.get(key)) {
+            Some(rect) => format!(
+                "{{\"x\":{},\"y\":{},\"width\":{},\"height\":{}}}",
+                rect.min.x, rect.min.y, rect.width(), rect.height(),
+            ),
+            None => "null".to_string(),
+        };
+        entries.push(format!(
+            "{{\"key\":{},\"parent\":{},\"type\":\"{}\",\"bounds\":{},\"visible\":{}}}",
+            json_string(key), parent, kind, bounds, visible,
+        ));
+    }
+    dump.set_if_neq(UiTreeDump(format!("[{}]", entries.join(","))));
+}
+
+/// 按 UiEventConfig.dump_key 在控制台打印 UI 树快照（上一帧 PostUpdate 的结果）
+pub(crate) fn print_ui_tree_dump(keyboard_input: Res<ButtonInput<KeyCode>>, config: Res<UiEventConfig>, dump: Res<UiTreeDump>) {
+    let Some(key) = config.dump_key else { return; };
+    if keyboard_input.just_pressed(key) {
+        println!("[UI] UI 树快照: {}", dump.0);
+    }
+}
+
+/// 把字符串转为带引号的 JSON 字符串字面量
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if ch.is_control() => out.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
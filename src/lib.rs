@@ -0,0 +1,2499 @@
+// ─── 路径 B：TypeScript + WIT（WASM Component Model）────────────────────────
+//
+// 架构说明：
+//   1. wit/game.wit  → 接口契约（唯一真相来源）
+//   2. wasmtime::component::bindgen! 宏读取 WIT，在编译期自动生成：
+//        - 强类型结构体（KeyboardInput、UpdateResult、PlayerState、PanelConfig、TextConfig）
+//        - Bevy 需要实现的 Host trait（game::logic::bevy_api::Host）
+//        - Guest 调用句柄（通过 GameWorld.interface0.call_xxx）
+//   3. TypeScript 实现 game-logic 接口，jco componentize 编译为 WASM Component
+//   4. Bevy System 直接调用 Guest 的强类型方法，零手写桥接代码
+//
+// 模块划分：wasm_runtime（绑定、HostState、运行时构建）、loader / wasm_config / wasm_plugin（组件加载、
+// 组件列表与 WasmPlugin）、lifecycle / pool（提示、热重载、退出清理与实例池）、tick（wasm_tick 及其 SystemParam）、
+// ui_commands（UI 命令与消费系统）、ui_state / layout / tween / sprite_animation（UI 状态、布局、补间与精灵动画）、
+// input / focus / clipboard / window（输入、焦点、剪贴板与窗口系统）、其余按功能各成一个模块；
+// 本文件只保留模块声明、导出与 GameLogicPlugin / run() 的组装。
+
+use bevy::app::AppExit;
+use bevy::asset::LoadState;
+use bevy::ecs::query::Has;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::time::common_conditions::on_timer;
+use bevy::transform::TransformSystem;
+use bevy::ui::{TargetCamera, UiSystem};
+use bevy::window::{Ime, PrimaryWindow, ReceivedCharacter, WindowFocused, WindowResized};
+use bevy_xpbd_2d::prelude::{Collision, ExternalImpulse, Gravity, LinearVelocity, PhysicsPlugins, RigidBody};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use wasmtime::component::{Component, Linker};
+use wasmtime::{Config, Engine};
+
+mod audio;
+mod camera;
+mod clipboard;
+mod fixed_tick;
+mod focus;
+mod game_backend;
+mod http;
+mod i18n;
+mod input;
+mod layout;
+mod lifecycle;
+mod loader;
+mod metrics;
+mod permissions;
+mod physics;
+mod pool;
+mod resources;
+mod retry;
+mod rng;
+mod scene;
+mod shared_state;
+mod sprite_animation;
+mod storage;
+mod text;
+mod tick;
+mod tilemap;
+mod timers;
+mod tween;
+mod ui_commands;
+mod ui_state;
+mod wasm_config;
+mod wasm_plugin;
+mod wasm_runtime;
+mod window;
+
+pub use fixed_tick::*;
+pub use game_backend::*;
+pub use http::*;
+pub use i18n::*;
+pub use layout::*;
+pub use loader::*;
+pub use metrics::*;
+pub use permissions::*;
+pub use pool::*;
+pub use resources::*;
+pub use retry::*;
+pub use shared_state::*;
+pub use storage::*;
+pub use text::*;
+pub use tilemap::*;
+pub use timers::*;
+pub use ui_state::*;
+pub use wasm_config::*;
+pub use wasm_plugin::*;
+pub use wasm_runtime::*;
+use audio::*;
+use camera::*;
+use clipboard::*;
+use focus::*;
+use input::*;
+use lifecycle::*;
+use physics::*;
+use rng::*;
+use scene::*;
+use sprite_animation::*;
+use tick::*;
+use tween::*;
+use ui_commands::*;
+use window::*;
+
+#[cfg(feature = "replay")]
+mod replay;
+
+#[cfg(feature = "debug-draw")]
+mod debug_draw;
+
+#[cfg(feature = "ui-save")]
+mod ui_save;
+
+#[cfg(any(test, feature = "test-support"))]
+#[doc(hidden)]
+pub mod test_support;
+
+// ─── Bevy 入口 ────────────────────────────────────────────────────────────────
+
+/// 完整的游戏宿主：WASM 运行时与标准系统链（同 WasmPlugin）、玩家与相机、输入、UI、音频、窗口等全部系统。
+/// 不包含渲染与物理：宿主 App 需自行添加 DefaultPlugins 与 PhysicsPlugins（wasm_tick 读取其 Collision 事件），
+/// 因此可以嵌入其他 Bevy App。用法：
+/// `App::new().add_plugins((DefaultPlugins, PhysicsPlugins::default(), GameLogicPlugin::new(config))).run()`
+pub struct GameLogicPlugin {
+    /// 要加载的组件；build 前已通过 WasmPlugin::add_wasm_interface 注册的组件会保留，同名的以此为准
+    pub config: WasmConfig,
+    /// 每个组件每帧可消耗的 fuel，None 表示不计量
+    pub fuel_per_frame: Option<u64>,
+}
+
+impl Default for GameLogicPlugin {
+    fn default() -> Self {
+        GameLogicPlugin::new(WasmConfig::default())
+    }
+}
+
+impl GameLogicPlugin {
+    pub fn new(config: WasmConfig) -> Self {
+        GameLogicPlugin { config, fuel_per_frame: None }
+    }
+
+    pub fn with_fuel(mut self, per_frame: u64) -> Self {
+        self.fuel_per_frame = Some(per_frame);
+        self
+    }
+}
+
+impl Plugin for GameLogicPlugin {
+    fn build(&self, app: &mut App) {
+        match app.world.get_resource_mut::<WasmConfig>() {
+            Some(mut existing) => {
+                for interface in &self.config.components {
+                    existing.register(&interface.name, &interface.path, interface.priority);
+                }
+            }
+            None => { app.insert_resource(self.config.clone()); }
+        }
+        // WasmRuntime 是 NonSend 资源：build 在主线程上执行，insert_non_send_resource 直接写入 World
+        add_wasm_core(app, self.fuel_per_frame);
+
+        // 没有物理插件时 wasm_tick 仍需要 Collision 事件（始终为空）
+        app.add_event::<Collision>()
+            .init_resource::<GameTimestamp>()
+            .init_resource::<UiBoundsCache>()
+            .init_resource::<SpriteBoundsCache>()
+            .init_resource::<UiTreeDump>()
+            .init_resource::<PendingSceneTransitions>()
+            .init_resource::<PersistentUiKeys>()
+            .init_resource::<ActiveTweens>()
+            .init_resource::<UiErrorLog>()
+            .init_resource::<UiEventConfig>()
+            .init_resource::<PendingAudio>()
+            .init_resource::<MusicEntityMap>()
+            .init_resource::<PendingCamera>()
+            .init_resource::<FontLoadQueue>()
+            .init_resource::<ClickEventQueue>()
+            .init_resource::<PendingClipboard>()
+            .init_resource::<PendingWindowTitle>()
+            .init_resource::<GamePaused>()
+            .init_resource::<TextInputQueue>()
+            .init_resource::<WindowFocusQueue>()
+            .init_resource::<LocalStorageConfig>()
+            .init_resource::<StorageLimitConfig>()
+            .init_resource::<TickConfig>()
+            .init_resource::<FixedTickState>()
+            .init_resource::<PendingWindowIcon>()
+            .init_resource::<PendingPlayerTeleport>()
+            .init_resource::<PhysicsCommandQueue>()
+            .init_resource::<PendingFocusRequest>()
+            .init_resource::<ClipboardCache>()
+            .insert_non_send_resource(ClipboardHandle::new())
+            .init_resource::<DespawnedUiKeys>()
+            .init_resource::<FocusHighlightColor>()
+            .init_resource::<FocusState>()
+            .init_resource::<UiStackOrder>()
+            .init_resource::<InputValueReadback>()
+            .init_resource::<QueueLimits>()
+            .init_resource::<DroppedCommandCounts>()
+            .add_event::<FocusChanged>()
+            .init_resource::<DragState>()
+            .add_event::<DragMoved>()
+            .init_resource::<FontRegistry>()
+            .init_resource::<GuestMetrics>()
+            .add_event::<SceneLoaded>()
+            .add_event::<TweenFinished>()
+            .add_event::<AnimationFinished>()
+            .add_systems(Startup, setup)
+            .add_systems(Update, check_ui_image_loads)
+            .add_systems(Update, (
+                detect_ui_clicks,
+                navigate_focus,
+                handle_drag,
+                collect_text_input,
+                notify_wasm_window_focus,
+                (focus_input_fields, process_input_field_typing, update_input_field_text).chain(),
+                refresh_clipboard_cache,
+            ).in_set(WasmSchedule::PreWasm))
+            .add_systems(Update, (
+                process_clipboard_commands,
+                process_audio,
+                process_camera,
+                apply_window_title,
+                apply_window_icon,
+                apply_player_teleport,
+                process_physics_commands,
+            ).after(WasmSchedule::WasmTick))
+            .add_systems(Update, (start_pending_sprite_sheets, animate_sprites).chain().after(WasmSchedule::PostWasmMutate))
+            // 目标可能是本帧刚创建的节点，需等 Spawn 命令生效
+            .add_systems(Update, apply_focus_request.after(WasmSchedule::PostWasmMutate))
+            .add_systems(Update, record_guest_metrics.after(WasmSchedule::WasmTick).before(WasmSchedule::PostWasmSpawn))
+            .add_systems(Update, (toggle_metrics_overlay, update_metrics_overlay).chain().after(record_guest_metrics))
+            // 布局与 Transform 传播完成后刷新 UI 包围盒，供下一帧 wasm_tick 使用
+            .add_systems(PostUpdate, update_ui_bounds_cache
+                .after(UiSystem::Layout)
+                .after(TransformSystem::TransformPropagate))
+            .add_systems(PostUpdate, update_sprite_bounds_cache.after(TransformSystem::TransformPropagate))
+            .add_systems(PostUpdate, update_ui_tree_dump.after(update_ui_bounds_cache).after(update_sprite_bounds_cache))
+            .add_systems(Update, print_ui_tree_dump)
+            .add_systems(Update, (debug_game_state, log_guest_metrics).run_if(on_timer(Duration::from_secs(3))))
+            .add_systems(Last, shutdown_wasm_components);
+
+        // 录制 / 回放：由环境变量 GAME_REPLAY_RECORD / GAME_REPLAY_PLAY 控制
+        #[cfg(feature = "replay")]
+        app.add_plugins(replay::ReplayPlugin);
+
+        #[cfg(feature = "debug-draw")]
+        app.add_plugins(debug_draw::DebugDrawPlugin);
+
+        #[cfg(feature = "ui-save")]
+        app.add_plugins(ui_save::UiSavePlugin);
+    }
+}
+
+/// 构建并运行游戏 App（src/main.rs 只调用此函数）
+pub fn run() {
+    let mut app = App::new();
+    app.add_plugins((DefaultPlugins, PhysicsPlugins::default(), GameLogicPlugin::default()))
+        // 俯视角游戏，不需要重力
+        .insert_resource(Gravity(Vec2::ZERO));
+    if js_fallback_requested() {
+        use_js_fallback(&mut app);
+    }
+    app.run();
+}
+
+/// 改用 assets/game_logic.js 驱动游戏逻辑；脚本加载失败时保留 WASM 后端
+#[cfg(feature = "js-fallback")]
+pub fn use_js_fallback(app: &mut App) {
+    match QuickJsRuntime::from_default_file() {
+        Ok(runtime) => {
+            app.world.non_send_resource_mut::<WasmRuntime>().fallback = Some(Box::new(runtime));
+            println!("[JS] 已切换到 QuickJS 回退后端");
+        }
+        Err(e) => eprintln!("[JS] QuickJS 后端初始化失败，继续使用 WASM: {}", e),
+    }
+}
+
+#[cfg(not(feature = "js-fallback"))]
+pub fn use_js_fallback(_app: &mut App) {
+    eprintln!("[JS] --js-fallback 需要启用 js-fallback feature 编译，继续使用 WASM");
+}
+
+// ─── 无窗口测试 ───────────────────────────────────────────────────────────────
+//
+// 直接驱动 Store<HostState> + GameWorld，不启动 Bevy App，覆盖 Host trait 与 Guest 调用。
+// 夹具由 build.rs 从 tests/fixtures/game_logic.wat 生成（见 test_support）；跨模块的集成测试在 tests/ 下。
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{tick_app, ui_world};
+
+    #[test]
+    fn spawn_container_builds_flex_row() {
+        let mut host = HostState::new(None);
+        host.spawn_container(ContainerConfig {
+            key:             "inventory".to_string(),
+            parent_key:      None,
+            flex_direction:  FlexDir::Row,
+            gap:             4.0,
+            padding:         8.0,
+            align_items:     AlignMode::Center,
+            justify_content: JustifyMode::SpaceBetween,
+        }).unwrap();
+
+        let [UiSpawnCommand::SpawnContainer { flex_direction, gap, padding, align_items, justify_content, .. }] =
+            host.spawn_commands.as_slice() else { panic!("应入队一条 SpawnContainer"); };
+        let style = container_style(*flex_direction, *gap, *padding, *align_items, *justify_content);
+        assert_eq!(style.flex_direction, FlexDirection::Row);
+        assert_eq!(style.position_type, PositionType::Relative);
+        assert_eq!(style.justify_content, JustifyContent::SpaceBetween);
+    }
+
+    #[test]
+    fn set_z_index_orders_overlapping_panels() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = ui_world();
+        let hud = world.spawn(NodeBundle::default()).id();
+        let modal = world.spawn(NodeBundle::default()).id();
+
+        let mut entity_map = UiEntityMap::default();
+        entity_map.map.insert("hud".to_string(), hud);
+        entity_map.map.insert("modal".to_string(), modal);
+        world.insert_resource(entity_map);
+        world.insert_resource(PendingUiCommands {
+            spawns: Vec::new(),
+            mutations: vec![
+                UiMutationCommand::SetZIndex { key: "hud".to_string(), z: 1 },
+                UiMutationCommand::SetZIndex { key: "modal".to_string(), z: 10 },
+            ],
+        });
+
+        world.run_system_once(process_ui_mutations);
+
+        assert!(matches!(world.get::<ZIndex>(hud), Some(ZIndex::Global(1))));
+        assert!(matches!(world.get::<ZIndex>(modal), Some(ZIndex::Global(10))));
+    }
+
+    #[test]
+    fn negative_z_index_moves_root_panel_to_background_camera() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = ui_world();
+        let background = world.spawn_empty().id();
+        world.resource_mut::<UiStackOrder>().background_camera = Some(background);
+        let backdrop = world.spawn(NodeBundle::default()).id();
+        let label = world.spawn(NodeBundle::default()).id();
+        world.entity_mut(backdrop).add_child(label);
+
+        let mut entity_map = UiEntityMap::default();
+        entity_map.map.insert("backdrop".to_string(), backdrop);
+        entity_map.map.insert("backdrop.label".to_string(), label);
+        world.insert_resource(entity_map);
+        let mutations = |mutations: Vec<UiMutationCommand>| PendingUiCommands { spawns: Vec::new(), mutations };
+        world.insert_resource(mutations(vec![
+            UiMutationCommand::SetZIndex { key: "backdrop".to_string(), z: -5 },
+            UiMutationCommand::SetZIndex { key: "backdrop.label".to_string(), z: -1 },
+        ]));
+        world.run_system_once(process_ui_mutations);
+
+        // 根节点由背景相机绘制（位于游戏世界之下），子节点跟随根节点，不单独指定相机
+        assert_eq!(world.get::<TargetCamera>(backdrop).map(|target| target.0), Some(background));
+        assert!(world.get::<TargetCamera>(label).is_none());
+
+        // bring-to-front 把它带回主相机
+        world.insert_resource(mutations(vec![UiMutationCommand::BringToFront { key: "backdrop".to_string() }]));
+        world.run_system_once(process_ui_mutations);
+        assert!(world.get::<TargetCamera>(backdrop).is_none());
+        assert!(matches!(world.get::<ZIndex>(backdrop), Some(ZIndex::Global(1))));
+    }
+
+    #[test]
+    fn bordered_panel_sets_style_border_and_color() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = ui_world();
+        let border = |width: f32| Some(PanelBorder { width, color_r: 1.0, color_g: 0.8, color_b: 0.2, color_a: 1.0 });
+        let mut host = HostState::new(None);
+        host.spawn_panel(PanelConfig { border: border(3.0), ..panel("framed") }).unwrap();
+        // panel() 为 100×50，边框最多为短边的一半
+        host.spawn_panel(PanelConfig { border: border(80.0), ..panel("thick") }).unwrap();
+        host.spawn_panel(panel("flat")).unwrap();
+        assert_eq!(
+            api_error(host.spawn_panel(PanelConfig { border: border(-1.0), ..panel("bad") })),
+            BevyApiError::InvalidDimensions
+        );
+        world.insert_resource(PendingUiCommands { spawns: host.spawn_commands.drain(..).collect(), mutations: Vec::new() });
+        world.run_system_once(process_ui_spawn);
+
+        let node = |key: &str| {
+            let entity = world.resource::<UiEntityMap>().map[key];
+            (world.get::<Style>(entity).unwrap().border, world.get::<BorderColor>(entity).unwrap().0)
+        };
+        assert_eq!(node("framed"), (UiRect::all(Val::Px(3.0)), Color::rgba(1.0, 0.8, 0.2, 1.0)));
+        assert_eq!(node("thick").0, UiRect::all(Val::Px(25.0)));
+        assert_eq!(node("flat"), (UiRect::all(Val::Px(0.0)), Color::NONE));
+    }
+
+    #[test]
+    fn corner_radius_masks_panel_corners() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = ui_world();
+        let mut host = HostState::new(None);
+        host.spawn_panel(PanelConfig { corner_radius: Some(8.0), ..panel("card") }).unwrap();
+        host.spawn_panel(PanelConfig { corner_radius: Some(8.0), ..panel("card2") }).unwrap();
+        // panel() 为 100×50，半径最多为短边的一半
+        host.spawn_panel(PanelConfig { corner_radius: Some(100.0), ..panel("pill") }).unwrap();
+        host.spawn_panel(PanelConfig { corner_radius: Some(0.2), ..panel("almost_square") }).unwrap();
+        host.spawn_panel(panel("square")).unwrap();
+        for radius in [-1.0, f32::NAN] {
+            assert_eq!(
+                api_error(host.spawn_panel(PanelConfig { corner_radius: Some(radius), ..panel("bad") })),
+                BevyApiError::InvalidDimensions
+            );
+        }
+        world.insert_resource(PendingUiCommands { spawns: host.spawn_commands.drain(..).collect(), mutations: Vec::new() });
+        world.run_system_once(process_ui_spawn);
+
+        let mask = |key: &str| {
+            let entity = world.resource::<UiEntityMap>().map[key];
+            world.get::<UiImage>(entity).map(|image| image.texture.clone())
+        };
+        let alpha = |handle: &Handle<Image>, x: usize, y: usize| {
+            let image = world.resource::<Assets<Image>>().get(handle).unwrap();
+            image.data[(y * image.width() as usize + x) * 4 + 3]
+        };
+        let card = mask("card").expect("圆角面板应带遮罩");
+        assert_eq!(mask("card2").as_ref(), Some(&card), "相同尺寸与半径共用遮罩");
+        assert_eq!(world.resource::<Assets<Image>>().get(&card).unwrap().size(), UVec2::new(100, 50));
+        // 角落透明，圆角以内与边中点不透明
+        assert_eq!(alpha(&card, 0, 0), 0);
+        assert_eq!(alpha(&card, 8, 8), 255);
+        assert_eq!(alpha(&card, 50, 0), 255);
+        let pill = mask("pill").unwrap();
+        assert_eq!((alpha(&pill, 2, 2), alpha(&pill, 50, 25), alpha(&pill, 1, 25)), (0, 255, 255));
+        assert!(mask("almost_square").is_none() && mask("square").is_none());
+    }
+
+    #[test]
+    fn measure_text_grows_with_length_and_lines() {
+        let mut host = HostState::new(None);
+        let size = |host: &mut HostState, text: &str| {
+            let size = host.measure_text(text.to_string(), String::new(), 20.0).unwrap();
+            Vec2::new(size.x, size.y)
+        };
+        let short = size(&mut host, "OK");
+        let long = size(&mut host, "Cancel and return");
+        assert!(long.x > short.x);
+        assert_eq!(long.y, short.y);
+        // 多行：宽度取最宽的一行，高度按行数增加
+        let two_lines = size(&mut host, "OK\nCancel and return");
+        assert_eq!(two_lines.x, long.x);
+        assert!(two_lines.y > long.y);
+        assert!(host.measure_text("OK".to_string(), String::new(), -1.0).is_err());
+    }
+
+    #[test]
+    fn measure_text_caches_by_rounded_size_until_fonts_change() {
+        let mut host = HostState::new(None);
+        let first = host.measure_text("Start".to_string(), "title".to_string(), 20.2).unwrap();
+        let key = ("Start".to_string(), "title".to_string(), 20);
+        assert_eq!(host.text_measure_cache.get(&key).copied(), Some(Vec2::new(first.x, first.y)));
+        // 四舍五入到同一字号的调用命中缓存
+        let rounded = host.measure_text("Start".to_string(), "title".to_string(), 19.8).unwrap();
+        assert_eq!((rounded.x, rounded.y), (first.x, first.y));
+        assert_eq!(host.text_measure_cache.len(), 1);
+
+        // 字体加载后缓存失效，避免继续返回估算值
+        host.set_text_measurer(TextMeasurer::default());
+        assert!(host.text_measure_cache.is_empty());
+    }
+
+    #[test]
+    fn bring_to_front_exceeds_spawned_panels() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = ui_world();
+        let mut host = HostState::new(None);
+        for (key, z) in [("hud", Some(5)), ("modal", Some(20)), ("tooltip", None)] {
+            host.spawn_panel(PanelConfig { z_index: z, ..panel(key) }).unwrap();
+        }
+        host.bring_to_front("tooltip".to_string()).unwrap();
+        host.send_to_back("modal".to_string()).unwrap();
+        world.insert_resource(PendingUiCommands {
+            spawns: host.spawn_commands.drain(..).collect(),
+            mutations: host.mutation_commands.drain(..).collect(),
+        });
+
+        world.run_system_once(process_ui_spawn);
+        world.run_system_once(process_ui_mutations);
+
+        let z_of = |key: &str| {
+            let entity = world.resource::<UiEntityMap>().map[key];
+            match world.get::<ZIndex>(entity) {
+                Some(ZIndex::Global(z)) => *z,
+                other => panic!("{} 的层级不是 Global: {:?}", key, other),
+            }
+        };
+        assert!(z_of("tooltip") > 20);
+        assert!(z_of("modal") < 0);
+    }
+
+    #[test]
+    fn set_opacity_updates_alpha_per_component() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = ui_world();
+        let panel = world.spawn(NodeBundle {
+            background_color: Color::rgba(0.1, 0.1, 0.2, 0.9).into(),
+            ..default()
+        }).id();
+        let text = world.spawn(TextBundle::from_section("hp", TextStyle::default())).id();
+
+        let mut entity_map = UiEntityMap::default();
+        entity_map.map.insert("panel".to_string(), panel);
+        entity_map.map.insert("panel.hp".to_string(), text);
+        world.insert_resource(entity_map);
+        world.insert_resource(PendingUiCommands {
+            spawns: Vec::new(),
+            mutations: vec![
+                UiMutationCommand::SetOpacity { key: "panel".to_string(), alpha: 0.25 },
+                UiMutationCommand::SetOpacity { key: "panel.hp".to_string(), alpha: 0.5 },
+            ],
+        });
+
+        world.run_system_once(process_ui_mutations);
+
+        assert_eq!(world.get::<BackgroundColor>(panel).unwrap().0.a(), 0.25);
+        assert_eq!(world.get::<Text>(text).unwrap().sections[0].style.color.a(), 0.5);
+    }
+
+    #[test]
+    fn set_opacity_clamps_alpha() {
+        let mut host = HostState::new(None);
+        host.set_opacity("panel".to_string(), 1.5).unwrap();
+        assert!(matches!(
+            host.mutation_commands.as_slice(),
+            [UiMutationCommand::SetOpacity { alpha, .. }] if *alpha == 1.0
+        ));
+    }
+
+    fn api_error<T: std::fmt::Debug>(result: wasmtime::Result<T>) -> BevyApiError {
+        result.expect_err("应当校验失败").downcast::<BevyApiError>().expect("应为 BevyApiError")
+    }
+
+    #[test]
+    fn physics_commands_set_gravity_and_accumulate_impulses() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        let crate_body = world.spawn(RigidBody::Dynamic).id();
+        let label = world.spawn(TransformBundle::default()).id();
+        let mut entity_map = UiEntityMap::default();
+        entity_map.insert("crate".to_string(), crate_body, None);
+        entity_map.insert("label".to_string(), label, None);
+        world.insert_resource(entity_map);
+        world.insert_resource(Gravity(Vec2::ZERO));
+
+        let mut host = HostState::new(None);
+        host.set_gravity(0.0, -98.0).unwrap();
+        host.apply_impulse("crate".to_string(), 10.0, 0.0).unwrap();
+        host.apply_impulse("crate".to_string(), 0.0, 5.0).unwrap();
+        host.apply_impulse("label".to_string(), 1.0, 1.0).unwrap();
+        assert_eq!(api_error(host.set_gravity(f32::INFINITY, 0.0)), BevyApiError::InvalidDimensions);
+        world.insert_resource(PhysicsCommandQueue { commands: std::mem::take(&mut host.physics_commands) });
+        world.run_system_once(process_physics_commands);
+
+        assert_eq!(world.resource::<Gravity>().0, Vec2::new(0.0, -98.0));
+        let impulse = world.get::<ExternalImpulse>(crate_body).expect("刚体应收到冲量");
+        assert_eq!(impulse.impulse(), Vec2::new(10.0, 5.0));
+        assert!(!impulse.is_persistent());
+        assert!(world.get::<ExternalImpulse>(label).is_none(), "非刚体应忽略冲量");
+
+        // 没有物理插件（无 Gravity 资源）时只警告，不 panic
+        world.remove_resource::<Gravity>();
+        host.set_gravity(0.0, 1.0).unwrap();
+        world.resource_mut::<PhysicsCommandQueue>().commands = std::mem::take(&mut host.physics_commands);
+        world.run_system_once(process_physics_commands);
+        assert!(world.resource::<PhysicsCommandQueue>().commands.is_empty());
+    }
+
+    #[cfg(feature = "ui-save")]
+    #[test]
+    fn ui_entity_log_restores_surviving_ui() {
+        use bevy::ecs::system::RunSystemOnce;
+        use ui_save::{prune_ui_entity_log, record_ui_spawns, restore_ui_state, save_ui_state, UiEntityLog};
+
+        let ui_world_with_log = || {
+            let mut world = ui_world();
+            world.init_resource::<UiEntityLog>();
+            world
+        };
+        let mut world = ui_world_with_log();
+        let mut host = HostState::new(None);
+        host.spawn_panel(panel("hud")).unwrap();
+        host.spawn_text(TextConfig {
+            key: "score".to_string(), parent_key: "hud".to_string(), text: "0".to_string(),
+            font_size: 16.0, color_r: 1.0, color_g: 1.0, color_b: 1.0, font: None, font_path: None,
+        }).unwrap();
+        host.spawn_panel(panel("toast")).unwrap();
+        host.despawn("toast".to_string()).unwrap();
+        world.insert_resource(PendingUiCommands {
+            spawns: host.spawn_commands.drain(..).collect(),
+            mutations: host.mutation_commands.drain(..).collect(),
+        });
+        world.run_system_once(record_ui_spawns);
+        world.run_system_once(process_ui_spawn);
+        world.run_system_once(process_ui_mutations);
+        world.run_system_once(prune_ui_entity_log);
+
+        let saved = save_ui_state(world.resource::<UiEntityLog>());
+        assert!(restore_ui_state(b"not json").is_empty());
+
+        // 新会话：Entity 全部不同，只靠存档中的命令重建
+        let mut world = ui_world_with_log();
+        let restored = restore_ui_state(&saved);
+        assert_eq!(restored.iter().map(UiSpawnCommand::key).collect::<Vec<_>>(), ["hud", "score"]);
+        world.insert_resource(PendingUiCommands { spawns: restored, mutations: Vec::new() });
+        world.run_system_once(process_ui_spawn);
+
+        let map = world.resource::<UiEntityMap>();
+        assert!(!map.map.contains_key("toast"));
+        let (hud, score) = (map.map["hud"], map.map["score"]);
+        assert_eq!(world.get::<Parent>(score).map(Parent::get), Some(hud));
+    }
+
+    #[test]
+    fn set_parent_moves_text_between_panels() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = ui_world();
+        let mut host = HostState::new(None);
+        host.spawn_panel(panel("slot_a")).unwrap();
+        host.spawn_panel(panel("slot_b")).unwrap();
+        host.spawn_text(TextConfig {
+            key: "item".to_string(), parent_key: "slot_a".to_string(), text: "剑".to_string(),
+            font_size: 12.0, color_r: 1.0, color_g: 1.0, color_b: 1.0, font: None, font_path: None,
+        }).unwrap();
+        host.set_parent("item".to_string(), "slot_b".to_string()).unwrap();
+        // 挂到自身后代之下会形成环，应被忽略
+        host.set_parent("slot_b".to_string(), "item".to_string()).unwrap();
+        world.insert_resource(PendingUiCommands {
+            spawns: host.spawn_commands.drain(..).collect(),
+            mutations: host.mutation_commands.drain(..).collect(),
+        });
+        world.run_system_once(process_ui_spawn);
+        world.run_system_once(process_ui_mutations);
+
+        let entity_of = |key: &str| world.resource::<UiEntityMap>().map[key];
+        let (item, slot_b) = (entity_of("item"), entity_of("slot_b"));
+        assert_eq!(world.get::<Parent>(item).map(Parent::get), Some(slot_b));
+        assert!(world.get::<Parent>(slot_b).is_none());
+        let map = world.resource::<UiEntityMap>();
+        assert_eq!(map.parents["item"], "slot_b");
+        assert!(map.children.get("slot_a").map_or(true, Vec::is_empty));
+
+        // 空字符串移到根节点
+        host.set_parent("item".to_string(), String::new()).unwrap();
+        world.resource_mut::<PendingUiCommands>().mutations = host.mutation_commands.drain(..).collect();
+        world.run_system_once(process_ui_mutations);
+        assert!(world.get::<Parent>(item).is_none());
+        assert!(!world.resource::<UiEntityMap>().parents.contains_key("item"));
+    }
+
+    #[test]
+    fn spawn_command_is_traced_from_enqueue_to_consume() {
+        use bevy::ecs::system::RunSystemOnce;
+        use bevy::log::tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+        use bevy::log::tracing_subscriber::registry::{LookupSpan, Registry};
+        use bevy::utils::tracing::{self, field::Field, span, Event, Subscriber};
+        use std::fmt::{Debug, Write};
+        use std::sync::{Arc, Mutex};
+
+        /// 把 target=game 的事件记为 "<message> key=<key> in <所在 span>"
+        #[derive(Clone, Default)]
+        struct Capture(Arc<Mutex<Vec<String>>>);
+
+        impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Capture {
+            fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+                if event.metadata().target() != TRACE_TARGET {
+                    return;
+                }
+                let mut line = String::new();
+                let mut key = String::new();
+                event.record(&mut |field: &Field, value: &dyn Debug| match field.name() {
+                    "message" => { let _ = write!(line, "{:?}", value); }
+                    "key" => { let _ = write!(key, "{:?}", value); }
+                    _ => {}
+                });
+                let scope = ctx.event_span(event).map_or("-", |span| span.name());
+                self.0.lock().unwrap().push(format!("{} key={} in {}", line, key.trim_matches('"'), scope));
+            }
+
+            fn on_new_span(&self, attrs: &span::Attributes<'_>, _id: &span::Id, _ctx: Context<'_, S>) {
+                if attrs.metadata().target() == TRACE_TARGET {
+                    self.0.lock().unwrap().push(format!("span {}", attrs.metadata().name()));
+                }
+            }
+        }
+
+        let capture = Capture::default();
+        let subscriber = Registry::default().with(capture.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            let mut world = ui_world();
+            let mut host = HostState::new(None);
+            host.spawn_panel(panel("hud")).unwrap();
+            world.insert_resource(PendingUiCommands { spawns: host.spawn_commands.drain(..).collect(), mutations: Vec::new() });
+            world.run_system_once(process_ui_spawn);
+        });
+
+        let lines = capture.0.lock().unwrap().clone();
+        assert_eq!(
+            lines,
+            [
+                "spawn enqueued key=hud in -",
+                "span process_ui_spawn",
+                "spawn consumed key=hud in process_ui_spawn",
+            ],
+        );
+    }
+
+    fn panel(key: &str) -> PanelConfig {
+        PanelConfig {
+            key: key.to_string(), parent_key: None,
+            x: 0.0, y: 0.0, width: 100.0, height: 50.0,
+            color_r: 0.1, color_g: 0.2, color_b: 0.3, color_a: 1.0,
+            z_index: None, border: None, corner_radius: None,
+        }
+    }
+
+    #[test]
+    fn host_rejects_invalid_keys() {
+        let mut host = HostState::new(None);
+        assert_eq!(api_error(host.spawn_panel(panel(""))), BevyApiError::EmptyKey);
+        let long = "k".repeat(MAX_KEY_LEN + 1);
+        assert_eq!(api_error(host.despawn(long)), BevyApiError::KeyTooLong(MAX_KEY_LEN + 1));
+        assert_eq!(
+            api_error(host.reorder_child("row".to_string(), String::new(), 0)),
+            BevyApiError::EmptyKey
+        );
+        assert!(host.spawn_commands.is_empty() && host.mutation_commands.is_empty(), "校验失败不应入队");
+    }
+
+    #[test]
+    fn host_rejects_invalid_color_and_dimensions() {
+        let mut host = HostState::new(None);
+        let mut bad_color = panel("panel");
+        bad_color.color_g = 1.2;
+        assert_eq!(api_error(host.spawn_panel(bad_color)), BevyApiError::InvalidColor);
+
+        let mut bad_size = panel("panel");
+        bad_size.width = -1.0;
+        assert_eq!(api_error(host.spawn_panel(bad_size)), BevyApiError::InvalidDimensions);
+
+        assert_eq!(api_error(host.set_opacity("panel".to_string(), f32::NAN)), BevyApiError::InvalidColor);
+        assert_eq!(api_error(host.get_random_range(2.0, 1.0)), BevyApiError::InvalidRange);
+        assert!(host.spawn_commands.is_empty() && host.mutation_commands.is_empty());
+
+        host.spawn_panel(panel("panel")).expect("合法参数应当通过");
+        assert_eq!(host.spawn_commands.len(), 1);
+    }
+
+    #[test]
+    fn easing_curves_hit_endpoints() {
+        for easing in [Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut] {
+            assert_eq!(easing.apply(0.0), 0.0, "{:?}", easing);
+            assert_eq!(easing.apply(1.0), 1.0, "{:?}", easing);
+        }
+        assert!(Easing::EaseIn.apply(0.5) < 0.5 && Easing::EaseOut.apply(0.5) > 0.5);
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+    }
+
+    #[test]
+    fn tween_interpolates_and_reports_completion() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = ui_world();
+        let sprite = world.spawn((Sprite::default(), Transform::default())).id();
+        world.resource_mut::<ActiveTweens>().tweens.push(Tween {
+            key: "ship".to_string(), entity: sprite, property: TweenProperty::PositionX,
+            from: None, to: 100.0, duration_secs: 1.0, elapsed_secs: 0.0, easing: Easing::Linear,
+        });
+
+        world.resource_mut::<Time>().advance_by(Duration::from_millis(250));
+        world.run_system_once(update_tweens);
+        assert_eq!(world.get::<Transform>(sprite).unwrap().translation.x, 25.0);
+        assert!(world.resource::<Events<TweenFinished>>().is_empty());
+
+        world.resource_mut::<Time>().advance_by(Duration::from_secs(1));
+        world.run_system_once(update_tweens);
+        assert_eq!(world.get::<Transform>(sprite).unwrap().translation.x, 100.0);
+        assert!(world.resource::<ActiveTweens>().tweens.is_empty());
+        let events = world.resource::<Events<TweenFinished>>();
+        let keys: Vec<_> = events.get_reader().read(events).map(|e| e.0.as_str()).collect();
+        assert_eq!(keys, ["ship"]);
+    }
+
+    #[test]
+    fn local_key_strips_own_namespace_only() {
+        let host = HostState::new(Some("hud".to_string()));
+        assert_eq!(host.local_key("hud/bar"), Some("bar"));
+        assert_eq!(host.local_key("minimap/bar"), None);
+        assert_eq!(HostState::new(None).local_key("bar"), Some("bar"));
+    }
+
+    #[test]
+    fn input_field_edits_by_char_and_reads_back() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut state = InputFieldState { placeholder: "名字".to_string(), max_length: 4, ..default() };
+        assert_eq!(state.display(), ("名字".to_string(), INPUT_PLACEHOLDER_COLOR));
+        for ch in "勇者AB超出".chars() {
+            state.insert(ch);
+        }
+        assert_eq!(state.text, "勇者AB");
+        state.move_cursor(-2);
+        state.backspace();
+        state.focused = true;
+        assert_eq!(state.display(), ("勇|AB".to_string(), INPUT_TEXT_COLOR));
+
+        let mut world = ui_world();
+        let field = world.spawn(state).id();
+        let mut entity_map = UiEntityMap::default();
+        entity_map.insert("name".to_string(), field, None);
+        world.insert_resource(entity_map);
+        world.insert_resource(PendingUiCommands {
+            spawns: Vec::new(),
+            mutations: vec![UiMutationCommand::GetInputValue { key: "name".to_string() }],
+        });
+        world.run_system_once(process_ui_mutations);
+
+        assert_eq!(world.resource::<InputValueReadback>().values.get("name").map(String::as_str), Some("勇AB"));
+    }
+
+    #[test]
+    fn local_storage_round_trips_and_enforces_limits() {
+        let save_dir = std::env::temp_dir().join(format!("test_game_saves_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&save_dir);
+        let storage = LocalStorage {
+            config: LocalStorageConfig { save_dir: save_dir.clone() },
+            limits: StorageLimitConfig { max_bytes_per_slot: 8, max_slots: 2 },
+        };
+
+        assert_eq!(storage.load("slot1"), Ok(None));
+        assert_eq!(storage.save("slot1", b"hp=45"), Ok(Ok(())));
+        assert_eq!(storage.save("slot1", b"hp=40"), Ok(Ok(())));
+        assert_eq!(storage.load("slot1"), Ok(Some(b"hp=40".to_vec())));
+
+        assert_eq!(storage.save("slot1", b"123456789"), Err(BevyApiError::SlotTooLarge(9)));
+        assert_eq!(storage.save("../evil", b"x"), Err(BevyApiError::InvalidSlot));
+        assert_eq!(storage.save("slot2", b"x"), Ok(Ok(())));
+        assert_eq!(storage.save("slot3", b"x"), Err(BevyApiError::TooManySlots));
+        // 覆盖已有槽不受数量上限影响
+        assert_eq!(storage.save("slot2", b"y"), Ok(Ok(())));
+
+        let _ = std::fs::remove_dir_all(&save_dir);
+    }
+
+    #[test]
+    fn window_resize_notifies_once_per_integer_size() {
+        let window = Entity::from_raw(0);
+        let resize = |width: f32, height: f32| WindowResized { window, width, height };
+        let mut debounce = WindowSizeDebounce::default();
+
+        // 同一帧拖动产生的多个事件只通知最后的尺寸
+        let drag = [resize(800.0, 600.0), resize(1023.6, 767.8), resize(1024.2, 768.1)];
+        assert_eq!(debounce.update(&drag), Some(UVec2::new(1024, 768)));
+        // 取整后不变的后续事件不再通知
+        assert_eq!(debounce.update(&[resize(1023.9, 768.3)]), None);
+        assert_eq!(debounce.update(std::iter::empty()), None);
+        assert_eq!(debounce.update(&[resize(1280.0, 720.0)]), Some(UVec2::new(1280, 720)));
+    }
+
+    #[test]
+    fn window_focus_queue_keeps_primary_transitions() {
+        let mut app = App::new();
+        app.add_event::<WindowFocused>()
+            .init_resource::<WindowFocusQueue>()
+            .add_systems(Update, notify_wasm_window_focus);
+        let primary = app.world.spawn(PrimaryWindow).id();
+        let other = app.world.spawn_empty().id();
+
+        for (window, focused) in [(primary, false), (primary, false), (other, true), (primary, true)] {
+            app.world.send_event(WindowFocused { window, focused });
+        }
+        app.update();
+        assert_eq!(app.world.resource::<WindowFocusQueue>().0, [false, true]);
+    }
+
+    #[test]
+    fn text_input_collects_chars_per_frame() {
+        let window = Entity::from_raw(0);
+        let mut app = App::new();
+        app.add_event::<ReceivedCharacter>()
+            .add_event::<Ime>()
+            .init_resource::<TextInputQueue>()
+            .add_systems(Update, collect_text_input);
+        let mut type_chars = |app: &mut App, chars: &[&str]| {
+            for ch in chars {
+                app.world.send_event(ReceivedCharacter { window, char: (*ch).into() });
+            }
+            app.update();
+            // 模拟 wasm_tick 转给 Guest 后清空
+            std::mem::take(&mut *app.world.resource_mut::<TextInputQueue>())
+        };
+
+        assert_eq!(type_chars(&mut app, &["a"]).typed, "a");
+        assert_eq!(type_chars(&mut app, &["b"]).typed, "b");
+
+        let frame = type_chars(&mut app, &["é", "勇", "\u{8}", "\u{8}", "\r", "\t"]);
+        assert_eq!(frame, TextInputQueue { typed: "é勇".to_string(), backspaces: 2, enter: true });
+
+        app.world.send_event(Ime::Commit { window, value: "者".to_string() });
+        assert_eq!(type_chars(&mut app, &[]).typed, "者");
+        assert!(type_chars(&mut app, &[]).is_empty());
+    }
+
+    #[test]
+    fn entity_map_reverse_lookup_stays_in_sync() {
+        let mut entity_map = UiEntityMap::default();
+        let (a, b) = (Entity::from_raw(1), Entity::from_raw(2));
+        entity_map.insert("panel".to_string(), a, None);
+        entity_map.insert("panel.title".to_string(), b, Some("panel"));
+        assert_eq!(entity_map.key_for(b), Some("panel.title"));
+
+        // 同一 key 重新 spawn：旧实体的反查随之失效
+        entity_map.insert("panel".to_string(), Entity::from_raw(3), None);
+        assert_eq!(entity_map.key_for(a), None);
+
+        entity_map.remove_subtree("panel");
+        assert_eq!(entity_map.key_for(b), None);
+    }
+
+    #[test]
+    fn spawn_image_loads_requested_texture() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = ui_world();
+        world.insert_resource(PendingUiCommands {
+            spawns: vec![UiSpawnCommand::SpawnImage {
+                key: "logo".to_string(),
+                parent_key: None,
+                width: 64.0, height: 32.0,
+                texture_path: "icons/logo.png".to_string(),
+            }],
+            mutations: Vec::new(),
+        });
+
+        world.run_system_once(process_ui_spawn);
+
+        let entity = world.resource::<UiEntityMap>().map["logo"];
+        let image = world.get::<UiImage>(entity).expect("缺少 UiImage");
+        let requested = world.resource::<AssetServer>().get_handle::<Image>("icons/logo.png");
+        assert_eq!(Some(&image.texture), requested.as_ref());
+        assert!(world.get::<PendingUiImage>(entity).is_some());
+    }
+
+    #[test]
+    fn asset_ready_flips_after_texture_loads() {
+        use bevy::ecs::system::RunSystemOnce;
+        use bevy::render::texture::ImageLoader;
+
+        let dir = std::env::temp_dir().join(format!("test_game_asset_ready_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("icons")).unwrap();
+        image::RgbaImage::new(2, 2).save(dir.join("icons/dot.png")).unwrap();
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin { file_path: dir.to_string_lossy().into_owned(), ..default() }))
+            .init_asset::<Image>()
+            .init_asset_loader::<ImageLoader>()
+            .init_asset::<TextureAtlasLayout>()
+            .init_resource::<UiEntityMap>()
+            .init_resource::<PersistentUiKeys>()
+            .init_resource::<FontRegistry>()
+            .init_resource::<FontConfig>()
+            .init_resource::<UiAssetHandles>()
+            .init_resource::<UiLayoutEngine>()
+            .init_resource::<ScreenSize>()
+            .init_resource::<UiLayoutWarnings>()
+            .init_resource::<UiAssetReadiness>()
+            .init_resource::<UiStackOrder>()
+            .init_resource::<InputValueReadback>()
+            .add_systems(Update, update_asset_readiness)
+            .insert_resource(PendingUiCommands {
+                spawns: vec![UiSpawnCommand::SpawnImage {
+                    key: "dot".to_string(),
+                    parent_key: None,
+                    width: 8.0, height: 8.0,
+                    texture_path: "icons/dot.png".to_string(),
+                }],
+                mutations: Vec::new(),
+            });
+        app.world.run_system_once(process_ui_spawn);
+
+        let mut host = HostState::new(None);
+        assert!(!host.asset_ready("dot".to_string()).unwrap());
+        for _ in 0..200 {
+            app.update();
+            if !app.world.resource::<UiAssetReadiness>().0.is_empty() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        host.ready_assets = app.world.resource::<UiAssetReadiness>().0.clone();
+        assert!(host.asset_ready("dot".to_string()).unwrap());
+        assert!(!host.asset_ready("missing".to_string()).unwrap());
+
+        // 销毁后不再报告就绪
+        app.world.resource_mut::<UiEntityMap>().remove_subtree("dot");
+        app.update();
+        assert!(app.world.resource::<UiAssetReadiness>().0.is_empty());
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn set_progress_resizes_fill_to_fraction_of_width() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = ui_world();
+
+        let mut host = HostState::new(None);
+        host.spawn_progress_bar(ProgressBarConfig {
+            key: "hp".to_string(), parent_key: None,
+            x: 0.0, y: 0.0, width: 200.0, height: 16.0,
+            bg_r: 0.2, bg_g: 0.2, bg_b: 0.2, bg_a: 1.0,
+            fill_r: 0.8, fill_g: 0.1, fill_b: 0.1, fill_a: 1.0,
+            value: 1.0,
+        }).unwrap();
+        host.set_progress("hp".to_string(), 0.5).unwrap();
+        assert!(host.set_progress("hp".to_string(), f32::NAN).is_err());
+        world.insert_resource(PendingUiCommands {
+            spawns: host.spawn_commands.drain(..).collect(),
+            mutations: host.mutation_commands.drain(..).collect(),
+        });
+
+        world.run_system_once(process_ui_spawn);
+        world.run_system_once(process_ui_mutations);
+
+        let fill = world.resource::<UiEntityMap>().map["hp.fill"];
+        assert_eq!(world.get::<Style>(fill).unwrap().width, Val::Px(100.0));
+
+        // 超出范围的值钳制到 0~1
+        host.set_progress("hp".to_string(), 1.5).unwrap();
+        world.resource_mut::<PendingUiCommands>().mutations = host.mutation_commands.drain(..).collect();
+        world.run_system_once(process_ui_mutations);
+        assert_eq!(world.get::<Style>(fill).unwrap().width, Val::Px(200.0));
+    }
+
+    #[test]
+    fn spawn_text_uses_registered_font_alias() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = ui_world();
+        world.insert_resource(FontLoadQueue {
+            requests: vec![FontLoadRequest { path: "fonts/mono.ttf".to_string(), alias: "mono".to_string() }],
+        });
+        let panel = world.spawn(NodeBundle::default()).id();
+        world.resource_mut::<UiEntityMap>().insert("console".to_string(), panel, None);
+        let text = |key: &str, font: Option<&str>| UiSpawnCommand::SpawnText {
+            key: key.to_string(), parent_key: "console".to_string(), text: String::new(),
+            font_size: 14.0, color_r: 1.0, color_g: 1.0, color_b: 1.0,
+            font: font.map(str::to_string), font_path: None,
+        };
+        world.insert_resource(PendingUiCommands {
+            spawns: vec![text("console.code", Some("mono")), text("console.title", Some("fancy"))],
+            mutations: Vec::new(),
+        });
+
+        world.run_system_once(process_font_loads);
+        world.run_system_once(process_ui_spawn);
+
+        let font_of = |key: &str| {
+            let entity = world.resource::<UiEntityMap>().map[key];
+            world.get::<Text>(entity).unwrap().sections[0].style.font.clone()
+        };
+        let server = world.resource::<AssetServer>();
+        assert_eq!(Some(font_of("console.code")), server.get_handle::<Font>("fonts/mono.ttf"));
+        // 未注册的别名回退到默认字体
+        assert_eq!(Some(font_of("console.title")), server.get_handle::<Font>(DEFAULT_FONT));
+    }
+
+    #[test]
+    fn text_font_path_overrides_configured_default() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = ui_world();
+        world.insert_resource(FontConfig { default_font: "fonts/NotoSans.ttf".to_string() });
+        let panel = world.spawn(NodeBundle::default()).id();
+        world.resource_mut::<UiEntityMap>().insert("hud".to_string(), panel, None);
+        let text = |key: &str, font_path: Option<&str>| UiSpawnCommand::SpawnText {
+            key: key.to_string(), parent_key: "hud".to_string(), text: String::new(),
+            font_size: 14.0, color_r: 1.0, color_g: 1.0, color_b: 1.0,
+            font: Some("unregistered".to_string()), font_path: font_path.map(str::to_string),
+        };
+        world.insert_resource(PendingUiCommands {
+            spawns: vec![text("hud.score", Some("fonts/pixel.ttf")), text("hud.name", None)],
+            mutations: Vec::new(),
+        });
+
+        world.run_system_once(process_ui_spawn);
+
+        let font_of = |key: &str| {
+            let entity = world.resource::<UiEntityMap>().map[key];
+            world.get::<Text>(entity).unwrap().sections[0].style.font.clone()
+        };
+        let server = world.resource::<AssetServer>();
+        assert_eq!(Some(font_of("hud.score")), server.get_handle::<Font>("fonts/pixel.ttf"));
+        assert_eq!(Some(font_of("hud.name")), server.get_handle::<Font>("fonts/NotoSans.ttf"));
+        assert_eq!(server.get_handle::<Font>(DEFAULT_FONT), None);
+    }
+
+    #[test]
+    fn text_with_icons_groups_text_runs_around_icons() {
+        use bevy::ecs::system::RunSystemOnce;
+        use wasm_runtime::game::logic::bevy_api::{RichTextConfig, SegmentKind, TextSegment as WitTextSegment};
+
+        let segment = |kind, content: &str, size| WitTextSegment {
+            kind, content: content.to_string(), size, color_r: 1.0, color_g: 0.8, color_b: 0.0,
+        };
+        let config = RichTextConfig {
+            key: "gold".to_string(),
+            parent_key: None,
+            segments: vec![
+                segment(SegmentKind::Text, "金币", 16.0),
+                segment(SegmentKind::Text, " ×", 12.0),
+                segment(SegmentKind::Icon, "icons/coin.png", 14.0),
+                segment(SegmentKind::Text, "120", 16.0),
+            ],
+        };
+        let mut host = HostState::new(None);
+        host.permissions.remove(&PermissionType::ReadFile);
+        assert_eq!(
+            api_error(host.spawn_text_with_icons(config.clone())),
+            BevyApiError::PermissionDenied(PermissionType::ReadFile),
+        );
+        host.permissions.insert(PermissionType::ReadFile);
+        host.spawn_text_with_icons(config).unwrap();
+
+        let mut world = ui_world();
+        world.insert_resource(PendingUiCommands { spawns: host.spawn_commands.drain(..).collect(), mutations: Vec::new() });
+        world.run_system_once(process_ui_spawn);
+
+        let row = world.resource::<UiEntityMap>().map["gold"];
+        assert_eq!(world.get::<Style>(row).unwrap().flex_direction, FlexDirection::Row);
+        let children: Vec<Entity> = world.get::<Children>(row).unwrap().to_vec();
+        assert_eq!(children.len(), 3);
+        let sections = |entity| world.get::<Text>(entity).map(|text| {
+            text.sections.iter().map(|section| section.value.clone()).collect::<Vec<_>>()
+        });
+        assert_eq!(sections(children[0]), Some(vec!["金币".to_string(), " ×".to_string()]));
+        assert!(world.get::<UiImage>(children[1]).is_some());
+        assert_eq!(world.get::<Style>(children[1]).unwrap().width, Val::Px(14.0));
+        assert_eq!(sections(children[2]), Some(vec!["120".to_string()]));
+    }
+
+    #[test]
+    fn world_text_spawns_at_world_position_and_moves() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = ui_world();
+        let mut host = HostState::new(None);
+        host.spawn_world_text(WorldTextConfig {
+            key: "dmg-1".to_string(), x: 120.0, y: -40.0, text: "-15".to_string(),
+            font_size: 24.0, color_r: 1.0, color_g: 0.2, color_b: 0.2, font: None,
+        }).unwrap();
+        assert_eq!(api_error(host.set_position("dmg-1".to_string(), f32::NAN, 0.0)), BevyApiError::InvalidDimensions);
+        world.insert_resource(PendingUiCommands { spawns: host.spawn_commands.drain(..).collect(), mutations: Vec::new() });
+        world.run_system_once(process_ui_spawn);
+
+        let entity = world.resource::<UiEntityMap>().map["dmg-1"];
+        assert_eq!(world.get::<Text>(entity).unwrap().sections[0].value, "-15");
+        assert!(world.get::<Node>(entity).is_none(), "世界文字不应是 UI 节点");
+        assert_eq!(world.get::<Transform>(entity).unwrap().translation, Vec3::new(120.0, -40.0, 0.0));
+
+        host.set_position("dmg-1".to_string(), 120.0, -10.0).unwrap();
+        host.set_visible("dmg-1".to_string(), false).unwrap();
+        world.insert_resource(PendingUiCommands { spawns: Vec::new(), mutations: host.mutation_commands.drain(..).collect() });
+        world.run_system_once(process_ui_mutations);
+        assert_eq!(world.get::<Transform>(entity).unwrap().translation.y, -10.0);
+        assert_eq!(world.get::<Visibility>(entity), Some(&Visibility::Hidden));
+    }
+
+    #[test]
+    fn despawn_cleans_children_regardless_of_key_prefix() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = ui_world();
+        let inventory = world.spawn(NodeBundle::default()).id();
+        let slot = world.spawn(NodeBundle::default()).id();
+        let icon = world.spawn(ImageBundle::default()).id();
+        world.entity_mut(inventory).add_child(slot);
+        world.entity_mut(slot).add_child(icon);
+
+        let mut entity_map = UiEntityMap::default();
+        entity_map.insert("inventory".to_string(), inventory, None);
+        entity_map.insert("slot_0".to_string(), slot, Some("inventory"));
+        entity_map.insert("sword_icon".to_string(), icon, Some("slot_0"));
+        world.insert_resource(entity_map);
+        world.insert_resource(PendingUiCommands {
+            spawns: Vec::new(),
+            mutations: vec![UiMutationCommand::Despawn { key: "inventory".to_string() }],
+        });
+
+        world.run_system_once(process_ui_mutations);
+
+        let entity_map = world.resource::<UiEntityMap>();
+        let leftover: Vec<&String> = entity_map.map.iter().map(|(key, _)| key).collect();
+        assert!(leftover.is_empty(), "残留映射: {:?}", leftover);
+        assert!(entity_map.children.is_empty() && entity_map.parents.is_empty());
+        assert!(world.get_entity(icon).is_none());
+        // 子树中每个 key 都会通知 TS，父节点在前
+        let despawned = &world.resource::<DespawnedUiKeys>().0;
+        assert_eq!(despawned, &["inventory", "slot_0", "sword_icon"]);
+    }
+
+    #[test]
+    fn despawn_child_detaches_from_parent_index() {
+        let mut entity_map = UiEntityMap::default();
+        entity_map.insert("hud".to_string(), Entity::from_raw(0), None);
+        entity_map.insert("hp".to_string(), Entity::from_raw(1), Some("hud"));
+        entity_map.insert("mp".to_string(), Entity::from_raw(2), Some("hud"));
+
+        entity_map.remove_subtree("hp");
+
+        assert_eq!(entity_map.children["hud"], ["mp"]);
+        assert!(entity_map.map.contains_key("hud") && !entity_map.map.contains_key("hp"));
+    }
+
+    #[test]
+    fn batch_spawn_text_is_all_or_nothing() {
+        let text = |key: &str| TextConfig {
+            key: key.to_string(), parent_key: "bag".to_string(), text: key.to_string(),
+            font_size: 12.0, color_r: 1.0, color_g: 1.0, color_b: 1.0, font: None, font_path: None,
+        };
+        let mut host = HostState::new(Some("inv".to_string()));
+
+        assert!(host.batch_spawn_text(vec![text("bag.a"), text("")]).is_err());
+        assert!(host.spawn_commands.is_empty());
+
+        host.batch_spawn_text(vec![text("bag.a"), text("bag.b")]).unwrap();
+        assert_eq!(host.captured_text_keys("inv/bag"), ["inv/bag.a", "inv/bag.b"]);
+    }
+
+    #[test]
+    fn layout_engine_flags_panels_outside_the_screen() {
+        use UiLayoutWarning::*;
+
+        let engine = UiLayoutEngine::default();
+        let screen = ScreenSize(Vec2::new(800.0, 600.0));
+        let check = |config: PanelConfig| {
+            let command = HostState::new(None).panel_command(config, false).unwrap();
+            engine.validate_spawn(&command, &screen)
+        };
+        // panel() 为 100×50，坐标原点在窗口中心
+        assert!(check(panel("center")).is_empty());
+        assert_eq!(check(PanelConfig { x: -450.0, ..panel("left") }), [PositionNegative]);
+        assert_eq!(check(PanelConfig { x: 350.0, y: 280.0, ..panel("corner") }), [PartiallyOffScreen]);
+        assert_eq!(check(PanelConfig { x: 900.0, ..panel("gone") }), [OffScreen]);
+        assert_eq!(check(PanelConfig { width: 0.0, ..panel("empty") }), [ZeroSize]);
+        // flex 子节点由布局定位，不检查
+        assert!(check(PanelConfig { x: 900.0, parent_key: Some("hud".to_string()), ..panel("child") }).is_empty());
+
+        // 取出后清空；超出上限时丢弃最旧的
+        let mut host = HostState::new(None);
+        host.push_layout_warnings((0..MAX_LAYOUT_WARNINGS + 1).map(|i| (format!("w{}", i), OffScreen)).collect());
+        let warnings = host.get_layout_warnings().unwrap();
+        assert_eq!(warnings.len(), MAX_LAYOUT_WARNINGS);
+        assert_eq!(warnings[0].key, "w1");
+        assert!(matches!(warnings[0].kind, LayoutWarningKind::OffScreen));
+        assert!(host.get_layout_warnings().unwrap().is_empty());
+    }
+
+    #[test]
+    fn spawn_panels_matches_singular_calls() {
+        let grid: Vec<PanelConfig> = (0..100).map(|i| panel(&format!("grid.cell{}", i))).collect();
+        let mut singular = HostState::new(None);
+        for config in grid.clone() {
+            singular.spawn_panel(config).unwrap();
+        }
+        let mut batched = HostState::new(None);
+        batched.spawn_panels(grid.clone()).unwrap();
+        assert_eq!(format!("{:?}", batched.spawn_commands), format!("{:?}", singular.spawn_commands));
+
+        // 任一项非法时整批不入队
+        let mut host = HostState::new(None);
+        assert_eq!(api_error(host.spawn_panels(vec![panel("ok"), panel("")])), BevyApiError::EmptyKey);
+        assert!(host.spawn_commands.is_empty());
+        // 容量不足时整批丢弃
+        host.queue_limits = QueueLimits { max_pending_spawns: 50, max_pending_mutations: 50 };
+        host.spawn_panels(grid).unwrap();
+        assert!(host.spawn_commands.is_empty());
+        assert_eq!(host.dropped.spawns, 100);
+    }
+
+    #[test]
+    fn commands_beyond_queue_limit_are_dropped() {
+        let mut host = HostState::new(None);
+        host.queue_limits = QueueLimits { max_pending_spawns: 3, max_pending_mutations: 2 };
+
+        for i in 0..10 {
+            host.spawn_panel(panel(&format!("spam{}", i))).unwrap();
+            host.despawn(format!("spam{}", i)).unwrap();
+        }
+        assert_eq!(host.spawn_commands.len(), 3);
+        assert_eq!(host.mutation_commands.len(), 2);
+        assert_eq!(host.dropped, DroppedCommandCounts { spawns: 7, mutations: 8 });
+
+        // 剩余容量放不下整批时整批丢弃
+        let text = TextConfig {
+            key: "a".to_string(), parent_key: "spam0".to_string(), text: String::new(),
+            font_size: 12.0, color_r: 1.0, color_g: 1.0, color_b: 1.0, font: None, font_path: None,
+        };
+        host.spawn_commands.clear();
+        host.batch_spawn_text(vec![text.clone(), text.clone(), text.clone(), text]).unwrap();
+        assert!(host.spawn_commands.is_empty());
+        assert_eq!(host.dropped.spawns, 11);
+    }
+
+    #[test]
+    fn scene_transition_clears_ui_and_resets_state() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        let panel = world.spawn((NodeBundle::default(), WitUiPanel)).id();
+        let text = world.spawn(TextBundle::default()).id();
+        world.entity_mut(panel).add_child(text);
+
+        let mut entity_map = UiEntityMap::default();
+        entity_map.map.insert("main_panel".to_string(), panel);
+        entity_map.map.insert("main_panel.title".to_string(), text);
+        world.insert_resource(entity_map);
+        world.insert_resource(GameState { player_position: Vec2::new(50.0, -20.0), player_speed: 400.0, player_health: 30.0 });
+        world.insert_resource(PendingSceneTransitions {
+            transitions: vec![SceneTransitionCommand::Load("level2".to_string())],
+        });
+        world.init_resource::<Events<SceneLoaded>>();
+        world.init_resource::<PersistentUiKeys>();
+
+        world.run_system_once(handle_scene_transitions);
+
+        assert!(world.get_entity(panel).is_none());
+        assert!(world.get_entity(text).is_none());
+        assert!(world.resource::<UiEntityMap>().map.is_empty());
+        assert_eq!(world.resource::<GameState>().player_position, Vec2::ZERO);
+        assert_eq!(world.resource::<GameState>().player_speed, 200.0);
+        assert_eq!(world.resource::<GameState>().player_health, 100.0);
+
+        let events = world.resource::<Events<SceneLoaded>>();
+        let names: Vec<_> = events.get_reader().read(events).map(|e| e.0.clone()).collect();
+        assert_eq!(names, vec!["level2".to_string()]);
+    }
+
+    #[test]
+    fn scene_transition_keeps_persistent_panels() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        let hud = world.spawn((NodeBundle::default(), WitUiPanel)).id();
+        let hp = world.spawn(TextBundle::default()).id();
+        world.entity_mut(hud).add_child(hp);
+        let menu = world.spawn((NodeBundle::default(), WitUiPanel)).id();
+
+        let mut entity_map = UiEntityMap::default();
+        entity_map.insert("hud".to_string(), hud, None);
+        entity_map.insert("hud.hp".to_string(), hp, Some("hud"));
+        entity_map.insert("menu".to_string(), menu, None);
+        world.insert_resource(entity_map);
+        world.insert_resource(PersistentUiKeys(HashSet::from(["hud".to_string()])));
+        world.insert_resource(PendingSceneTransitions {
+            transitions: vec![SceneTransitionCommand::Load("level2".to_string())],
+        });
+        world.init_resource::<GameState>();
+        world.init_resource::<Events<SceneLoaded>>();
+
+        world.run_system_once(handle_scene_transitions);
+
+        assert!(world.get_entity(hud).is_some());
+        assert!(world.get_entity(hp).is_some());
+        assert!(world.get_entity(menu).is_none());
+        let entity_map = world.resource::<UiEntityMap>();
+        assert_eq!(entity_map.map.iter().count(), 2);
+        assert_eq!(entity_map.parents.get("hud.hp").map(String::as_str), Some("hud"));
+        assert!(!entity_map.map.contains_key("menu"));
+    }
+
+    #[test]
+    fn guest_metrics_track_window_and_pending_commands() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        world.init_resource::<GuestMetrics>();
+        let mut host = HostState::new(None);
+        host.spawn_panel(panel("hud")).unwrap();
+        host.despawn("old".to_string()).unwrap();
+        host.set_visible("hud".to_string(), false).unwrap();
+        world.insert_resource(PendingUiCommands {
+            spawns: host.spawn_commands.drain(..).collect(),
+            mutations: host.mutation_commands.drain(..).collect(),
+        });
+
+        // 模拟 METRICS_WINDOW + 10 次 tick，前 10 帧的大耗时应被滚出窗口
+        for frame in 0..(METRICS_WINDOW as u64 + 10) {
+            let us = if frame < 10 { 10_000 } else { 100 + frame as u32 % 2 * 100 };
+            world.insert_resource(WasmCallMetrics { keyboard_us: us, update_game_us: us, ui_event_us: 0, total_us: us * 3, frame });
+            world.run_system_once(record_guest_metrics);
+        }
+
+        let guest = world.resource::<GuestMetrics>();
+        assert_eq!(guest.window_len(), METRICS_WINDOW);
+        assert_eq!(guest.keyboard, CallStats { avg_us: 150.0, max_us: 200 });
+        assert_eq!(guest.ui_event, CallStats::default());
+        assert_eq!((guest.pending_spawns, guest.pending_mutations), (1, 2));
+        assert_eq!(micros(Duration::from_secs(10_000)), u32::MAX);
+    }
+
+    #[test]
+    fn wit_hash_mismatch_blames_stale_component() {
+        assert_eq!(WIT_HASH.len(), 64);
+        // 测试在源码树中运行，磁盘上的 game.wit 与编译时一致，过期的只能是组件
+        let mismatch = WitHashMismatch::new(&"0".repeat(64));
+        assert!(mismatch.0.contains("npm run build"), "{}", mismatch);
+        assert!(mismatch.0.contains(&WIT_HASH[..12]));
+    }
+
+    #[test]
+    fn component_manifest_rejects_newer_game_version() {
+        let meta = WasmComponentMeta::parse(
+            "wit_version = \"0.3.0\"\nauthor = \"ui-team\"\nmin_game_version = [0, 2, 0]\n",
+        ).unwrap();
+        assert_eq!(meta, WasmComponentMeta {
+            wit_version: "0.3.0".to_string(),
+            author: "ui-team".to_string(),
+            min_game_version: [0, 2, 0],
+        });
+
+        assert!(meta.check_compatibility("hud", GameVersion([0, 2, 0])).is_ok());
+        assert!(meta.check_compatibility("hud", GameVersion([1, 0, 0])).is_ok());
+        let message = meta.check_compatibility("hud", GameVersion([0, 1, 9])).unwrap_err();
+        assert!(message.contains(">= 0.2.0") && message.contains("当前为 0.1.9"), "{}", message);
+
+        assert!(WasmComponentMeta::parse("author = \"x\"\nmin_game_version = [0, 1, 0]").is_err());
+        assert!(WasmComponentMeta::parse("wit_version = \"1\"\nauthor = \"x\"\nmin_game_version = [0, -1, 0]").is_err());
+        assert!(WasmComponentMeta::parse("wit_version = \"1\"\nauthor = \"x\"\nmin_game_version = [1, 0]").is_err());
+    }
+
+    #[test]
+    fn overlaps_reads_sprite_bounds_snapshot() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        let mut entity_map = UiEntityMap::default();
+        for (key, x, y) in [("a", 0.0, 0.0), ("b", 30.0, 20.0), ("c", 200.0, 0.0), ("d", 200.0, 50.0)] {
+            let entity = world.spawn((
+                Sprite { custom_size: Some(Vec2::new(40.0, 40.0)), ..default() },
+                GlobalTransform::from_translation(Vec3::new(x, y, 0.0)),
+            )).id();
+            entity_map.insert(key.to_string(), entity, None);
+        }
+        world.insert_resource(entity_map);
+        world.init_resource::<SpriteBoundsCache>();
+
+        world.run_system_once(update_sprite_bounds_cache);
+
+        let mut host = HostState::new(None);
+        host.sprite_bounds = world.resource::<SpriteBoundsCache>().map.clone();
+        assert!(host.overlaps("a".to_string(), "b".to_string()).unwrap());
+        // c、d 的边缘恰好相接，不算重叠
+        assert!(!host.overlaps("c".to_string(), "d".to_string()).unwrap());
+        assert!(!host.overlaps("a".to_string(), "c".to_string()).unwrap());
+        assert!(!host.overlaps("a".to_string(), "missing".to_string()).unwrap());
+    }
+
+    #[test]
+    fn dump_ui_lists_spawned_panels() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        let mut entity_map = UiEntityMap::default();
+        let root = world.spawn(NodeBundle { inherited_visibility: InheritedVisibility::VISIBLE, ..default() }).id();
+        let child = world.spawn(NodeBundle::default()).id();
+        entity_map.insert("menu".to_string(), root, None);
+        entity_map.insert("menu/title".to_string(), child, Some("menu"));
+        world.insert_resource(entity_map);
+        let mut ui_bounds = UiBoundsCache::default();
+        ui_bounds.map.insert("menu".to_string(), Rect::new(-100.0, -50.0, 100.0, 50.0));
+        world.insert_resource(ui_bounds);
+        world.init_resource::<SpriteBoundsCache>();
+        world.init_resource::<UiTreeDump>();
+
+        world.run_system_once(update_ui_tree_dump);
+
+        let mut host = HostState::new(None);
+        host.ui_dump = world.resource::<UiTreeDump>().0.clone();
+        assert_eq!(
+            host.dump_ui().unwrap(),
+            concat!(
+                r#"[{"key":"menu","parent":null,"type":"node","bounds":{"x":-100,"y":-50,"width":200,"height":100},"visible":true},"#,
+                r#"{"key":"menu/title","parent":"menu","type":"node","bounds":null,"visible":false}]"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn tooltip_opens_towards_screen_center_and_follows_anchor_lifetime() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let window = Vec2::new(800.0, 600.0);
+        // 左上角的锚点：向右、向下展开
+        let style = tooltip_style(Rect::new(10.0, 10.0, 110.0, 40.0), window, 200.0);
+        assert_eq!((style.left, style.right), (Val::Px(10.0), Val::Auto));
+        assert_eq!((style.top, style.bottom), (Val::Px(40.0 + TOOLTIP_GAP), Val::Auto));
+        // 右下角的锚点：向左、向上展开
+        let style = tooltip_style(Rect::new(700.0, 550.0, 790.0, 590.0), window, 1000.0);
+        assert_eq!((style.left, style.right), (Val::Auto, Val::Px(10.0)));
+        assert_eq!((style.top, style.bottom), (Val::Auto, Val::Px(50.0 + TOOLTIP_GAP)));
+        assert_eq!(style.max_width, Val::Px(800.0));
+
+        let mut world = World::new();
+        let anchor = world.spawn_empty().id();
+        let tooltip = world.spawn(TooltipAnchor(anchor)).id();
+        let mut entity_map = UiEntityMap::default();
+        entity_map.insert("tip".to_string(), tooltip, None);
+        world.insert_resource(entity_map);
+        world.init_resource::<DespawnedUiKeys>();
+
+        world.run_system_once(despawn_orphaned_tooltips);
+        assert!(world.get_entity(tooltip).is_some());
+
+        world.despawn(anchor);
+        world.run_system_once(despawn_orphaned_tooltips);
+        assert!(world.get_entity(tooltip).is_none());
+        assert!(!world.resource::<UiEntityMap>().map.contains_key("tip"));
+        assert_eq!(world.resource::<DespawnedUiKeys>().0, vec!["tip".to_string()]);
+    }
+
+    #[test]
+    fn injected_system_sees_guest_commands_before_ui_spawn() {
+        #[derive(Resource, Default)]
+        struct Observed(Vec<usize>);
+
+        let mut app = App::new();
+        configure_wasm_schedule(&mut app);
+        app.init_resource::<PendingUiCommands>()
+            .init_resource::<Observed>()
+            // 注册顺序与执行顺序相反，只有 WasmSchedule 的排序能让断言成立
+            .add_systems(Update, (|mut pending: ResMut<PendingUiCommands>| pending.spawns.clear()).in_set(WasmSchedule::PostWasmSpawn))
+            .add_systems(Update, (|pending: Res<PendingUiCommands>, mut observed: ResMut<Observed>| {
+                observed.0.push(pending.spawns.len());
+            }).after(WasmSchedule::WasmTick).before(WasmSchedule::PostWasmSpawn))
+            .add_systems(Update, (|mut pending: ResMut<PendingUiCommands>| {
+                pending.spawns.push(UiSpawnCommand::SpawnTooltip {
+                    key: "tip".to_string(),
+                    anchor_key: "button".to_string(),
+                    text: "hint".to_string(),
+                    max_width: 100.0,
+                });
+            }).in_set(WasmSchedule::WasmTick));
+
+        app.update();
+        app.update();
+        assert_eq!(app.world.resource::<Observed>().0, vec![1, 1]);
+        assert!(app.world.resource::<PendingUiCommands>().spawns.is_empty());
+    }
+
+    #[test]
+    fn event_bus_gates_bevy_events_on_subscription() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut host = HostState::new(None);
+        host.post_event("net:response".to_string(), vec![1, 2, 3]).unwrap();
+        assert_eq!(api_error(host.post_event("bevy:scene-loaded".to_string(), Vec::new())), BevyApiError::ReservedEventType);
+        assert_eq!(host.posted_events, vec![("net:response".to_string(), vec![1, 2, 3])]);
+
+        let mut world = World::new();
+        world.init_resource::<Events<SceneLoaded>>();
+        world.init_resource::<WasmEventBus>();
+        world.send_event(SceneLoaded("dungeon".to_string()));
+        world.run_system_once(forward_bevy_events);
+        let bus = world.resource::<WasmEventBus>();
+        assert_eq!(bus.0, [("bevy:scene-loaded".to_string(), b"dungeon".to_vec())]);
+
+        assert!(host.wants_event("net:response"));
+        assert!(!host.wants_event("bevy:scene-loaded"));
+        host.subscribe_to_bevy_event("scene-loaded".to_string()).unwrap();
+        assert!(host.wants_event("bevy:scene-loaded"));
+        assert!(!host.wants_event("bevy:tween-finished"));
+    }
+
+    #[test]
+    fn nearest_returns_closest_tagged_sprite() {
+        let mut host = HostState::new(None);
+        for (key, x, y) in [("enemy", 0.0, 0.0), ("p1", 100.0, 0.0), ("p2", -30.0, 40.0), ("p3", 0.0, -60.0), ("rock", 5.0, 5.0)] {
+            host.sprite_bounds.insert(key.to_string(), Rect::from_center_size(Vec2::new(x, y), Vec2::splat(10.0)));
+        }
+        assert_eq!(host.nearest("enemy".to_string(), "player".to_string()).unwrap(), None);
+
+        for key in ["p1", "p2", "p3", "gone"] {
+            host.tag(key.to_string(), "player".to_string()).unwrap();
+        }
+        // rock 更近但没有 player 标签；gone 不在快照中
+        assert_eq!(host.nearest("enemy".to_string(), "player".to_string()).unwrap().as_deref(), Some("p2"));
+        assert_eq!(host.nearest("enemy".to_string(), "boss".to_string()).unwrap(), None);
+    }
+
+    #[test]
+    fn pressed_panels_are_queued_as_clicks() {
+        let mut world = World::new();
+        let button = world.spawn(Interaction::Pressed).id();
+        let hovered = world.spawn(Interaction::Hovered).id();
+        let mut entity_map = UiEntityMap::default();
+        entity_map.insert("menu/start".to_string(), button, None);
+        entity_map.insert("menu/quit".to_string(), hovered, None);
+        world.insert_resource(entity_map);
+        world.init_resource::<ClickEventQueue>();
+
+        // 注册为持久系统，Changed 过滤才能跨两次运行生效
+        let detect = world.register_system(detect_ui_clicks);
+        world.run_system(detect).unwrap();
+        assert_eq!(world.resource::<ClickEventQueue>().0, ["menu/start"]);
+
+        // 按住不放（Interaction 未变化）时不重复入队
+        world.resource_mut::<ClickEventQueue>().0.clear();
+        world.run_system(detect).unwrap();
+        assert!(world.resource::<ClickEventQueue>().0.is_empty());
+    }
+
+    #[test]
+    fn tab_cycles_focus_and_reports_changes() {
+        let mut world = World::new();
+        let mut entity_map = UiEntityMap::default();
+        let buttons: Vec<Entity> = ["ok", "cancel"].iter().map(|key| {
+            let entity = world.spawn((NodeBundle::default(), Focusable)).id();
+            entity_map.insert(key.to_string(), entity, None);
+            entity
+        }).collect();
+        world.insert_resource(entity_map);
+        world.init_resource::<FocusHighlightColor>();
+        world.init_resource::<FocusState>();
+        world.init_resource::<Events<FocusChanged>>();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        let navigate = world.register_system(navigate_focus);
+        let press_tab = |world: &mut World, shift: bool| {
+            let mut input = world.resource_mut::<ButtonInput<KeyCode>>();
+            input.reset_all();
+            if shift {
+                input.press(KeyCode::ShiftLeft);
+            }
+            input.press(KeyCode::Tab);
+            world.run_system(navigate).unwrap();
+        };
+
+        press_tab(&mut world, false);
+        press_tab(&mut world, false);
+        assert_eq!(world.resource::<FocusState>().focused.as_ref().map(|(e, _)| *e), Some(buttons[1]));
+        assert!(world.get::<Outline>(buttons[1]).is_some() && world.get::<Outline>(buttons[0]).is_none());
+
+        // Shift+Tab 反向切换
+        press_tab(&mut world, true);
+        assert_eq!(world.resource::<FocusState>().focused.as_ref().map(|(_, k)| k.as_str()), Some("ok"));
+
+        let events = world.resource::<Events<FocusChanged>>();
+        let changes: Vec<_> = events.get_reader().read(events).map(|e| (e.old.clone(), e.new.clone())).collect();
+        let key = |k: &str| Some(k.to_string());
+        assert_eq!(changes, [(None, key("ok")), (key("ok"), key("cancel")), (key("cancel"), key("ok"))]);
+    }
+
+    #[test]
+    fn despawning_focused_widget_clears_focus() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        // 未标记 Focusable 的输入框，set-focus 会自动补上
+        let name_input = world.spawn(NodeBundle::default()).id();
+        let mut entity_map = UiEntityMap::default();
+        entity_map.insert("chat/name".to_string(), name_input, None);
+        world.insert_resource(entity_map);
+        world.init_resource::<FocusHighlightColor>();
+        world.init_resource::<FocusState>();
+        world.init_resource::<Events<FocusChanged>>();
+        world.init_resource::<ButtonInput<KeyCode>>();
+
+        let mut host = HostState::new(Some("chat".to_string()));
+        host.set_focus("name".to_string()).unwrap();
+        world.insert_resource(PendingFocusRequest(host.focus_request.take()));
+        world.run_system_once(apply_focus_request);
+        world.run_system_once(navigate_focus);
+        assert_eq!(world.resource::<FocusState>().focused, Some((name_input, "chat/name".to_string())));
+        assert!(world.get::<Focusable>(name_input).is_some());
+
+        world.entity_mut(name_input).despawn_recursive();
+        world.resource_mut::<UiEntityMap>().remove_subtree("chat/name");
+        world.run_system_once(navigate_focus);
+        assert!(world.resource::<FocusState>().focused.is_none());
+        let events = world.resource::<Events<FocusChanged>>();
+        let last = events.get_reader().read(events).last().cloned();
+        assert_eq!(last, Some(FocusChanged { old: Some("chat/name".to_string()), new: None }));
+
+        // 空字符串表示清除焦点
+        host.set_focus(String::new()).unwrap();
+        assert_eq!(host.focus_request, Some(None));
+    }
+
+    #[test]
+    fn set_text_style_only_changes_given_fields() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = ui_world();
+        let score = world.spawn(TextBundle::from_section(
+            "0",
+            TextStyle { font_size: 20.0, color: Color::rgba(0.2, 0.4, 0.6, 0.8), ..default() },
+        )).id();
+        let mut entity_map = UiEntityMap::default();
+        entity_map.insert("score".to_string(), score, None);
+        world.insert_resource(entity_map);
+
+        let mut host = HostState::new(None);
+        host.set_text_style("score".to_string(), Some(32.0), None, None, None).unwrap();
+        assert!(host.set_text_style("score".to_string(), None, Some(1.5), None, None).is_err());
+        world.insert_resource(PendingUiCommands { spawns: Vec::new(), mutations: host.mutation_commands.drain(..).collect() });
+
+        world.run_system_once(process_ui_mutations);
+
+        let style = &world.get::<Text>(score).unwrap().sections[0].style;
+        assert_eq!(style.font_size, 32.0);
+        assert_eq!(style.color, Color::rgba(0.2, 0.4, 0.6, 0.8));
+    }
+
+    #[test]
+    fn set_font_swaps_registered_alias_only() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = ui_world();
+        let label = world.spawn(TextBundle::from_sections([
+            TextSection::new("HP ", TextStyle { font_size: 20.0, ..default() }),
+            TextSection::new("100", TextStyle { font_size: 24.0, ..default() }),
+        ])).id();
+        let mut entity_map = UiEntityMap::default();
+        entity_map.insert("label".to_string(), label, None);
+        world.insert_resource(entity_map);
+        let cjk: Handle<Font> = Handle::weak_from_u128(0x5eed);
+        let mut fonts = FontRegistry::default();
+        fonts.map.insert("cjk".to_string(), cjk.clone());
+        world.insert_resource(fonts);
+
+        let mut host = HostState::new(None);
+        host.set_font("label".to_string(), "missing".to_string(), Some(40.0)).unwrap();
+        world.insert_resource(PendingUiCommands { spawns: Vec::new(), mutations: host.mutation_commands.drain(..).collect() });
+        world.run_system_once(process_ui_mutations);
+        let sections = &world.get::<Text>(label).unwrap().sections;
+        assert!(sections.iter().all(|s| s.style.font == Handle::default()));
+        assert_eq!(sections[0].style.font_size, 20.0);
+
+        host.set_font("label".to_string(), "cjk".to_string(), None).unwrap();
+        world.insert_resource(PendingUiCommands { spawns: Vec::new(), mutations: host.mutation_commands.drain(..).collect() });
+        world.run_system_once(process_ui_mutations);
+        let sections = &world.get::<Text>(label).unwrap().sections;
+        assert!(sections.iter().all(|s| s.style.font == cjk));
+        assert_eq!((sections[0].style.font_size, sections[1].style.font_size), (20.0, 24.0));
+    }
+
+    #[test]
+    fn sensitive_host_calls_require_permission() {
+        let mut host = HostState::new(None);
+        assert_eq!(host.request_permission(WitPermissionType::Network).unwrap(), PermissionResult::Denied);
+        assert_eq!(host.request_permission(WitPermissionType::ReadFile).unwrap(), PermissionResult::Granted);
+
+        host.permissions.remove(&PermissionType::ReadFile);
+        assert_eq!(
+            api_error(host.load_font("fonts/cjk.ttf".to_string(), "cjk".to_string())),
+            BevyApiError::PermissionDenied(PermissionType::ReadFile)
+        );
+        assert!(host.font_loads.is_empty());
+
+        host.permissions.remove(&PermissionType::WriteFile);
+        assert_eq!(
+            api_error(host.save_to_local_storage("slot1".to_string(), vec![1])),
+            BevyApiError::PermissionDenied(PermissionType::WriteFile)
+        );
+    }
+
+    #[test]
+    fn http_request_is_delivered_only_to_its_component() {
+        let mut host = HostState::new(Some("ui".to_string()));
+        let mut other = HostState::new(Some("ai".to_string()));
+        assert_eq!(
+            api_error(host.http_request("GET".to_string(), "https://example.com".to_string(), None)),
+            BevyApiError::PermissionDenied(PermissionType::Network)
+        );
+
+        host.permissions.insert(PermissionType::Network);
+        assert_eq!(api_error(host.http_request("CONNECT".to_string(), "https://example.com".to_string(), None)), BevyApiError::InvalidHttpRequest);
+        assert_eq!(api_error(host.http_request("GET".to_string(), "file:///etc/passwd".to_string(), None)), BevyApiError::InvalidHttpRequest);
+        assert!(host.http_requests.is_empty());
+
+        let handle = host.http_request("post".to_string(), "https://example.com/score".to_string(), Some(vec![1, 2])).unwrap();
+        assert_eq!(host.http_requests[0].method, "POST");
+        assert!(host.poll_request(handle).unwrap().is_none());
+
+        // 模拟其他组件的完成结果与未知句柄：只有自己发起的句柄会被取走
+        let response = HttpResponse { status: 200, body: b"ok".to_vec() };
+        other.deliver_http_responses(&[(handle, Ok(response.clone()))]);
+        assert!(other.poll_request(handle).unwrap().is_none());
+        host.deliver_http_responses(&[(handle, Ok(response)), (handle + 1000, Err("x".to_string()))]);
+        let result = host.poll_request(handle).unwrap().unwrap().unwrap();
+        assert_eq!((result.status, result.body), (200, b"ok".to_vec()));
+        assert!(host.poll_request(handle).unwrap().is_none());
+        assert!(host.http_responses.is_empty());
+    }
+
+    #[test]
+    fn set_locale_loads_bundle_and_falls_back_to_en() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let dir = std::env::temp_dir().join(format!("test_game_i18n_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("zh-CN.ftl"), "# 菜单\nmenu-start = 开始游戏\nhelp =\n    第一行\n    第二行\n-brand = 不可引用\n").unwrap();
+        std::fs::write(dir.join("en.json"), r#"{ "menu-start": "Start", "lives": 3 }"#).unwrap();
+
+        let mut host = HostState::new(None);
+        assert_eq!(api_error(host.set_locale("../secret".to_string())), BevyApiError::InvalidLocale);
+        assert_eq!(host.translate("menu-start".to_string()).unwrap(), "menu-start");
+        host.set_locale("zh-CN".to_string()).unwrap();
+
+        let mut world = World::new();
+        world.insert_resource(I18nConfig { dir: dir.clone() });
+        world.init_resource::<I18nBundle>();
+        world.init_resource::<Events<LocaleChanged>>();
+        world.insert_resource(PendingLocale(host.locale_request.take()));
+        world.run_system_once(apply_locale_request);
+        let bundle = world.resource::<I18nBundle>();
+        assert_eq!(bundle.locale, "zh-CN");
+        assert_eq!(bundle.translate("help"), "第一行\n第二行");
+        assert!(!bundle.strings.contains_key("-brand"));
+
+        host.i18n_strings = bundle.strings.clone();
+        assert_eq!(host.translate("menu-start".to_string()).unwrap(), "开始游戏");
+
+        // 缺失的语言回退到 en.json，非字符串条目被忽略
+        world.insert_resource(PendingLocale(Some("fr".to_string())));
+        world.run_system_once(apply_locale_request);
+        let bundle = world.resource::<I18nBundle>();
+        assert_eq!((bundle.locale.as_str(), bundle.translate("menu-start")), ("en", "Start".to_string()));
+        assert!(!bundle.strings.contains_key("lives"));
+
+        let events = world.resource::<Events<LocaleChanged>>();
+        let changed: Vec<_> = events.get_reader().read(events).cloned().collect();
+        assert_eq!(changed, [LocaleChanged("zh-CN".to_string()), LocaleChanged("en".to_string())]);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[cfg(not(feature = "networking"))]
+    #[test]
+    fn http_request_without_networking_fails_without_sending() {
+        let mut host = HostState::new(None);
+        host.permissions.insert(PermissionType::Network);
+        let handle = host.http_request("GET".to_string(), "http://localhost/".to_string(), None).unwrap();
+
+        let mut world = World::new();
+        world.init_resource::<HttpResponseMap>();
+        world.insert_resource(PendingHttpRequests(host.http_requests.drain(..).collect()));
+        world.run_system_once(start_http_requests);
+        let completed = std::mem::take(&mut world.resource_mut::<HttpResponseMap>().completed);
+        host.deliver_http_responses(&completed);
+
+        assert!(matches!(host.poll_request(handle).unwrap(), Some(Err(_))));
+    }
+
+    #[test]
+    fn clipboard_read_sees_write_in_same_frame() {
+        let mut host = HostState::new(None);
+        assert_eq!(host.clipboard_read().unwrap(), None);
+        host.clipboard_write("勇者".to_string()).unwrap();
+        assert_eq!(host.clipboard_read().unwrap().as_deref(), Some("勇者"));
+        assert_eq!(host.clipboard_commands, [ClipboardCommand::Write("勇者".to_string())]);
+    }
+
+    #[test]
+    fn window_title_applies_last_request() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut host = HostState::new(None);
+        host.set_window_title("Dungeon Level 2".to_string()).unwrap();
+        host.set_window_title("Dungeon Level 3 – HP: 45".to_string()).unwrap();
+
+        let mut world = World::new();
+        let window = world.spawn((Window::default(), PrimaryWindow)).id();
+        world.insert_resource(PendingWindowTitle(host.window_title.take()));
+        world.run_system_once(apply_window_title);
+
+        assert_eq!(world.get::<Window>(window).unwrap().title, "Dungeon Level 3 – HP: 45");
+        assert!(world.resource::<PendingWindowTitle>().0.is_none());
+    }
+
+    #[cfg(feature = "js-fallback")]
+    #[test]
+    fn quickjs_backend_matches_wasm_update_rules() {
+        use crate::test_support::keys;
+
+        let mut backend = QuickJsRuntime::from_default_file().expect("assets/game_logic.js 加载失败");
+        let keyboard = backend.call_process_keyboard(keys(true, false, true, false)).unwrap();
+        assert_eq!((keyboard.right, keyboard.up, keyboard.left), (true, true, false));
+
+        let moved = backend.call_update_game(keyboard, PlayerState { x: 0.0, y: 0.0, speed: 200.0 }, 0.1).unwrap();
+        assert_eq!((moved.x, moved.y), (20.0, 20.0));
+        // 与 TS 版本相同的边界钳制
+        let clamped = backend.call_update_game(keyboard, PlayerState { x: 290.0, y: 190.0, speed: 200.0 }, 1.0).unwrap();
+        assert_eq!((clamped.x, clamped.y), (300.0, 200.0));
+        backend.call_on_ui_event("toggle_panel").unwrap();
+
+        let broken = QuickJsRuntime::from_source("function updateGame() { throw new Error('boom'); }").unwrap();
+        let mut broken: Box<dyn GameLogicBackend> = Box::new(broken);
+        let error = broken.call_update_game(keyboard, PlayerState { x: 0.0, y: 0.0, speed: 1.0 }, 0.1).unwrap_err();
+        assert!(error.to_string().contains("boom"), "{}", error);
+        assert!(broken.call_on_ui_event("toggle_panel").is_err(), "缺少 onUiEvent 应返回错误");
+    }
+
+    #[test]
+    fn query_game_state_matches_player_getters() {
+        let mut host = HostState::new(None);
+        host.player_position = Vec2::new(32.0, -16.0);
+        host.player_health = 75.0;
+        host.set_player_speed(350.0).unwrap();
+
+        let state = host.query_game_state().unwrap();
+        assert_eq!((state.x, state.y, state.speed, state.health), (32.0, -16.0, 350.0, 75.0));
+        host.set_player_position(1.0, 2.0).unwrap();
+        let state = host.query_game_state().unwrap();
+        assert_eq!((state.x, state.y), (1.0, 2.0));
+    }
+
+    #[test]
+    fn fixed_tick_runs_whole_steps_and_carries_remainder() {
+        let mut fixed = FixedTickState::default();
+        // 60hz 一步约 16.7ms：34ms 的一帧跑两步
+        assert_eq!(fixed.advance(0.034, 60.0), 2);
+
+        // 33ms 差一点够两步，余量留到下一帧，稳定 30fps 时每帧平均两步
+        let mut fixed = FixedTickState::default();
+        assert_eq!(fixed.advance(0.033, 60.0), 1);
+        assert_eq!(fixed.advance(0.033, 60.0), 2);
+        assert_eq!(fixed.advance(0.001, 60.0), 0);
+
+        // 长时间卡顿只补跑有限步数
+        assert_eq!(fixed.advance(1.0, 60.0), MAX_FIXED_STEPS_PER_FRAME);
+        assert_eq!(fixed.accumulator, 0.0);
+    }
+
+    #[test]
+    fn fixed_tick_interpolates_between_last_two_steps() {
+        let mut fixed = FixedTickState::default();
+        fixed.advance(0.025, 60.0);
+        fixed.step_completed(Vec2::ZERO, Vec2::new(10.0, 0.0));
+        // 余量约半步
+        let rendered = fixed.interpolated(60.0);
+        assert!((rendered.x - 5.0).abs() < 0.1, "插值位置 {:?}", rendered);
+
+        // 物理引擎把玩家推回 2 个单位：逻辑位置同步修正
+        let corrected = fixed.resync(rendered - Vec2::new(2.0, 0.0));
+        assert!(corrected.abs_diff_eq(Vec2::new(8.0, 0.0), 1e-4), "修正后位置 {:?}", corrected);
+    }
+
+    #[test]
+    fn missing_wasm_falls_back_to_runnable_runtime() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let runtime = WasmRuntimeBuilder::new()
+            .wasm_path("assets/no_such_logic.wasm")
+            .build_or_fallback()
+            .expect("缺少文件时应以空逻辑启动");
+        assert_eq!(runtime.order, ["no_such_logic"]);
+        assert!(runtime.instances.is_empty());
+        assert!(runtime.failed.contains("no_such_logic"));
+
+        let mut app = tick_app(runtime);
+        app.world.resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::ArrowRight);
+        app.world.run_system_once(wasm_tick);
+        app.world.run_system_once(wasm_tick);
+        assert_eq!(app.world.resource::<GameState>().player_position, Vec2::ZERO);
+    }
+
+    #[test]
+    fn velocity_covers_target_in_one_frame() {
+        let velocity = velocity_towards(Vec2::new(10.0, 0.0), Vec2::new(12.0, -1.0), 0.5);
+        assert_eq!(velocity, Vec2::new(4.0, -2.0));
+        assert_eq!(velocity_towards(Vec2::ZERO, Vec2::ONE, 0.0), Vec2::ZERO);
+    }
+
+    #[test]
+    fn configured_keys_trigger_their_events() {
+        let config = UiEventConfig {
+            bindings: vec![
+                (KeyCode::KeyE, "toggle_panel".to_string()),
+                (KeyCode::Escape, "close_panel".to_string()),
+                (KeyCode::KeyI, "open_inventory".to_string()),
+            ],
+            dump_key: None,
+        };
+        let mut input = ButtonInput::<KeyCode>::default();
+        input.press(KeyCode::KeyE);
+        input.press(KeyCode::Escape);
+
+        assert_eq!(config.triggered(&input), ["toggle_panel", "close_panel"]);
+
+        // 按住不放：下一帧 just_pressed 被清除，不再重复触发
+        input.clear();
+        assert!(config.triggered(&input).is_empty());
+    }
+
+    #[test]
+    fn play_sound_enqueues_audio_command() {
+        let mut host = HostState::new(Some("hud".to_string()));
+        host.play_sound("sfx/click.ogg".to_string(), 0.8).unwrap();
+        host.play_music("bgm".to_string(), "music/theme.ogg".to_string(), true, 0.5).unwrap();
+        assert_eq!(api_error(host.play_sound("sfx/click.ogg".to_string(), -1.0)), BevyApiError::InvalidVolume);
+
+        assert_eq!(host.audio_commands, [
+            AudioCommand::PlaySound { path: "sfx/click.ogg".to_string(), volume: 0.8 },
+            AudioCommand::PlayMusic {
+                key: "hud/bgm".to_string(), path: "music/theme.ogg".to_string(), looped: true, volume: 0.5,
+            },
+        ]);
+    }
+
+    #[test]
+    fn set_camera_moves_main_camera() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut host = HostState::new(None);
+        host.set_camera(120.0, -40.0, 2.0).unwrap();
+        host.set_camera(64.0, 32.0, 50.0).unwrap();
+
+        let mut world = World::new();
+        let camera = world.spawn((Camera2dBundle::default(), MainCamera)).id();
+        world.insert_resource(PendingCamera { request: host.camera_request.take() });
+
+        world.run_system_once(process_camera);
+
+        let translation = world.get::<Transform>(camera).unwrap().translation;
+        assert_eq!((translation.x, translation.y), (64.0, 32.0));
+        // 同帧最后一次请求生效，zoom 截断到上限
+        assert_eq!(world.get::<OrthographicProjection>(camera).unwrap().scale, 1.0 / CAMERA_ZOOM_RANGE.1);
+    }
+
+    #[test]
+    fn oversized_sprite_grids_are_rejected() {
+        let mut host = HostState::new(None);
+        let sprite = |columns: u32, rows: u32| AnimatedSpriteConfig {
+            key: "coin".to_string(), x: 0.0, y: 0.0, texture_path: "ui/coin.png".to_string(),
+            tile_width: 16.0, tile_height: 16.0, columns, rows, fps: 10.0,
+        };
+        // 65536 × 65536 在 u32 中溢出为 0，必须按 u64 计算后再比较上限
+        assert_eq!(api_error(host.spawn_animated_sprite(sprite(65_536, 65_536))), BevyApiError::InvalidDimensions);
+        assert_eq!(api_error(host.spawn_animated_sprite(sprite(4_097, 1))), BevyApiError::InvalidDimensions);
+        assert_eq!(
+            api_error(host.play_sprite_animation("coin".to_string(), "ui/coin.png".to_string(), 65_536, 65_536, 10.0, true)),
+            BevyApiError::InvalidDimensions
+        );
+        assert!(host.spawn_commands.is_empty());
+
+        host.spawn_animated_sprite(sprite(64, 64)).unwrap();
+        host.play_sprite_animation("coin".to_string(), "ui/coin.png".to_string(), 64, 64, 10.0, true).unwrap();
+        assert_eq!(host.spawn_commands.len(), 2);
+    }
+
+    #[test]
+    fn animation_advances_with_time() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = ui_world();
+        let sprite = world.spawn((
+            TextureAtlas::default(),
+            SpriteAnimation { first: 0, last: 3, fps: 10.0, elapsed_secs: 0.0, frame_count: 8, looping: true },
+        )).id();
+
+        world.resource_mut::<Time>().advance_by(Duration::from_millis(50));
+        world.run_system_once(animate_sprites);
+        assert_eq!(world.get::<TextureAtlas>(sprite).unwrap().index, 0, "不足一帧时长时不换帧");
+
+        world.resource_mut::<Time>().advance_by(Duration::from_millis(200));
+        world.run_system_once(animate_sprites);
+        assert_eq!(world.get::<TextureAtlas>(sprite).unwrap().index, 2);
+
+        // 再过 0.2s 越过区间末尾（帧 3）后回到起始帧
+        world.resource_mut::<Time>().advance_by(Duration::from_millis(200));
+        world.run_system_once(animate_sprites);
+        assert_eq!(world.get::<TextureAtlas>(sprite).unwrap().index, 0);
+    }
+
+    #[test]
+    fn ui_sprite_sheet_plays_once_and_reports_completion() {
+        use bevy::ecs::system::RunSystemOnce;
+        use bevy::render::render_asset::RenderAssetUsages;
+        use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+        let mut world = ui_world();
+        let coin = world.spawn(NodeBundle::default()).id();
+        world.resource_mut::<UiEntityMap>().insert("coin".to_string(), coin, None);
+
+        let mut host = HostState::new(None);
+        assert_eq!(
+            api_error(host.play_sprite_animation("coin".to_string(), "ui/coin.png".to_string(), 0, 4, 10.0, false)),
+            BevyApiError::InvalidDimensions,
+        );
+        host.play_sprite_animation("coin".to_string(), "ui/coin.png".to_string(), 1, 4, 10.0, false).unwrap();
+        world.insert_resource(PendingUiCommands { spawns: host.spawn_commands.drain(..).collect(), mutations: Vec::new() });
+        world.run_system_once(process_ui_spawn);
+
+        // 精灵表加载完成前保持等待
+        world.run_system_once(start_pending_sprite_sheets);
+        assert!(world.get::<PendingSpriteSheet>(coin).is_some());
+        let sheet = world.get::<UiImage>(coin).unwrap().texture.clone();
+        let image = Image::new_fill(
+            Extent3d { width: 64, height: 16, depth_or_array_layers: 1 },
+            TextureDimension::D2,
+            &[255; 4],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+        world.resource_mut::<Assets<Image>>().insert(sheet.id(), image);
+        world.run_system_once(start_pending_sprite_sheets);
+        let layout = world.get::<TextureAtlas>(coin).unwrap().layout.clone();
+        let layout = world.resource::<Assets<TextureAtlasLayout>>().get(&layout).unwrap().clone();
+        assert_eq!(layout.textures.len(), 4);
+        assert_eq!(layout.textures[1], Rect::new(16.0, 0.0, 32.0, 16.0));
+
+        // 10fps 下 0.5s：走完 0→3 帧后不再回到第 0 帧
+        world.resource_mut::<Time>().advance_by(Duration::from_millis(500));
+        world.run_system_once(animate_sprites);
+        assert!(world.get::<SpriteAnimation>(coin).is_none());
+        assert_eq!(world.get::<TextureAtlas>(coin).unwrap().index, 3);
+        let finished: Vec<AnimationFinished> = world.resource_mut::<Events<AnimationFinished>>().drain().collect();
+        assert_eq!(finished, [AnimationFinished("coin".to_string())]);
+    }
+
+    #[test]
+    fn wasm_interfaces_tick_by_priority() {
+        let mut app = App::new();
+        WasmPlugin::add_wasm_interface(&mut app, "ui_logic", Path::new("ui_logic.wasm"), -10);
+        WasmPlugin::add_wasm_interface(&mut app, "ai_logic", Path::new("ai_logic.wasm"), 10);
+        WasmPlugin::add_wasm_interface(&mut app, "audio_logic", Path::new("audio_logic.wasm"), 0);
+        // 同名重复注册只更新优先级
+        WasmPlugin::add_wasm_interface(&mut app, "ui_logic", Path::new("ui_logic.wasm"), 20);
+
+        let config = app.world.resource::<WasmConfig>();
+        assert_eq!(config.components.len(), 4);
+        assert_eq!(config.tick_order(), ["ui_logic", "ai_logic", "game_logic", "audio_logic"]);
+    }
+
+    #[test]
+    fn game_logic_plugin_runs_in_minimal_app() {
+        let config = WasmConfig::default().with_component("ai_logic", "assets/ai_logic.wasm", 10);
+        let mut app = App::new();
+        WasmPlugin::add_wasm_interface(&mut app, "ui_logic", Path::new("ui_logic.wasm"), -10);
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            bevy::input::InputPlugin,
+            bevy::window::WindowPlugin { primary_window: None, ..default() },
+        ))
+        .init_asset::<TextureAtlasLayout>()
+        .init_asset::<Font>()
+        .add_plugins(GameLogicPlugin::new(config));
+
+        assert_eq!(app.world.resource::<WasmConfig>().tick_order(), ["ai_logic", "game_logic", "ui_logic"]);
+        app.update();
+        // Startup 已生成玩家，组件尚在异步加载，wasm_tick 跳过它们
+        let mut players = app.world.query_filtered::<(), With<Player>>();
+        assert_eq!(players.iter(&app.world).count(), 1);
+        assert!(app.world.non_send_resource::<WasmRuntime>().handles.contains_key("ai_logic"));
+    }
+
+    #[test]
+    fn plugin_wasm_path_replaces_default_component() {
+        let mut config = WasmConfig::default();
+        config.register("ai_logic", Path::new("ai_logic.wasm"), 10);
+        config.set_primary(Path::new("logic/platformer.wasm"));
+
+        assert_eq!(config.tick_order(), ["ai_logic", "platformer"]);
+        let primary = config.components.iter().find(|c| c.name == "platformer").unwrap();
+        assert_eq!(primary.path, Path::new("logic/platformer.wasm"));
+    }
+
+    /// bevy-api 的每个函数及其受哪项 CapabilityProfile 约束（None 为不受约束）。
+    /// require_capability 只在列出的入口检查，新增函数必须在此登记，否则下面的测试失败
+    const BEVY_API_CAPABILITIES: [(&str, Option<Capability>); 85] = [
+        ("spawn-panel",             Some(Capability::SpawnUi)),
+        ("spawn-persistent-panel",  Some(Capability::SpawnUi)),
+        ("spawn-text",              Some(Capability::SpawnUi)),
+        ("spawn-container",         Some(Capability::SpawnUi)),
+        ("spawn-image",             Some(Capability::SpawnUi)),
+        ("spawn-text-with-icons",   Some(Capability::SpawnUi)),
+        ("spawn-animated-sprite",   Some(Capability::SpawnUi)),
+        ("spawn-tilemap",           Some(Capability::SpawnUi)),
+        ("spawn-input-field",       Some(Capability::SpawnUi)),
+        ("spawn-world-text",        Some(Capability::SpawnUi)),
+        ("spawn-progress-bar",      Some(Capability::SpawnUi)),
+        ("set-progress",            None),
+        ("spawn-tooltip",           Some(Capability::SpawnUi)),
+        ("request-input-value",     None),
+        ("get-input-value",         None),
+        ("set-animation",           None),
+        ("set-tile",                None),
+        ("play-sprite-animation",   Some(Capability::SpawnUi)),
+        ("stop-animation",          None),
+        ("batch-spawn-text",        Some(Capability::SpawnUi)),
+        ("spawn-texts",             Some(Capability::SpawnUi)),
+        ("spawn-panels",            Some(Capability::SpawnUi)),
+        ("despawn",                 None),
+        ("set-visible",             None),
+        ("set-opacity",             None),
+        ("set-position",            None),
+        ("set-z-index",             None),
+        ("bring-to-front",          None),
+        ("send-to-back",            None),
+        ("set-text-style",          None),
+        ("set-font",                None),
+        ("set-focusable",           None),
+        ("set-draggable",           None),
+        ("set-focus",               None),
+        ("reorder-child",           None),
+        ("set-parent",              None),
+        ("tween",                   None),
+        ("load-font",               None),
+        ("measure-text",            None),
+        ("query-ui-bounds",         None),
+        ("get-layout-warnings",     None),
+        ("asset-ready",             None),
+        ("overlaps",                None),
+        ("tag",                     None),
+        ("nearest",                 None),
+        ("dump-ui",                 None),
+        ("load-scene",              None),
+        ("get-current-scene",       None),
+        ("get-random-f32",          None),
+        ("get-random-range",        None),
+        ("save-to-local-storage",   Some(Capability::SaveData)),
+        ("load-from-local-storage", None),
+        ("set-paused",              None),
+        ("paused",                  None),
+        ("request-permission",      None),
+        ("get-player-position",     None),
+        ("query-game-state",        None),
+        ("set-player-position",     None),
+        ("get-player-speed",        None),
+        ("set-player-speed",        None),
+        ("set-locale",              None),
+        ("translate",               None),
+        ("set-var",                 None),
+        ("get-var",                 None),
+        ("set-gravity",             None),
+        ("apply-impulse",           None),
+        ("http-request",            Some(Capability::Http)),
+        ("poll-request",            None),
+        ("play-sound",              Some(Capability::PlayAudio)),
+        ("play-music",              Some(Capability::PlayAudio)),
+        ("stop-music",              None),
+        ("set-camera",              None),
+        ("set-window-title",        None),
+        ("set-window-icon",         None),
+        ("clipboard-write",         None),
+        ("clipboard-read",          None),
+        ("draw-debug-line",         None),
+        ("draw-debug-circle",       None),
+        ("post-event",              None),
+        ("subscribe-to-bevy-event", None),
+        ("get-time",                None),
+        ("current-frame",           None),
+        ("set-timer",               None),
+        ("cancel-timer",            None),
+        ("log",                     None),
+    ];
+
+    #[test]
+    fn capability_profile_blocks_denied_host_calls() {
+        let mut app = App::new();
+        let profile = CapabilityProfile { can_spawn_ui: false, can_http: false, ..CapabilityProfile::FULL };
+        WasmPlugin::register_mod_with_profile(&mut app, "third_party", Path::new("mods/third_party.wasm"), profile);
+        let config = app.world.resource::<WasmConfig>();
+        let registered = config.components.iter().find(|c| c.name == "third_party").unwrap();
+        assert_eq!((registered.priority, registered.capabilities), (0, profile));
+
+        let mut host = HostState::new(Some("third_party".to_string()));
+        host.capabilities = profile;
+        host.permissions.insert(PermissionType::Network);
+        assert_eq!(api_error(host.spawn_panel(panel("hud"))), BevyApiError::CapabilityDenied(Capability::SpawnUi));
+        assert_eq!(api_error(host.spawn_panels(vec![panel("a")])), BevyApiError::CapabilityDenied(Capability::SpawnUi));
+        assert_eq!(
+            api_error(host.http_request("GET".to_string(), "https://example.com".to_string(), None)),
+            BevyApiError::CapabilityDenied(Capability::Http)
+        );
+        assert!(host.spawn_commands.is_empty() && host.http_requests.is_empty());
+
+        // 未被限制的能力照常可用
+        host.play_sound("sfx/click.ogg".to_string(), 1.0).unwrap();
+        assert_eq!(host.audio_commands.len(), 1);
+    }
+
+    #[test]
+    fn every_bevy_api_function_has_a_capability_mapping() {
+        let wit = std::fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("wit/game.wit")).unwrap();
+        let start = wit.find("interface bevy-api {").unwrap();
+        let end = start + wit[start..].find("\n}").unwrap();
+        let declared: Vec<&str> = wit[start..end]
+            .lines()
+            .filter_map(|line| line.trim().split_once(':'))
+            .filter(|(_, rest)| rest.trim_start().starts_with("func("))
+            .map(|(name, _)| name)
+            .collect();
+        let mapped: Vec<&str> = BEVY_API_CAPABILITIES.iter().map(|(name, _)| *name).collect();
+        assert_eq!(declared, mapped, "bevy-api 函数与能力映射表不一致");
+    }
+
+    #[test]
+    fn capability_gated_functions_are_denied_without_capability() {
+        let text = |key: &str| TextConfig {
+            key: key.to_string(), parent_key: "hud".to_string(), text: "hp".to_string(),
+            font_size: 16.0, color_r: 1.0, color_g: 1.0, color_b: 1.0, font: None, font_path: None,
+        };
+        for (name, capability) in BEVY_API_CAPABILITIES {
+            let Some(capability) = capability else { continue; };
+            let mut host = HostState::new(None);
+            host.capabilities = CapabilityProfile::NONE;
+            host.permissions.extend([
+                PermissionType::ReadFile, PermissionType::WriteFile, PermissionType::Network,
+                PermissionType::Clipboard, PermissionType::Audio,
+            ]);
+            // 参数均合法、权限全部授予：调用只可能因能力配置被拒绝
+            let result = match name {
+                "spawn-panel" => host.spawn_panel(panel("hud")),
+                "spawn-persistent-panel" => host.spawn_persistent_panel(panel("hud")),
+                "spawn-text" => host.spawn_text(text("hud.hp")),
+                "spawn-container" => host.spawn_container(ContainerConfig {
+                    key: "row".to_string(), parent_key: None, flex_direction: FlexDir::Row, gap: 4.0, padding: 4.0,
+                    align_items: AlignMode::Center, justify_content: JustifyMode::Start,
+                }),
+                "spawn-image" => host.spawn_image(ImageConfig {
+                    key: "logo".to_string(), parent_key: None, width: 32.0, height: 32.0,
+                    texture_path: "icons/logo.png".to_string(),
+                }),
+                "spawn-text-with-icons" => host.spawn_text_with_icons(RichTextConfig {
+                    key: "gold".to_string(), parent_key: None, segments: Vec::new(),
+                }),
+                "spawn-animated-sprite" => host.spawn_animated_sprite(AnimatedSpriteConfig {
+                    key: "coin".to_string(), x: 0.0, y: 0.0, texture_path: "ui/coin.png".to_string(),
+                    tile_width: 16.0, tile_height: 16.0, columns: 4, rows: 1, fps: 10.0,
+                }),
+                "spawn-tilemap" => host.spawn_tilemap(TilemapConfig {
+                    key: "level".to_string(), x: 0.0, y: 0.0, cols: 1, rows: 1, tile_size: 16.0,
+                    tileset_path: "tiles/ground.png".to_string(), tileset_columns: 1, tileset_rows: 1, tiles: vec![0],
+                }),
+                "spawn-input-field" => host.spawn_input_field(InputFieldConfig {
+                    key: "name".to_string(), x: 0.0, y: 0.0, width: 120.0, height: 24.0,
+                    placeholder: String::new(), max_length: 16,
+                }),
+                "spawn-world-text" => host.spawn_world_text(WorldTextConfig {
+                    key: "dmg".to_string(), x: 0.0, y: 0.0, text: "-1".to_string(),
+                    font_size: 16.0, color_r: 1.0, color_g: 1.0, color_b: 1.0, font: None,
+                }),
+                "spawn-progress-bar" => host.spawn_progress_bar(ProgressBarConfig {
+                    key: "hp".to_string(), parent_key: None, x: 0.0, y: 0.0, width: 100.0, height: 8.0,
+                    bg_r: 0.2, bg_g: 0.2, bg_b: 0.2, bg_a: 1.0, fill_r: 0.8, fill_g: 0.1, fill_b: 0.1, fill_a: 1.0,
+                    value: 1.0,
+                }),
+                "spawn-tooltip" => host.spawn_tooltip("tip".to_string(), "hud".to_string(), "提示".to_string(), 120.0),
+                "play-sprite-animation" => {
+                    host.play_sprite_animation("coin".to_string(), "ui/coin.png".to_string(), 1, 4, 10.0, false)
+                }
+                "batch-spawn-text" => host.batch_spawn_text(vec![text("hud.a")]),
+                "spawn-texts" => host.spawn_texts(vec![text("hud.a")]),
+                "spawn-panels" => host.spawn_panels(vec![panel("a")]),
+                "play-sound" => host.play_sound("sfx/click.ogg".to_string(), 1.0),
+                "play-music" => host.play_music("bgm".to_string(), "music/theme.ogg".to_string(), true, 0.5),
+                "save-to-local-storage" => host.save_to_local_storage("slot1".to_string(), vec![1]).map(drop),
+                "http-request" => host.http_request("GET".to_string(), "https://example.com".to_string(), None).map(drop),
+                _ => panic!("{} 受 {:?} 约束，但缺少调用用例", name, capability),
+            };
+            assert_eq!(api_error(result), BevyApiError::CapabilityDenied(capability), "{}", name);
+            assert!(host.spawn_commands.is_empty() && host.audio_commands.is_empty() && host.http_requests.is_empty(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn one_shot_timer_fires_exactly_once() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut host = HostState::new(Some("hud".to_string()));
+        host.set_timer("cooldown".to_string(), 0.1, false).unwrap();
+        assert!(host.set_timer("spin".to_string(), 0.0, true).is_err());
+
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world.init_resource::<GamePaused>();
+        world.init_resource::<GuestTimers>();
+        for command in host.timer_commands.drain(..) {
+            world.resource_mut::<GuestTimers>().apply(command);
+        }
+
+        let mut fired = Vec::new();
+        for _ in 0..20 {
+            world.resource_mut::<Time>().advance_by(Duration::from_millis(16));
+            world.run_system_once(tick_timers);
+            fired.append(&mut world.resource_mut::<GuestTimers>().fired);
+        }
+        assert_eq!(fired, ["hud/cooldown"]);
+        assert!(world.resource::<GuestTimers>().timers.is_empty());
+    }
+
+    #[test]
+    fn dragging_moves_panel_by_cursor_delta() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        world.init_resource::<UiEntityMap>();
+        world.init_resource::<DragState>();
+        world.init_resource::<Events<DragMoved>>();
+        world.init_resource::<ButtonInput<MouseButton>>();
+        let mut window = Window::default();
+        window.set_cursor_position(Some(Vec2::new(100.0, 100.0)));
+        let window = world.spawn((window, PrimaryWindow)).id();
+        let style = Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(UI_ORIGIN.x + 10.0),
+            bottom: Val::Px(UI_ORIGIN.y + 20.0),
+            ..default()
+        };
+        let panel = world.spawn((NodeBundle { style, ..default() }, Interaction::Pressed, Draggable)).id();
+        world.resource_mut::<UiEntityMap>().insert("window".to_string(), panel, None);
+
+        // 按下开始拖拽，光标右移 30、上移 20（窗口坐标 y 轴向下）
+        world.resource_mut::<ButtonInput<MouseButton>>().press(MouseButton::Left);
+        world.run_system_once(handle_drag);
+        world.get_mut::<Window>(window).unwrap().set_cursor_position(Some(Vec2::new(130.0, 80.0)));
+        world.run_system_once(handle_drag);
+
+        let style = world.get::<Style>(panel).unwrap();
+        assert_eq!((style.left, style.bottom), (Val::Px(UI_ORIGIN.x + 40.0), Val::Px(UI_ORIGIN.y + 40.0)));
+        let events = world.resource::<Events<DragMoved>>();
+        let moved: Vec<_> = events.get_reader().read(events).cloned().collect();
+        assert_eq!(moved, [DragMoved { key: "window".to_string(), position: Vec2::new(40.0, 40.0) }]);
+
+        // 松开后光标移动不再影响面板
+        world.resource_mut::<ButtonInput<MouseButton>>().release(MouseButton::Left);
+        world.run_system_once(handle_drag);
+        world.get_mut::<Window>(window).unwrap().set_cursor_position(Some(Vec2::new(0.0, 0.0)));
+        world.run_system_once(handle_drag);
+        assert_eq!(world.get::<Style>(panel).unwrap().left, Val::Px(UI_ORIGIN.x + 40.0));
+    }
+
+    #[test]
+    fn tilemap_spawns_tiles_and_set_tile_updates_them() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = ui_world();
+        let config = TilemapConfig {
+            key: "level".to_string(),
+            x: 0.0, y: 0.0,
+            cols: 3, rows: 2,
+            tile_size: 16.0,
+            tileset_path: "tiles/ground.png".to_string(),
+            tileset_columns: 4, tileset_rows: 4,
+            tiles: vec![0, 1, EMPTY_TILE, 2, 2, 2],
+        };
+        let mut host = HostState::new(None);
+        assert_eq!(
+            api_error(host.spawn_tilemap(TilemapConfig { tiles: vec![0; 5], ..config.clone() })),
+            BevyApiError::InvalidDimensions
+        );
+        assert_eq!(
+            api_error(host.spawn_tilemap(TilemapConfig { tiles: vec![16; 6], ..config.clone() })),
+            BevyApiError::InvalidDimensions
+        );
+        host.spawn_tilemap(config).unwrap();
+        world.insert_resource(PendingUiCommands { spawns: host.spawn_commands.drain(..).collect(), mutations: Vec::new() });
+        world.run_system_once(process_ui_spawn);
+
+        let root = world.resource::<UiEntityMap>().map["level"];
+        let tiles = world.get::<Tilemap>(root).unwrap().tiles.clone();
+        assert_eq!(tiles.iter().filter(|tile| tile.is_some()).count(), 5);
+        assert_eq!(world.get::<Children>(root).unwrap().len(), 5);
+        // (1, 0) 位于上面一行的中间
+        let top_middle = tiles[1].unwrap();
+        assert_eq!(world.get::<Transform>(top_middle).unwrap().translation, Vec3::new(24.0, 24.0, 0.0));
+
+        host.set_tile("level".to_string(), 1, 0, 7).unwrap();
+        host.set_tile("level".to_string(), 2, 0, 3).unwrap();
+        host.set_tile("level".to_string(), 0, 1, EMPTY_TILE).unwrap();
+        host.set_tile("level".to_string(), 5, 5, 0).unwrap();
+        world.insert_resource(PendingUiCommands { spawns: Vec::new(), mutations: host.mutation_commands.drain(..).collect() });
+        world.run_system_once(process_ui_mutations);
+
+        assert_eq!(world.get::<TextureAtlas>(top_middle).unwrap().index, 7);
+        let tilemap = world.get::<Tilemap>(root).unwrap();
+        let (filled, cleared) = (tilemap.tiles[2].unwrap(), tilemap.tiles[3]);
+        assert!(cleared.is_none() && world.get_entity(tiles[3].unwrap()).is_none());
+        assert_eq!(world.get::<TextureAtlas>(filled).unwrap().index, 3);
+        assert_eq!(world.get::<Children>(root).unwrap().len(), 5);
+    }
+}
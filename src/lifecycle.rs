@@ -0,0 +1,160 @@
+// ─── 组件生命周期 ─────────────────────────────────────────────────────────────
+//
+// wasm 缺失时的提示、热重载时跨实例迁移状态，以及退出前通知各组件的 on-shutdown。
+
+use super::*;
+use std::sync::mpsc::{self, RecvTimeoutError};
+
+// ─── 缺少游戏逻辑时的提示 ─────────────────────────────────────────────────────
+//
+// wasm 文件缺失或无法编译时不中断启动：组件没有实例，wasm_tick 直接跳过（等同空逻辑），
+// 屏幕中央显示提示。之后放入有效的 wasm，file_watcher 触发加载、实例化成功后提示自动消失。
+
+/// 标记："No game logic loaded" 提示
+#[derive(Component)]
+pub(crate) struct MissingLogicNotice;
+
+/// 记录加载失败的组件，并按是否还有可用实例显示 / 移除提示
+pub(crate) fn show_missing_logic_notice(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    font_config: Res<FontConfig>,
+    mut wasm: NonSendMut<WasmRuntime>,
+    notices: Query<Entity, With<MissingLogicNotice>>,
+) {
+    let WasmRuntime { ref handles, ref instances, ref mut failed, ref fallback, .. } = *wasm;
+    for (name, handle) in handles {
+        if !instances.contains_key(name)
+            && asset_server.get_load_state(handle.id()) == Some(LoadState::Failed)
+            && failed.insert(name.clone())
+        {
+            eprintln!("[WASM] 组件 {} 加载失败，以空逻辑运行", name);
+        }
+    }
+
+    let missing = instances.is_empty() && !failed.is_empty() && fallback.is_none();
+    match (missing, notices.get_single()) {
+        (true, Err(_)) => {
+            commands.spawn((
+                TextBundle::from_section(
+                    "No game logic loaded",
+                    TextStyle { font: asset_server.load(&font_config.default_font), font_size: 32.0, color: Color::ORANGE_RED },
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    align_self:    AlignSelf::Center,
+                    justify_self:  JustifySelf::Center,
+                    ..default()
+                }),
+                ZIndex::Global(i32::MAX),
+                MissingLogicNotice,
+            ));
+        }
+        (false, Ok(entity)) => commands.entity(entity).despawn_recursive(),
+        _ => {}
+    }
+}
+
+// ─── 热重载 ───────────────────────────────────────────────────────────────────
+//
+// 替换组件前先调用旧实例的 save-state，新实例化后再调用 restore-state，
+// 让 TS 侧的状态（玩家位置、分数、UI 管理器状态等）跨重载保留。
+// 两个函数属于可选的 hot-reload 接口：任一侧未导出或调用失败都退化为全新启动，
+// 只记录日志，不影响游戏继续运行。
+
+pub(crate) fn reload_instance(
+    engine: &Engine,
+    linker: &Linker<HostState>,
+    name: &str,
+    instance: &mut ComponentInstance,
+    component: &Component,
+) -> wasmtime::Result<()> {
+    // ① 旧实例导出状态
+    let saved = match instance.hot_reload {
+        Some(hot_reload) => match hot_reload.save_state(&mut instance.store) {
+            Ok(bytes) => Some(bytes),
+            Err(e) => {
+                eprintln!("[热重载] save-state 失败 [{}]，将以全新状态启动: {}", name, e);
+                None
+            }
+        },
+        None => {
+            println!("[热重载] 组件 {} 未导出 hot-reload，将以全新状态启动", name);
+            None
+        }
+    };
+
+    // ② 实例化新编译的组件；沿用命名空间与帧计数
+    let old_state = instance.store.data();
+    let mut host_state = HostState::new(old_state.key_namespace.clone());
+    host_state.frame_count = old_state.frame_count;
+    host_state.capabilities = old_state.capabilities;
+    host_state.rng = old_state.rng.clone();
+    let mut fresh = instantiate_precompiled(engine, linker, host_state, component)?;
+
+    // ③ 新实例恢复状态
+    match (saved, fresh.hot_reload) {
+        (Some(saved), Some(hot_reload)) => match hot_reload.restore_state(&mut fresh.store, &saved) {
+            Ok(()) => println!("[热重载] 已恢复状态 [{}]，{} 字节", name, saved.len()),
+            Err(e) => eprintln!("[热重载] restore-state 失败 [{}]，将以全新状态启动: {}", name, e),
+        },
+        (Some(saved), None) => {
+            println!("[热重载] 新组件 {} 未导出 hot-reload，丢弃 {} 字节的旧状态", name, saved.len());
+        }
+        (None, _) => {}
+    }
+
+    *instance = fresh;
+    Ok(())
+}
+
+// ─── 退出清理 ─────────────────────────────────────────────────────────────────
+//
+// 收到 AppExit 后按 order 依次调用各组件的 on-shutdown。
+// 每次调用由看门狗线程限时：超时后推进 engine 的 epoch，使 Guest 在下一个检查点 trap，
+// 保证行为异常的组件无法阻止应用退出。
+
+/// 单个组件 on-shutdown 的最长执行时间
+pub(crate) const SHUTDOWN_TIMEOUT: Duration = Duration::from_millis(500);
+
+pub(crate) fn shutdown_instance(engine: &Engine, instance: &mut ComponentInstance, timeout: Duration) -> wasmtime::Result<()> {
+    let ComponentInstance { ref game_world, ref mut store, .. } = *instance;
+    store.set_epoch_deadline(1);
+
+    // 调用结束后 done_tx 被丢弃，看门狗收到 Disconnected 直接退出，不会误伤后续组件
+    let (done_tx, done_rx) = mpsc::channel::<()>();
+    let watchdog_engine = engine.clone();
+    std::thread::spawn(move || {
+        if let Err(RecvTimeoutError::Timeout) = done_rx.recv_timeout(timeout) {
+            watchdog_engine.increment_epoch();
+        }
+    });
+
+    let result = game_world.interface0.call_on_shutdown(&mut *store);
+    drop(done_tx);
+    result
+}
+
+/// Last：检测到 AppExit 时通知所有组件；AppExit 可能被多个系统或多帧重复发送，每个实例只通知一次
+pub(crate) fn shutdown_wasm_components(
+    mut exit_events: EventReader<AppExit>,
+    mut wasm: NonSendMut<WasmRuntime>,
+) {
+    if exit_events.read().count() == 0 {
+        return;
+    }
+
+    let WasmRuntime { ref engine, ref order, ref mut instances, .. } = *wasm;
+    for name in order {
+        let Some(instance) = instances.get_mut(name) else { continue; };
+        if instance.store.data().shutdown_calls > 0 {
+            continue;
+        }
+        // 先计数：Guest trap 或超时同样算作已通知，不会在下一次 AppExit 时重试
+        instance.store.data_mut().shutdown_calls += 1;
+        match shutdown_instance(engine, instance, SHUTDOWN_TIMEOUT) {
+            Ok(()) => println!("[退出] on-shutdown 完成: {}", name),
+            Err(e) => eprintln!("[退出] on-shutdown 失败或超时 [{}]，继续退出: {}", name, e),
+        }
+    }
+}
@@ -0,0 +1,158 @@
+// ─── WASM 资源加载（AssetServer）──────────────────────────────────────────────
+//
+// .wasm 文件通过 Bevy 的 AssetServer 异步读取，不再阻塞主线程；
+// 开启 file_watcher 后，文件变化会再次触发 LoadedWithDependencies，走热重载流程。
+
+use super::*;
+use bevy::asset::io::{AssetReaderError, Reader};
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext, ReadAssetBytesError};
+use bevy::utils::BoxedFuture;
+
+/// 原始 wasm 字节及其清单，由 WasmComponentLoader 产出
+#[derive(Asset, TypePath)]
+pub(crate) struct WasmComponentAsset {
+    pub(crate) bytes: Vec<u8>,
+    /// 同目录下 `<文件名>.wasm.toml` 的内容；没有清单时为 None，不做兼容性检查
+    pub(crate) meta: Option<WasmComponentMeta>,
+}
+
+/// 组件清单，例如：
+///
+/// ```toml
+/// wit_version = "0.3.0"
+/// author = "ui-team"
+/// min_game_version = [0, 2, 0]
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct WasmComponentMeta {
+    /// 组件构建时使用的 WIT 版本，仅用于日志与排查
+    pub wit_version: String,
+    pub author: String,
+    /// 要求的最低游戏版本（主、次、修订），高于 GameVersion 时拒绝实例化
+    pub min_game_version: [u32; 3],
+}
+
+impl WasmComponentMeta {
+    /// 解析清单文本；三个字段均为必填，出错时返回可直接展示的原因
+    pub(crate) fn parse(text: &str) -> Result<Self, String> {
+        let table: toml::Table = text.parse().map_err(|e: toml::de::Error| e.message().to_string())?;
+        let string_field = |name: &str| match table.get(name) {
+            Some(toml::Value::String(s)) => Ok(s.clone()),
+            Some(_) => Err(format!("{} 必须是字符串", name)),
+            None => Err(format!("缺少字段 {}", name)),
+        };
+        let version_error = || "min_game_version 必须是三个非负整数，如 [0, 2, 0]".to_string();
+        let Some(toml::Value::Array(parts)) = table.get("min_game_version") else {
+            return Err(version_error());
+        };
+        let parts: Vec<u32> = parts
+            .iter()
+            .map(|v| v.as_integer().and_then(|n| u32::try_from(n).ok()))
+            .collect::<Option<_>>()
+            .ok_or_else(version_error)?;
+        let min_game_version: [u32; 3] = parts.try_into().map_err(|_| version_error())?;
+        Ok(WasmComponentMeta {
+            wit_version: string_field("wit_version")?,
+            author: string_field("author")?,
+            min_game_version,
+        })
+    }
+
+    /// 检查组件能否在 game_version 上运行；不兼容时返回给集成方看的错误文本
+    pub(crate) fn check_compatibility(&self, component: &str, game_version: GameVersion) -> Result<(), String> {
+        if self.min_game_version <= game_version.0 {
+            return Ok(());
+        }
+        let [a, b, c] = self.min_game_version;
+        let [x, y, z] = game_version.0;
+        Err(format!(
+            "组件 {} 需要游戏版本 >= {}.{}.{}，当前为 {}.{}.{}（作者 {}，WIT {}）：请升级游戏，\
+             或改用为 {}.{}.{} 构建的组件并相应调低 {}.wasm.toml 中的 min_game_version",
+            component, a, b, c, x, y, z, self.author, self.wit_version, x, y, z, component,
+        ))
+    }
+}
+
+/// 宿主游戏版本，默认取本 crate 的包版本；用于检查组件清单的 min_game_version
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct GameVersion(pub [u32; 3]);
+
+impl Default for GameVersion {
+    fn default() -> Self {
+        let part = |s: &str| s.parse().unwrap_or(0);
+        GameVersion([
+            part(env!("CARGO_PKG_VERSION_MAJOR")),
+            part(env!("CARGO_PKG_VERSION_MINOR")),
+            part(env!("CARGO_PKG_VERSION_PATCH")),
+        ])
+    }
+}
+
+/// 组件清单要求的游戏版本高于 GameVersion，或组件导出的 wit-hash 与宿主不一致，
+/// 组件未被实例化（热重载时继续使用旧实例）
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct WasmCompatibilityError {
+    pub component: String,
+    pub message: String,
+}
+
+#[derive(Debug)]
+pub(crate) enum WasmComponentLoadError {
+    Io(std::io::Error),
+    /// 清单存在但无法读取或解析
+    Manifest { path: PathBuf, reason: String },
+}
+
+impl std::fmt::Display for WasmComponentLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WasmComponentLoadError::Io(e) => write!(f, "读取 wasm 失败: {}", e),
+            WasmComponentLoadError::Manifest { path, reason } => write!(f, "组件清单 {} 无效: {}", path.display(), reason),
+        }
+    }
+}
+
+impl std::error::Error for WasmComponentLoadError {}
+
+#[derive(Default)]
+pub(crate) struct WasmComponentLoader;
+
+impl AssetLoader for WasmComponentLoader {
+    type Asset = WasmComponentAsset;
+    type Settings = ();
+    type Error = WasmComponentLoadError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a Self::Settings,
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await.map_err(WasmComponentLoadError::Io)?;
+
+            // 清单通过 LoadContext 读取，会记为加载依赖：修改清单同样触发热重载
+            let mut manifest_path = load_context.path().as_os_str().to_owned();
+            manifest_path.push(".toml");
+            let manifest_path = PathBuf::from(manifest_path);
+            let meta = match load_context.read_asset_bytes(manifest_path.clone()).await {
+                Ok(text) => {
+                    let text = String::from_utf8_lossy(&text);
+                    let meta = WasmComponentMeta::parse(&text)
+                        .map_err(|reason| WasmComponentLoadError::Manifest { path: manifest_path, reason })?;
+                    Some(meta)
+                }
+                Err(ReadAssetBytesError::AssetReaderError(AssetReaderError::NotFound(_))) => None,
+                Err(e) => {
+                    return Err(WasmComponentLoadError::Manifest { path: manifest_path, reason: e.to_string() });
+                }
+            };
+            Ok(WasmComponentAsset { bytes, meta })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["wasm"]
+    }
+}
@@ -0,0 +1,154 @@
+// ─── WASM 调用耗时统计 ────────────────────────────────────────────────────────
+//
+// wasm_tick 结束时整体写入 WasmCallMetrics（多组件时为各组件之和），
+// record_guest_metrics 再汇总为最近 60 帧的平均 / 最大值（GuestMetrics）。
+// 外部代码可直接读取这两个 Resource 做监控；按 F2 显示统计面板，另每 3 秒输出一次日志。
+// 同样的数值也写入 Bevy 的 DiagnosticsStore（路径见下），LogDiagnosticsPlugin 等工具可直接使用。
+
+use super::*;
+use bevy::diagnostic::DiagnosticPath;
+
+pub const WASM_UPDATE_GAME_US: DiagnosticPath = DiagnosticPath::const_new("wasm/call_update_game_us");
+pub const WASM_PROCESS_KEYBOARD_US: DiagnosticPath = DiagnosticPath::const_new("wasm/call_process_keyboard_us");
+pub const WASM_ON_UI_EVENT_US: DiagnosticPath = DiagnosticPath::const_new("wasm/call_on_ui_event_us");
+pub const WASM_TOTAL_US: DiagnosticPath = DiagnosticPath::const_new("wasm/total_us");
+
+/// 最近一帧 wasm_tick 中各类 Guest 调用的耗时（微秒）
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq)]
+pub struct WasmCallMetrics {
+    /// process-keyboard
+    pub keyboard_us: u32,
+    /// update-game
+    pub update_game_us: u32,
+    /// on-ui-event（含按键事件与 tween-done 通知）
+    pub ui_event_us: u32,
+    /// 整个 wasm_tick 的 Guest 调用循环，包含上面未单独统计的回调
+    pub total_us: u32,
+    /// 已统计的帧数
+    pub frame: u64,
+}
+
+/// 滚动平均的窗口大小（帧）
+pub(crate) const METRICS_WINDOW: usize = 60;
+
+/// 单个计数器在滚动窗口内的统计（微秒）
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CallStats {
+    pub avg_us: f32,
+    pub max_us: u32,
+}
+
+/// 最近 METRICS_WINDOW 帧的 Guest 调用统计，以及最近一帧新增的 UI 命令数；
+/// 由 record_guest_metrics 每帧更新，F2 面板与定时日志读取
+#[derive(Resource, Default, Debug)]
+pub struct GuestMetrics {
+    pub keyboard:    CallStats,
+    pub update_game: CallStats,
+    pub ui_event:    CallStats,
+    pub total:       CallStats,
+    /// 最近一帧 wasm_tick 转存到 PendingUiCommands 的命令数
+    pub pending_spawns:    usize,
+    pub pending_mutations: usize,
+    samples: VecDeque<WasmCallMetrics>,
+}
+
+impl GuestMetrics {
+    pub(crate) fn record(&mut self, sample: WasmCallMetrics, pending_spawns: usize, pending_mutations: usize) {
+        if self.samples.len() == METRICS_WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+        self.keyboard    = self.stats(|s| s.keyboard_us);
+        self.update_game = self.stats(|s| s.update_game_us);
+        self.ui_event    = self.stats(|s| s.ui_event_us);
+        self.total       = self.stats(|s| s.total_us);
+        self.pending_spawns = pending_spawns;
+        self.pending_mutations = pending_mutations;
+    }
+
+    fn stats(&self, counter: impl Fn(&WasmCallMetrics) -> u32) -> CallStats {
+        let sum: u64 = self.samples.iter().map(|s| counter(s) as u64).sum();
+        CallStats {
+            avg_us: sum as f32 / self.samples.len().max(1) as f32,
+            max_us: self.samples.iter().map(&counter).max().unwrap_or(0),
+        }
+    }
+
+    /// 参与统计的帧数（不超过 METRICS_WINDOW）
+    pub fn window_len(&self) -> usize {
+        self.samples.len()
+    }
+}
+
+/// 标记：F2 打开的耗时统计文字面板
+#[derive(Component)]
+pub(crate) struct MetricsOverlay;
+
+/// Duration → 微秒，超出 u32 时饱和
+pub(crate) fn micros(duration: Duration) -> u32 {
+    u32::try_from(duration.as_micros()).unwrap_or(u32::MAX)
+}
+
+/// 每帧把 wasm_tick 写入的 WasmCallMetrics 与新增命令数计入 GuestMetrics
+/// 运行在 process_ui_spawn 之前，此时 PendingUiCommands 里恰好是本帧新增的命令
+pub(crate) fn record_guest_metrics(
+    metrics: Res<WasmCallMetrics>,
+    pending: Res<PendingUiCommands>,
+    mut guest: ResMut<GuestMetrics>,
+) {
+    if metrics.is_changed() {
+        guest.record(*metrics, pending.spawns.len(), pending.mutations.len());
+    }
+}
+
+/// 定时输出 GuestMetrics，与 debug_game_state 同频
+pub(crate) fn log_guest_metrics(guest: Res<GuestMetrics>) {
+    println!(
+        "WASM 耗时（{} 帧，平均/最大 μs）- keyboard: {:.1}/{} update: {:.1}/{} ui-event: {:.1}/{} 命令: spawn {} mutation {}",
+        guest.window_len(),
+        guest.keyboard.avg_us, guest.keyboard.max_us,
+        guest.update_game.avg_us, guest.update_game.max_us,
+        guest.ui_event.avg_us, guest.ui_event.max_us,
+        guest.pending_spawns, guest.pending_mutations,
+    );
+}
+
+/// F2 打开 / 关闭耗时统计面板；面板不属于 TS 的 UI，场景切换时不受影响
+pub(crate) fn toggle_metrics_overlay(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    overlays: Query<Entity, With<MetricsOverlay>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F2) {
+        return;
+    }
+    if let Ok(entity) = overlays.get_single() {
+        commands.entity(entity).despawn_recursive();
+        return;
+    }
+    commands.spawn((
+        TextBundle::from_section("", TextStyle { font_size: 16.0, color: Color::YELLOW, ..default() })
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                top:   Val::Px(8.0),
+                right: Val::Px(8.0),
+                ..default()
+            }),
+        ZIndex::Global(i32::MAX),
+        MetricsOverlay,
+    ));
+}
+
+/// 刷新统计面板文字（最近 METRICS_WINDOW 帧的平均 / 最大值）
+pub(crate) fn update_metrics_overlay(guest: Res<GuestMetrics>, mut overlays: Query<&mut Text, With<MetricsOverlay>>) {
+    let Ok(mut text) = overlays.get_single_mut() else { return; };
+    text.sections[0].value = format!(
+        "WASM 耗时（{} 帧，平均/最大 μs）\nprocess-keyboard: {:.1}/{}\nupdate-game: {:.1}/{}\non-ui-event: {:.1}/{}\ntotal: {:.1}/{}\n本帧命令: spawn {} / mutation {}",
+        guest.window_len(),
+        guest.keyboard.avg_us, guest.keyboard.max_us,
+        guest.update_game.avg_us, guest.update_game.max_us,
+        guest.ui_event.avg_us, guest.ui_event.max_us,
+        guest.total.avg_us, guest.total.max_us,
+        guest.pending_spawns, guest.pending_mutations,
+    );
+}
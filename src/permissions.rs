@@ -0,0 +1,109 @@
+// ─── 权限 ─────────────────────────────────────────────────────────────────────
+//
+// 读写文件、剪贴板、音频等能力需要先授予才能调用：WasmPermissionConfig 列出启动时授予的权限，
+// Startup 时写入 PermissionRegistry；之后宿主可随时增删，wasm_tick 每帧同步给各组件。
+// 未授予时对应 Host 函数 trap，TS 可先用 request-permission 查询。
+
+use super::*;
+
+/// 与 WIT 的 permission-type 一一对应；bindgen 生成的枚举没有实现 Hash，无法放进 HashSet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PermissionType {
+    ReadFile,
+    WriteFile,
+    Network,
+    Clipboard,
+    Audio,
+}
+
+impl From<WitPermissionType> for PermissionType {
+    fn from(permission: WitPermissionType) -> Self {
+        match permission {
+            WitPermissionType::ReadFile  => PermissionType::ReadFile,
+            WitPermissionType::WriteFile => PermissionType::WriteFile,
+            WitPermissionType::Network   => PermissionType::Network,
+            WitPermissionType::Clipboard => PermissionType::Clipboard,
+            WitPermissionType::Audio     => PermissionType::Audio,
+        }
+    }
+}
+
+/// 启动时授予的权限；默认授予现有功能需要的全部权限，网络除外
+#[derive(Resource, Debug, Clone)]
+pub struct WasmPermissionConfig {
+    pub granted: Vec<PermissionType>,
+}
+
+impl Default for WasmPermissionConfig {
+    fn default() -> Self {
+        WasmPermissionConfig {
+            granted: vec![
+                PermissionType::ReadFile,
+                PermissionType::WriteFile,
+                PermissionType::Clipboard,
+                PermissionType::Audio,
+            ],
+        }
+    }
+}
+
+/// 当前授予的权限，所有组件共享
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PermissionRegistry {
+    pub granted: HashSet<PermissionType>,
+}
+
+/// Startup：按 WasmPermissionConfig 初始化 PermissionRegistry
+pub(crate) fn apply_permission_config(config: Res<WasmPermissionConfig>, mut registry: ResMut<PermissionRegistry>) {
+    registry.granted = config.granted.iter().copied().collect();
+    println!("[权限] 已授予: {:?}", config.granted);
+}
+
+/// 按组件限制的宿主能力，随 WasmConfig 注册；与 PermissionRegistry 叠加，两者都允许时调用才会成功。
+/// 权限由玩家或宿主随时授予、所有组件共享，能力配置则在注册时固定，用于限制第三方 mod
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapabilityProfile {
+    /// spawn-* 系列与 play-sprite-animation
+    pub can_spawn_ui:   bool,
+    /// play-sound / play-music
+    pub can_play_audio: bool,
+    /// save-to-local-storage
+    pub can_save_data:  bool,
+    /// http-request
+    pub can_http:       bool,
+}
+
+impl CapabilityProfile {
+    /// 内置组件的默认配置：不额外限制，只受 PermissionRegistry 约束
+    pub const FULL: CapabilityProfile = CapabilityProfile {
+        can_spawn_ui: true, can_play_audio: true, can_save_data: true, can_http: true,
+    };
+    /// 只能读取状态、处理输入，不能产生任何外部效果
+    pub const NONE: CapabilityProfile = CapabilityProfile {
+        can_spawn_ui: false, can_play_audio: false, can_save_data: false, can_http: false,
+    };
+
+    pub(crate) fn allows(&self, capability: Capability) -> bool {
+        match capability {
+            Capability::SpawnUi   => self.can_spawn_ui,
+            Capability::PlayAudio => self.can_play_audio,
+            Capability::SaveData  => self.can_save_data,
+            Capability::Http      => self.can_http,
+        }
+    }
+}
+
+impl Default for CapabilityProfile {
+    fn default() -> Self {
+        CapabilityProfile::FULL
+    }
+}
+
+/// CapabilityProfile 的各项，用于 BevyApiError::CapabilityDenied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    SpawnUi,
+    PlayAudio,
+    SaveData,
+    Http,
+}
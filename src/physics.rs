@@ -0,0 +1,65 @@
+// ─── 物理 ─────────────────────────────────────────────────────────────────────
+//
+// set-gravity / apply-impulse 经 PhysicsCommandQueue 交给 process_physics_commands。
+// GameLogicPlugin 不包含物理插件：宿主 App 没有添加 PhysicsPlugins 时没有 Gravity 资源，
+// 命令只输出警告后丢弃。
+
+use super::*;
+
+#[derive(Resource, Default)]
+pub(crate) struct PhysicsCommandQueue {
+    pub(crate) commands: Vec<PhysicsCommand>,
+}
+
+/// 应用 set-player-position 请求；同时清零速度，否则物理引擎会按 wasm_tick 算出的速度继续移动
+pub(crate) fn apply_player_teleport(
+    mut pending: ResMut<PendingPlayerTeleport>,
+    mut game_state: ResMut<GameState>,
+    mut fixed: ResMut<FixedTickState>,
+    mut players: Query<(&mut Transform, &mut LinearVelocity), With<Player>>,
+) {
+    let Some(position) = pending.0.take() else { return; };
+    game_state.player_position = position;
+    fixed.teleport(position);
+    for (mut transform, mut velocity) in players.iter_mut() {
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+        velocity.0 = Vec2::ZERO;
+    }
+}
+
+/// 应用 set-gravity / apply-impulse；同一实体同帧的多个冲量累加后一次施加
+pub(crate) fn process_physics_commands(
+    mut commands: Commands,
+    mut queue: ResMut<PhysicsCommandQueue>,
+    mut gravity: Option<ResMut<Gravity>>,
+    entity_map: Res<UiEntityMap>,
+    mut bodies: Query<Option<&mut ExternalImpulse>, With<RigidBody>>,
+) {
+    if queue.commands.is_empty() {
+        return;
+    }
+    let mut impulses: HashMap<Entity, Vec2> = HashMap::new();
+    for command in queue.commands.drain(..) {
+        match command {
+            PhysicsCommand::SetGravity(value) => match gravity.as_deref_mut() {
+                Some(gravity) => gravity.0 = value,
+                None => warn!("[物理] set-gravity 被忽略：未添加 PhysicsPlugins"),
+            },
+            PhysicsCommand::ApplyImpulse { key, impulse } => {
+                let Some(entity) = entity_map.get_or_warn("ApplyImpulse", &key) else { continue; };
+                if !bodies.contains(entity) {
+                    warn!("[物理] apply-impulse 被忽略：key={} 不是刚体", key);
+                    continue;
+                }
+                *impulses.entry(entity).or_default() += impulse;
+            }
+        }
+    }
+    for (entity, impulse) in impulses {
+        match bodies.get_mut(entity) {
+            Ok(Some(mut existing)) => { existing.apply_impulse(impulse); }
+            _ => { commands.entity(entity).insert(ExternalImpulse::new(impulse).with_persistence(false)); }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+// ─── 实例池 ───────────────────────────────────────────────────────────────────
+//
+// 频繁加载的 mod 组件每次从头编译 + 实例化需要数十毫秒。
+// WasmComponentPool 只编译一次，预先实例化 pool_size 个单组件运行时，acquire / release 复用。
+// 归还时 HostState 队列必须已清空，否则上一位使用者的命令会泄漏给下一位。
+
+use super::*;
+
+/// 单组件运行时池（所有运行时共享同一个 Engine 与已编译的 Component）
+pub struct WasmComponentPool {
+    pub(crate) engine: Engine,
+    pub(crate) linker: Linker<HostState>,
+    pub(crate) component: Component,
+    /// 池中运行时的组件名
+    pub(crate) name: String,
+    pool_size: usize,
+    idle: VecDeque<WasmRuntime>,
+}
+
+/// release 被拒绝的原因，runtime 原样交还给调用方
+pub enum PoolReleaseError {
+    /// instance 的 HostState 仍有未转存的命令
+    UndrainedQueues { instance: String, runtime: Box<WasmRuntime> },
+}
+
+impl std::fmt::Display for PoolReleaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PoolReleaseError::UndrainedQueues { instance, .. } => {
+                write!(f, "实例 {} 的命令队列未清空，拒绝归还", instance)
+            }
+        }
+    }
+}
+
+impl WasmComponentPool {
+    /// 编译组件并预先实例化 pool_size 个运行时
+    pub fn new(config: &Config, name: &str, wasm_bytes: &[u8], pool_size: usize) -> wasmtime::Result<Self> {
+        let runtime = new_runtime(config, Vec::new())?;
+        let component = Component::new(&runtime.engine, wasm_bytes)?;
+        let mut pool = WasmComponentPool {
+            engine: runtime.engine,
+            linker: runtime.linker,
+            component,
+            name: name.to_string(),
+            pool_size,
+            idle: VecDeque::with_capacity(pool_size),
+        };
+        for _ in 0..pool_size {
+            let runtime = pool.instantiate()?;
+            pool.idle.push_back(runtime);
+        }
+        Ok(pool)
+    }
+
+    pub(crate) fn instantiate(&self) -> wasmtime::Result<WasmRuntime> {
+        let instance = instantiate_precompiled(&self.engine, &self.linker, HostState::new(None), &self.component)?;
+        Ok(WasmRuntime {
+            engine:    self.engine.clone(),
+            linker:    new_linker(&self.engine)?,
+            handles:   HashMap::new(),
+            instances: HashMap::from([(self.name.clone(), instance)]),
+            order:     vec![self.name.clone()],
+            profiles:  HashMap::new(),
+            fuel_per_frame: None,
+            rng_seed: None,
+            failed: HashSet::new(),
+            fallback: None,
+        })
+    }
+
+    /// 取出一个空闲运行时；池已耗尽时返回 None
+    pub fn acquire(&mut self) -> Option<WasmRuntime> {
+        self.idle.pop_front()
+    }
+
+    /// 归还运行时；池已满时直接丢弃
+    pub fn release(&mut self, runtime: WasmRuntime) -> Result<(), PoolReleaseError> {
+        let undrained = runtime.instances.iter()
+            .find(|(_, instance)| instance.store.data().has_pending_commands())
+            .map(|(name, _)| name.clone());
+        if let Some(instance) = undrained {
+            return Err(PoolReleaseError::UndrainedQueues { instance, runtime: Box::new(runtime) });
+        }
+        if self.idle.len() < self.pool_size {
+            self.idle.push_back(runtime);
+        }
+        Ok(())
+    }
+
+    pub fn idle_count(&self) -> usize {
+        self.idle.len()
+    }
+}
@@ -0,0 +1,179 @@
+// ─── 录制 / 回放（feature = "replay"）─────────────────────────────────────────
+//
+// 复现 TS 逻辑 bug 需要完全相同的输入与输出序列：
+//   - ReplayRecorder：每帧把键盘输入、点击、帧时间、WASM 产出的 UI 命令、玩家位置
+//     以 bincode 追加写入文件（append-only，崩溃时已写入的帧不会丢失）
+//   - ReplayPlayer（Output）：读入整份录像，wasm_tick 直接使用录制的结果，
+//     完全绕过 WASM 调用，因此回放不依赖当前 wasm 版本
+//   - ReplayPlayer（Input）：只取录像中的输入与帧时间，照常调用 WASM，
+//     用于在修改 TS 后重跑同一段操作；录制与输入回放都固定随机数种子
+//
+// 启用方式（需 `cargo run --features replay`）：
+//   GAME_REPLAY_RECORD=replay.bin  录制
+//   GAME_REPLAY_PLAY=replay.bin    回放结果（优先级最高）
+//   GAME_REPLAY_INPUT=replay.bin   回放输入（优先于录制）
+
+use super::{UiMutationCommand, UiSpawnCommand, WasmRuntime};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, ErrorKind, Write};
+use std::path::Path;
+
+/// 一帧的原始输入（bindgen 生成的 KeyboardInput 不支持 serde，单独定义）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedInput {
+    pub right: bool,
+    pub left:  bool,
+    pub up:    bool,
+    pub down:  bool,
+    /// 本帧按 UiEventConfig 触发的 UI 事件名
+    pub ui_events: Vec<String>,
+    /// 本帧交给 on-entity-clicked 的全局 key
+    pub clicks: Vec<String>,
+}
+
+/// 录制与输入回放使用的随机数种子（WasmRuntime 已设置种子时保留原值）
+pub const REPLAY_RNG_SEED: u64 = 0x5EED;
+
+/// 录像中的一帧
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReplayFrame {
+    pub frame:           u64,
+    pub elapsed_secs:    f64,
+    pub delta_secs:      f32,
+    pub input:           RecordedInput,
+    /// update-game 之后的玩家位置
+    pub player_position: (f32, f32),
+    pub spawns:          Vec<UiSpawnCommand>,
+    pub mutations:       Vec<UiMutationCommand>,
+}
+
+// ─── 录制 ─────────────────────────────────────────────────────────────────────
+
+#[derive(Resource)]
+pub struct ReplayRecorder {
+    writer: BufWriter<File>,
+    next_frame: u64,
+}
+
+impl ReplayRecorder {
+    pub fn create(path: &Path) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(ReplayRecorder { writer: BufWriter::new(file), next_frame: 0 })
+    }
+
+    /// 分配帧号并追加写入；每帧 flush，保证进程崩溃前的帧都已落盘
+    pub fn record(&mut self, mut frame: ReplayFrame) {
+        frame.frame = self.next_frame;
+        self.next_frame += 1;
+        let result = bincode::serialize_into(&mut self.writer, &frame)
+            .map_err(|e| e.to_string())
+            .and_then(|()| self.writer.flush().map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            eprintln!("[录像] 写入第 {} 帧失败: {}", frame.frame, e);
+        }
+    }
+}
+
+// ─── 回放 ─────────────────────────────────────────────────────────────────────
+
+/// 回放录像中的哪一部分
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayMode {
+    /// 直接套用录制的玩家位置与 UI 命令，不调用 WASM
+    Output,
+    /// 用录制的输入与帧时间驱动 WASM
+    Input,
+}
+
+#[derive(Resource)]
+pub struct ReplayPlayer {
+    frames: VecDeque<ReplayFrame>,
+    mode:   ReplayMode,
+}
+
+impl ReplayPlayer {
+    /// 读入整份录像，直到文件末尾；按 Output 模式回放
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        Self::open_with_mode(path, ReplayMode::Output)
+    }
+
+    pub fn open_with_mode(path: &Path, mode: ReplayMode) -> std::io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut frames = VecDeque::new();
+        loop {
+            match bincode::deserialize_from::<_, ReplayFrame>(&mut reader) {
+                Ok(frame) => frames.push_back(frame),
+                Err(e) => match *e {
+                    bincode::ErrorKind::Io(ref io) if io.kind() == ErrorKind::UnexpectedEof => break,
+                    _ => return Err(std::io::Error::new(ErrorKind::InvalidData, e.to_string())),
+                },
+            }
+        }
+        Ok(ReplayPlayer { frames, mode })
+    }
+
+    pub fn replays_output(&self) -> bool {
+        self.mode == ReplayMode::Output
+    }
+
+    /// 取出下一帧；录像播放完毕后返回 None
+    pub fn next_frame(&mut self) -> Option<ReplayFrame> {
+        self.frames.pop_front()
+    }
+
+    /// Input 模式下取出下一帧；Output 模式或播放完毕时返回 None
+    pub fn next_input(&mut self) -> Option<ReplayFrame> {
+        match self.mode {
+            ReplayMode::Input => self.next_frame(),
+            ReplayMode::Output => None,
+        }
+    }
+}
+
+/// 录制与输入回放都需要 WASM 侧的随机数可复现
+fn seed_for_replay(app: &mut App) {
+    if let Some(mut wasm) = app.world.get_non_send_resource_mut::<WasmRuntime>() {
+        if wasm.rng_seed.is_none() {
+            wasm.seed_wasm_rng(REPLAY_RNG_SEED);
+        }
+    }
+}
+
+// ─── 插件：根据环境变量启用录制或回放 ─────────────────────────────────────────
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        if let Ok(path) = std::env::var("GAME_REPLAY_PLAY") {
+            match ReplayPlayer::open(Path::new(&path)) {
+                Ok(player) => {
+                    println!("[录像] 回放 {}，共 {} 帧", path, player.frames.len());
+                    app.insert_resource(player);
+                }
+                Err(e) => eprintln!("[录像] 无法读取 {}: {}", path, e),
+            }
+        } else if let Ok(path) = std::env::var("GAME_REPLAY_INPUT") {
+            match ReplayPlayer::open_with_mode(Path::new(&path), ReplayMode::Input) {
+                Ok(player) => {
+                    println!("[录像] 按输入回放 {}，共 {} 帧", path, player.frames.len());
+                    app.insert_resource(player);
+                    seed_for_replay(app);
+                }
+                Err(e) => eprintln!("[录像] 无法读取 {}: {}", path, e),
+            }
+        } else if let Ok(path) = std::env::var("GAME_REPLAY_RECORD") {
+            match ReplayRecorder::create(Path::new(&path)) {
+                Ok(recorder) => {
+                    println!("[录像] 录制到 {}", path);
+                    app.insert_resource(recorder);
+                    seed_for_replay(app);
+                }
+                Err(e) => eprintln!("[录像] 无法创建 {}: {}", path, e),
+            }
+        }
+    }
+}
@@ -0,0 +1,264 @@
+// ─── 共享 Resource ────────────────────────────────────────────────────────────
+//
+// wasm_tick、UI 命令处理与场景切换共用的状态：GameState、PendingUiCommands、UiEntityMap。
+
+use super::*;
+
+// ─── UI 命令中转 Resource ─────────────────────────────────────────────────────
+//
+// wasm_tick 产出的 UI 命令先存放在此 Resource 中，
+// process_ui_spawn / process_ui_mutations 从此处消费，完全不接触 WasmRuntime。
+
+#[derive(Resource, Default)]
+pub(crate) struct PendingUiCommands {
+    pub(crate) spawns:    Vec<UiSpawnCommand>,
+    pub(crate) mutations: Vec<UiMutationCommand>,
+}
+
+// ─── UI Key → Entity 映射表 Resource ──────────────────────────────────────────
+
+/// 双向一一映射：两个 HashMap 只能通过 insert / remove / retain 同步修改，
+/// 覆盖已有的 key 或 value 时会先解除旧配对，反查不会指向过期条目
+pub(crate) struct BiMap<K, V> {
+    forward: HashMap<K, V>,
+    reverse: HashMap<V, K>,
+}
+
+impl<K, V> Default for BiMap<K, V> {
+    fn default() -> Self {
+        BiMap { forward: HashMap::new(), reverse: HashMap::new() }
+    }
+}
+
+impl<K: Clone + Eq + std::hash::Hash, V: Copy + Eq + std::hash::Hash> BiMap<K, V> {
+    pub(crate) fn insert(&mut self, key: K, value: V) {
+        if let Some(old_value) = self.forward.remove(&key) {
+            self.reverse.remove(&old_value);
+        }
+        if let Some(old_key) = self.reverse.remove(&value) {
+            self.forward.remove(&old_key);
+        }
+        self.forward.insert(key.clone(), value);
+        self.reverse.insert(value, key);
+    }
+
+    pub(crate) fn remove(&mut self, key: &K) -> Option<V> {
+        let value = self.forward.remove(key)?;
+        self.reverse.remove(&value);
+        Some(value)
+    }
+
+    pub(crate) fn retain(&mut self, mut keep: impl FnMut(&K, &V) -> bool) {
+        let reverse = &mut self.reverse;
+        self.forward.retain(|key, value| {
+            let kept = keep(key, value);
+            if !kept {
+                reverse.remove(value);
+            }
+            kept
+        });
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.forward.clear();
+        self.reverse.clear();
+    }
+
+    pub(crate) fn get<Q: ?Sized + Eq + std::hash::Hash>(&self, key: &Q) -> Option<&V>
+    where
+        K: std::borrow::Borrow<Q>,
+    {
+        self.forward.get(key)
+    }
+
+    pub(crate) fn get_by_value(&self, value: &V) -> Option<&K> {
+        self.reverse.get(value)
+    }
+
+    pub(crate) fn contains_key<Q: ?Sized + Eq + std::hash::Hash>(&self, key: &Q) -> bool
+    where
+        K: std::borrow::Borrow<Q>,
+    {
+        self.forward.contains_key(key)
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.forward.iter()
+    }
+}
+
+/// TS 用 string key 引用实体，Rust 侧维护 key ↔ Entity 双向映射
+/// 以及 spawn 时给出的父子关系，despawn 按关系清理整棵子树，不依赖 key 命名
+#[derive(Resource, Default)]
+pub(crate) struct UiEntityMap {
+    pub(crate) map: BiMap<String, Entity>,
+    /// 父 key → 直接子节点 key
+    pub(crate) children: HashMap<String, Vec<String>>,
+    /// 子 key → 父 key，despawn 子节点时据此从父节点的列表中摘除
+    pub(crate) parents: HashMap<String, String>,
+}
+
+impl UiEntityMap {
+    /// 按 key 查找实体；找不到时输出带操作名的警告并返回 None
+    pub(crate) fn get_or_warn(&self, op: &str, key: &str) -> Option<Entity> {
+        let entity = self.map.get(key).copied();
+        if entity.is_none() {
+            warn!("[UI] {} 失败：找不到 key={}", op, key);
+        }
+        entity
+    }
+
+    /// 反查实体对应的 key；不是 TS 创建的实体返回 None
+    pub(crate) fn key_for(&self, entity: Entity) -> Option<&str> {
+        self.map.get_by_value(&entity).map(String::as_str)
+    }
+
+    /// 注册实体；有父节点时同时记录父子关系
+    pub(crate) fn insert(&mut self, key: String, entity: Entity, parent_key: Option<&str>) {
+        // 同一 key 重复 spawn 时先从旧父节点摘除
+        self.detach(&key);
+        if let Some(parent_key) = parent_key {
+            self.children.entry(parent_key.to_string()).or_default().push(key.clone());
+            self.parents.insert(key.clone(), parent_key.to_string());
+        }
+        self.map.insert(key, entity);
+    }
+
+    /// 移除 key 及其全部后代的映射与父子关系，返回实际移除的 key（先父后子）
+    pub(crate) fn remove_subtree(&mut self, key: &str) -> Vec<String> {
+        self.detach(key);
+        let mut removed = Vec::new();
+        let mut stack = vec![key.to_string()];
+        while let Some(current) = stack.pop() {
+            self.parents.remove(&current);
+            if let Some(children) = self.children.remove(&current) {
+                stack.extend(children);
+            }
+            if self.map.remove(&current).is_some() {
+                removed.push(current);
+            }
+        }
+        removed
+    }
+
+    /// 把 key 挂到 new_parent 下（None 为根节点），返回是否成功
+    /// new_parent 是 key 自身或其后代时会形成环，拒绝并保持原状
+    pub(crate) fn reparent(&mut self, key: &str, new_parent: Option<&str>) -> bool {
+        if let Some(new_parent) = new_parent {
+            let mut current = Some(new_parent);
+            while let Some(ancestor) = current {
+                if ancestor == key {
+                    return false;
+                }
+                current = self.parents.get(ancestor).map(String::as_str);
+            }
+        }
+        self.detach(key);
+        if let Some(new_parent) = new_parent {
+            self.children.entry(new_parent.to_string()).or_default().push(key.to_string());
+            self.parents.insert(key.to_string(), new_parent.to_string());
+        }
+        true
+    }
+
+    /// 从父节点的子节点列表中摘除 key
+    pub(crate) fn detach(&mut self, key: &str) {
+        let Some(parent_key) = self.parents.remove(key) else { return; };
+        if let Some(siblings) = self.children.get_mut(&parent_key) {
+            siblings.retain(|k| k != key);
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.map.clear();
+        self.children.clear();
+        self.parents.clear();
+    }
+
+    /// 只保留 roots 及其后代的映射，其余全部移除（场景切换时保留常驻 HUD）
+    pub(crate) fn retain_subtrees(&mut self, roots: &HashSet<String>) {
+        let mut kept = HashSet::new();
+        let mut stack: Vec<String> = roots.iter().filter(|k| self.map.contains_key(*k)).cloned().collect();
+        while let Some(current) = stack.pop() {
+            if let Some(children) = self.children.get(&current) {
+                stack.extend(children.iter().cloned());
+            }
+            kept.insert(current);
+        }
+        self.map.retain(|k, _| kept.contains(k));
+        self.children.retain(|k, _| kept.contains(k));
+        self.parents.retain(|k, _| kept.contains(k));
+    }
+}
+
+// ─── Bevy 游戏状态 ────────────────────────────────────────────────────────────
+
+#[derive(Resource)]
+pub(crate) struct GameState {
+    pub(crate) player_position: Vec2,
+    pub(crate) player_speed:    f32,
+    /// 玩家生命值，由宿主原生系统修改；Guest 只能通过 query-game-state 读取
+    pub(crate) player_health:   f32,
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        GameState {
+            player_position: Vec2::ZERO,
+            player_speed:    200.0,
+            player_health:   100.0,
+        }
+    }
+}
+
+/// set-player-position 请求，多组件同帧请求时按 tick 顺序最后一个生效；apply_player_teleport 消费
+#[derive(Resource, Default)]
+pub(crate) struct PendingPlayerTeleport(pub(crate) Option<Vec2>);
+
+/// set-focus 请求（Some(None) 为清除焦点），同帧多次请求时最后一个生效；apply_focus_request 消费
+#[derive(Resource, Default)]
+pub(crate) struct PendingFocusRequest(pub(crate) Option<Option<String>>);
+
+// ─── 队列上限 ─────────────────────────────────────────────────────────────────
+//
+// HostState 的 UI 命令队列每帧都会被 wasm_tick 清空，因此限制单个组件单帧的入队数量
+// 即可约束 PendingUiCommands 的规模。超出上限的命令被丢弃，计入 DroppedCommandCounts。
+
+/// 单个组件单帧最多可入队的 UI 命令数
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct QueueLimits {
+    pub max_pending_spawns:    usize,
+    pub max_pending_mutations: usize,
+}
+
+impl Default for QueueLimits {
+    fn default() -> Self {
+        QueueLimits {
+            max_pending_spawns:    4096,
+            max_pending_mutations: 16384,
+        }
+    }
+}
+
+/// 启动以来因超出 QueueLimits 被丢弃的命令数（所有组件之和）
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct DroppedCommandCounts {
+    pub spawns:    u64,
+    pub mutations: u64,
+}
+
+// ─── 游戏时间快照 Resource ────────────────────────────────────────────────────
+//
+// wasm_tick 开始时写入，同时拷贝到 HostState，TS 通过 get-time 读取。
+
+#[derive(Resource, Default, Clone, Copy, Debug)]
+pub(crate) struct GameTimestamp {
+    pub(crate) elapsed_secs: f64,
+    pub(crate) delta_secs:   f32,
+    pub(crate) frame_count:  u64,
+}
+
+/// wasm_tick 的执行次数（回放、暂停时照常计数），TS 通过 current-frame 读取。
+/// 与 game-time.frame-count 不同，它不随组件热重载、reset 归零，可用于对齐录像与日志
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameCounter(pub u64);
@@ -0,0 +1,186 @@
+// ─── WASM 调用重试 ────────────────────────────────────────────────────────────
+//
+// on-ui-event、on-entity-clicked 这类通知只在事件发生的那一帧送达，调用失败（fuel 耗尽、Guest trap 等）
+// 后即丢失。wasm_tick 把失败的调用放入 RetryQueue，之后每帧在该组件的其他调用之前，
+// 取出退避已到期的条目重试：第 n 次重试（n 从 0 开始）在上次失败后 RETRY_BASE_DELAY × 2ⁿ 进行，
+// 重试 MAX_RETRY_ATTEMPTS 次仍失败则丢弃并发出 WasmCallFailed。
+// Guest trap（含 fuel 耗尽）后实例不能再进入，直接重试必然失败；因此重试前先用 wit-hash 探测，
+// 实例失效时以同一组件重新实例化（沿用 HostState，Guest 内部状态丢失）后再重试。
+// 有返回值或依赖帧顺序的调用（process-keyboard、update-game 等）不重试；JS 回退后端不重试。
+
+use super::*;
+use wasmtime::Store;
+
+/// 首次失败后最多重试的次数
+pub const MAX_RETRY_ATTEMPTS: u8 = 3;
+
+/// 第一次重试前的等待时间，之后每次翻倍
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(50);
+
+/// 队列上限；组件持续出错时新的失败调用直接丢弃，不再排队
+const MAX_PENDING_RETRIES: usize = 256;
+
+/// 可以推迟执行的 Guest 调用，component 为组件名
+#[derive(Debug, Clone, PartialEq)]
+pub enum WasmCall {
+    OnUiEvent { component: String, event: String },
+    OnEntityClicked { component: String, key: String },
+}
+
+impl WasmCall {
+    pub fn component(&self) -> &str {
+        match self {
+            WasmCall::OnUiEvent { component, .. } | WasmCall::OnEntityClicked { component, .. } => component,
+        }
+    }
+
+    pub(crate) fn invoke(&self, game_world: &GameWorld, store: &mut Store<HostState>) -> wasmtime::Result<()> {
+        match self {
+            WasmCall::OnUiEvent { event, .. } => game_world.interface0.call_on_ui_event(store, event),
+            WasmCall::OnEntityClicked { key, .. } => game_world.interface0.call_on_entity_clicked(store, key),
+        }
+    }
+}
+
+/// 重试 MAX_RETRY_ATTEMPTS 次后仍失败的调用，error 为最后一次的错误
+#[derive(Event, Debug, Clone)]
+pub struct WasmCallFailed {
+    pub call:  WasmCall,
+    pub error: String,
+}
+
+/// 等待重试的调用：(调用, 已重试失败的次数, 可以重试的最早时间)
+#[derive(Resource, Default)]
+pub struct RetryQueue {
+    pub entries: VecDeque<(WasmCall, u8, Instant)>,
+}
+
+impl RetryQueue {
+    /// 在 now 之后按 attempts 对应的退避时间排队；首次失败时 attempts 为 0
+    pub(crate) fn schedule(&mut self, call: WasmCall, attempts: u8, now: Instant) {
+        if self.entries.len() >= MAX_PENDING_RETRIES {
+            eprintln!("[WASM] 重试队列已满（上限 {}），丢弃 {:?}", MAX_PENDING_RETRIES, call);
+            return;
+        }
+        let delay = RETRY_BASE_DELAY * 2u32.pow(attempts as u32);
+        self.entries.push_back((call, attempts, now + delay));
+    }
+
+    /// component 是否有退避已到期的条目
+    pub(crate) fn has_due(&self, component: &str, now: Instant) -> bool {
+        self.entries.iter().any(|(call, _, at)| call.component() == component && *at <= now)
+    }
+
+    /// 实例无法恢复时放弃 component 的到期条目，全部作为失败返回
+    pub(crate) fn fail_due(&mut self, component: &str, now: Instant, error: &str) -> Vec<WasmCallFailed> {
+        let (due, pending): (VecDeque<_>, VecDeque<_>) = std::mem::take(&mut self.entries)
+            .into_iter()
+            .partition(|(call, _, at)| call.component() == component && *at <= now);
+        self.entries = pending;
+        due.into_iter().map(|(call, _, _)| WasmCallFailed { call, error: error.to_string() }).collect()
+    }
+
+    /// 重试 component 的到期条目，返回次数用尽的调用；未到期的和其他组件的条目保持原顺序
+    pub(crate) fn retry_due(
+        &mut self,
+        component: &str,
+        now: Instant,
+        mut invoke: impl FnMut(&WasmCall) -> wasmtime::Result<()>,
+    ) -> Vec<WasmCallFailed> {
+        let (due, pending): (VecDeque<_>, VecDeque<_>) = std::mem::take(&mut self.entries)
+            .into_iter()
+            .partition(|(call, _, at)| call.component() == component && *at <= now);
+        self.entries = pending;
+
+        let mut exhausted = Vec::new();
+        for (call, attempts, _) in due {
+            let Err(e) = invoke(&call) else { continue; };
+            let attempts = attempts + 1;
+            if attempts >= MAX_RETRY_ATTEMPTS {
+                exhausted.push(WasmCallFailed { call, error: e.to_string() });
+            } else {
+                self.schedule(call, attempts, now);
+            }
+        }
+        exhausted
+    }
+}
+
+/// 探测实例是否仍可调用，已因 trap 失效时以同一组件重新实例化，返回是否重新实例化。
+/// 新实例沿用原 HostState（快照、命名空间、待处理命令）；实例化失败时原实例保持不变
+pub(crate) fn revive_trapped_instance(
+    engine: &Engine,
+    linker: &Linker<HostState>,
+    instance: &mut ComponentInstance,
+) -> wasmtime::Result<bool> {
+    if instance.game_world.interface0.call_wit_hash(&mut instance.store).is_ok() {
+        return Ok(false);
+    }
+    let old_state = instance.store.data();
+    let mut host_state = HostState::new(old_state.key_namespace.clone());
+    host_state.capabilities = old_state.capabilities;
+    let mut fresh = instantiate_precompiled(engine, linker, host_state, &instance.component)?;
+    std::mem::swap(fresh.store.data_mut(), instance.store.data_mut());
+    *instance = fresh;
+    Ok(true)
+}
+
+pub(crate) fn add_retry(app: &mut App) {
+    app.init_resource::<RetryQueue>().add_event::<WasmCallFailed>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_call_is_retried_with_backoff_then_reported() {
+        let start = Instant::now();
+        let ms = |n: u64| start + Duration::from_millis(n);
+        let call = WasmCall::OnUiEvent { component: "hud".to_string(), event: "toggle_panel".to_string() };
+        let mut queue = RetryQueue::default();
+        queue.schedule(call.clone(), 0, start);
+
+        let attempts = std::cell::Cell::new(0);
+        let fail = |_: &WasmCall| {
+            attempts.set(attempts.get() + 1);
+            Err(wasmtime::Error::msg("all fuel consumed"))
+        };
+        // 退避未到期、或不是该组件时不重试
+        assert!(queue.retry_due("hud", ms(10), &fail).is_empty());
+        assert!(queue.retry_due("ai", ms(1000), &fail).is_empty());
+        assert_eq!(attempts.get(), 0);
+
+        // 50ms、再 100ms、再 200ms 后各重试一次，第三次失败后放弃
+        assert!(queue.retry_due("hud", ms(50), &fail).is_empty());
+        assert!(queue.retry_due("hud", ms(100), &fail).is_empty());
+        assert!(queue.retry_due("hud", ms(150), &fail).is_empty());
+        let failed = queue.retry_due("hud", ms(350), &fail);
+        assert_eq!(attempts.get(), 3);
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].call, call);
+        assert!(queue.entries.is_empty());
+
+        // 重试成功后出队，不再报告
+        queue.schedule(call, 0, start);
+        assert!(queue.retry_due("hud", ms(50), |_| Ok(())).is_empty());
+        assert!(queue.entries.is_empty());
+    }
+
+    #[test]
+    fn failed_due_entries_are_reported_without_invoking() {
+        let start = Instant::now();
+        let call = WasmCall::OnEntityClicked { component: "hud".to_string(), key: "button".to_string() };
+        let mut queue = RetryQueue::default();
+        queue.schedule(call.clone(), 0, start);
+        assert!(!queue.has_due("hud", start));
+        assert!(queue.has_due("hud", start + RETRY_BASE_DELAY));
+        assert!(!queue.has_due("ai", start + RETRY_BASE_DELAY));
+
+        let failed = queue.fail_due("hud", start + RETRY_BASE_DELAY, "instantiate failed");
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].call, call);
+        assert_eq!(failed[0].error, "instantiate failed");
+        assert!(queue.entries.is_empty());
+    }
+}
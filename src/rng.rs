@@ -0,0 +1,25 @@
+// ─── 随机数 ───────────────────────────────────────────────────────────────────
+//
+// TS 的 Math.random() 无法复现；get-random-* 使用 HostState 中每个组件独立的 WasmRng，
+// 种子默认取自系统熵，测试与回放可通过 WasmRuntime::seed_wasm_rng 固定。
+
+use super::*;
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+#[derive(Clone)]
+pub(crate) struct WasmRng(pub(crate) SmallRng);
+
+impl WasmRng {
+    pub(crate) fn from_entropy() -> Self {
+        WasmRng(SmallRng::from_entropy())
+    }
+
+    /// 把组件名混入种子，避免多个组件得到完全相同的序列（FNV-1a，跨平台、跨版本稳定）
+    pub(crate) fn seeded(seed: u64, component: &str) -> Self {
+        let mixed = component.bytes().fold(seed ^ 0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        });
+        WasmRng(SmallRng::seed_from_u64(mixed))
+    }
+}
@@ -0,0 +1,121 @@
+// ─── 场景 ─────────────────────────────────────────────────────────────────────
+//
+// setup 创建相机与玩家；TS 请求的场景切换由 handle_scene_transitions 在 UI 命令全部生效后执行。
+
+use super::*;
+use bevy::render::camera::ClearColorConfig;
+use bevy::render::view::RenderLayers;
+use bevy::ui::IsDefaultUiCamera;
+use bevy_xpbd_2d::prelude::{Collider, LockedAxes};
+
+#[derive(Component)]
+pub(crate) struct Player;
+
+/// 标记：该实体是由 TS 通过 bevy-api 创建的根实体（UI 面板、图片、动画精灵），场景切换时统一清理
+#[derive(Component)]
+pub(crate) struct WitUiPanel;
+
+/// Startup：创建背景 UI 相机、主相机与玩家
+pub(crate) fn setup(mut commands: Commands, mut stack_order: ResMut<UiStackOrder>) {
+    // 背景 UI 相机先绘制负层级的 UI，只看第 1 层因而不渲染游戏世界；主相机随后不清屏地绘制世界与其余 UI
+    let background = commands.spawn((
+        Camera2dBundle { camera: Camera { order: -1, ..default() }, ..default() },
+        RenderLayers::layer(1),
+    )).id();
+    stack_order.background_camera = Some(background);
+    commands.spawn((
+        Camera2dBundle { camera: Camera { clear_color: ClearColorConfig::None, ..default() }, ..default() },
+        MainCamera,
+        IsDefaultUiCamera,
+    ));
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::BLUE,
+                custom_size: Some(Vec2::new(50.0, 50.0)),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::ZERO),
+            ..default()
+        },
+        Player,
+        // 位置由物理引擎积分：wasm_tick 只写入速度，碰撞响应交给 bevy_xpbd
+        RigidBody::Dynamic,
+        Collider::rectangle(50.0, 50.0),
+        LockedAxes::ROTATION_LOCKED,
+        LinearVelocity::ZERO,
+    ));
+
+    commands.insert_resource(GameState::default());
+
+    println!("游戏初始化完成！按 E 键打开/关闭 UI 面板");
+}
+
+pub(crate) fn debug_game_state(game_state: Res<GameState>) {
+    println!(
+        "游戏状态 - 位置: ({:.1}, {:.1})",
+        game_state.player_position.x,
+        game_state.player_position.y,
+    );
+}
+
+// ─── 场景切换 ─────────────────────────────────────────────────────────────────
+//
+// wasm_tick 把 HostState 中的 SceneTransitionCommand 转存到 PendingSceneTransitions；
+// handle_scene_transitions 清理 UI、重置 GameState 后发出 SceneLoaded，
+// 下一帧 wasm_tick 据此调用各组件的 on-scene-load，让 TS 初始化场景状态。
+
+#[derive(Resource, Default)]
+pub(crate) struct PendingSceneTransitions {
+    pub(crate) transitions: Vec<SceneTransitionCommand>,
+}
+
+/// 场景加载完成事件，携带场景名
+#[derive(Event, Debug, Clone)]
+pub(crate) struct SceneLoaded(pub(crate) String);
+
+/// 通过 spawn-persistent-panel 创建的面板 key，场景切换时连同子树一起保留
+#[derive(Resource, Default)]
+pub(crate) struct PersistentUiKeys(pub(crate) HashSet<String>);
+
+/// 执行场景切换：销毁常驻面板以外所有 TS 创建的 UI、清理映射表、重置玩家状态，然后发出 SceneLoaded
+/// 运行在 process_ui_mutations 之后，本帧的 UI 命令已全部生效
+pub(crate) fn handle_scene_transitions(
+    mut commands: Commands,
+    mut pending_scenes: ResMut<PendingSceneTransitions>,
+    // 只取根面板：嵌套面板会随父节点一起被 despawn_recursive 清理
+    panels: Query<Entity, (With<WitUiPanel>, Without<Parent>)>,
+    mut entity_map: ResMut<UiEntityMap>,
+    mut persistent_keys: ResMut<PersistentUiKeys>,
+    mut game_state: ResMut<GameState>,
+    mut players: Query<(&mut Transform, Option<&mut LinearVelocity>), With<Player>>,
+    mut scene_loaded: EventWriter<SceneLoaded>,
+) {
+    for transition in pending_scenes.transitions.drain(..) {
+        let SceneTransitionCommand::Load(name) = transition;
+
+        // 已被 despawn 的常驻 key 不再保留；常驻面板挂在普通面板下时会随父节点一起销毁
+        persistent_keys.0.retain(|key| entity_map.map.contains_key(key));
+        let kept: HashSet<Entity> = persistent_keys.0.iter()
+            .filter_map(|key| entity_map.map.get(key).copied())
+            .collect();
+        for entity in panels.iter().filter(|e| !kept.contains(e)) {
+            commands.entity(entity).despawn_recursive();
+        }
+        entity_map.retain_subtrees(&persistent_keys.0);
+
+        *game_state = GameState::default();
+        for (mut transform, velocity) in players.iter_mut() {
+            transform.translation.x = game_state.player_position.x;
+            transform.translation.y = game_state.player_position.y;
+            // 清零速度，避免本帧物理步把玩家从出生点带走
+            if let Some(mut velocity) = velocity {
+                velocity.0 = Vec2::ZERO;
+            }
+        }
+
+        println!("[场景] 切换到 {}", name);
+        scene_loaded.send(SceneLoaded(name));
+    }
+}
@@ -0,0 +1,72 @@
+// ─── 共享变量与事件总线 ───────────────────────────────────────────────────────
+//
+// 宿主与各组件共用的数值黑板 SharedState，以及把异步事件交给各组件 on-event 的 WasmEventBus。
+
+use super::*;
+
+// ─── 共享变量 ─────────────────────────────────────────────────────────────────
+//
+// 宿主与 TS 共用的数值黑板：原生系统（音频、粒子等）直接读写 SharedState，TS 经 set-var / get-var 访问。
+// 变量名不加组件命名空间，所有组件共享同一份。wasm_tick 在每个组件调用前拷贝快照，
+// 组件调用结束后把本帧写入合并回资源，因此排在后面的组件同帧即可读到前面组件的写入。
+
+/// 变量名 → 数值
+#[derive(Resource, Default, Debug, Clone)]
+pub struct SharedState(pub HashMap<String, f64>);
+
+impl SharedState {
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.0.get(name).copied()
+    }
+
+    pub fn set(&mut self, name: impl Into<String>, value: f64) {
+        self.0.insert(name.into(), value);
+    }
+}
+
+// ─── 事件总线 ─────────────────────────────────────────────────────────────────
+//
+// 异步事件（网络响应、文件加载完成、定时器等）不走同步的 Host 调用返回值，而是投递到
+// WasmEventBus，下一帧 wasm_tick 之前由 dispatch_wasm_events 依次交给各组件的 on-event。
+// 组件用 post-event 投递的事件广播给所有组件（含自身）；类型以 `bevy:` 开头的事件来自宿主，
+// 只发给调用过 subscribe-to-bevy-event 的组件。Rust 侧可直接调用 WasmEventBus::post。
+
+/// 宿主转发事件的类型前缀，subscribe-to-bevy-event 传入的是去掉前缀后的名字
+pub const BEVY_EVENT_PREFIX: &str = "bevy:";
+
+/// 待分发的 (事件类型, 负载)，按投递顺序分发
+#[derive(Resource, Default, Debug)]
+pub struct WasmEventBus(pub VecDeque<(String, Vec<u8>)>);
+
+impl WasmEventBus {
+    pub fn post(&mut self, event_type: impl Into<String>, payload: Vec<u8>) {
+        self.0.push_back((event_type.into(), payload));
+    }
+}
+
+/// 把 Bevy 事件转为 `bevy:` 事件投递到总线：目前转发 SceneLoaded（负载为 UTF-8 场景名）
+pub(crate) fn forward_bevy_events(mut scene_loaded: EventReader<SceneLoaded>, mut bus: ResMut<WasmEventBus>) {
+    for SceneLoaded(scene) in scene_loaded.read() {
+        bus.post(format!("{}scene-loaded", BEVY_EVENT_PREFIX), scene.clone().into_bytes());
+    }
+}
+
+/// 清空总线并按 tick 顺序把事件交给各组件；on-event 中产生的命令留在 HostState，由随后的 wasm_tick 转存
+pub(crate) fn dispatch_wasm_events(mut bus: ResMut<WasmEventBus>, mut wasm: NonSendMut<WasmRuntime>) {
+    if bus.0.is_empty() {
+        return;
+    }
+    let events: Vec<(String, Vec<u8>)> = bus.0.drain(..).collect();
+    let WasmRuntime { ref order, ref mut instances, .. } = *wasm;
+    for name in order {
+        let Some(ComponentInstance { store, game_world, .. }) = instances.get_mut(name) else { continue; };
+        for (event_type, payload) in &events {
+            if !store.data().wants_event(event_type) {
+                continue;
+            }
+            if let Err(e) = game_world.interface0.call_on_event(&mut *store, event_type, payload) {
+                eprintln!("[事件] on-event WASM 错误 [{}] {}: {}", name, event_type, e);
+            }
+        }
+    }
+}
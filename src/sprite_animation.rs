@@ -0,0 +1,102 @@
+// ─── 精灵动画 ─────────────────────────────────────────────────────────────────
+//
+// play-sprite-animation 的精灵表加载完成后切分为 TextureAtlas，animate_sprites 按 fps 推进帧，
+// 非循环动画播完后发出 AnimationFinished。
+
+use super::*;
+
+/// 单个精灵图集的帧数上限（columns × rows）
+pub(crate) const MAX_SPRITE_FRAMES: u64 = 4_096;
+
+/// 精灵图集动画状态，由 animate_sprites 按 Time 推进 TextureAtlas.index
+#[derive(Component, Debug, Clone, PartialEq)]
+pub(crate) struct SpriteAnimation {
+    /// 当前播放区间（闭区间）
+    pub(crate) first: usize,
+    pub(crate) last: usize,
+    /// 每秒帧数；0 表示暂停
+    pub(crate) fps: f32,
+    /// 距上次换帧累计的时间
+    pub(crate) elapsed_secs: f32,
+    /// 图集总帧数（columns × rows），set-animation 的区间会被截断到此范围内
+    pub(crate) frame_count: usize,
+    /// 为 false 时播完 last 帧后停在该帧，移除本组件并发出 AnimationFinished
+    pub(crate) looping: bool,
+}
+
+/// 标记：play-sprite-animation 的精灵表仍在加载；加载完成后 start_pending_sprite_sheets
+/// 按图片尺寸切分 rows × cols 帧，换成 TextureAtlas + SpriteAnimation
+#[derive(Component, Debug, Clone, PartialEq)]
+pub(crate) struct PendingSpriteSheet {
+    pub(crate) rows: u32,
+    pub(crate) cols: u32,
+    pub(crate) fps: f32,
+    pub(crate) looping: bool,
+}
+
+/// 非循环动画播放完毕，携带全局 key；下一帧 wasm_tick 调用所属组件的 on-animation-complete
+#[derive(Event, Debug, Clone, PartialEq)]
+pub(crate) struct AnimationFinished(pub(crate) String);
+
+/// 精灵表加载完成后按图片尺寸切分帧并开始播放；加载失败时保持静态图片
+pub(crate) fn start_pending_sprite_sheets(
+    mut commands: Commands,
+    images: Res<Assets<Image>>,
+    mut atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    pending: Query<(Entity, &UiImage, &PendingSpriteSheet)>,
+) {
+    for (entity, image, sheet) in pending.iter() {
+        let Some(texture) = images.get(&image.texture) else { continue; };
+        let tile = texture.size().as_vec2() / Vec2::new(sheet.cols as f32, sheet.rows as f32);
+        let layout = TextureAtlasLayout::from_grid(tile, sheet.cols as usize, sheet.rows as usize, None, None);
+        let frame_count = sheet.cols as usize * sheet.rows as usize;
+        commands.entity(entity)
+            .remove::<PendingSpriteSheet>()
+            .insert((
+                TextureAtlas { layout: atlas_layouts.add(layout), index: 0 },
+                SpriteAnimation {
+                    first: 0,
+                    last: frame_count - 1,
+                    fps: sheet.fps,
+                    elapsed_secs: 0.0,
+                    frame_count,
+                    looping: sheet.looping,
+                },
+            ));
+    }
+}
+
+/// 按 fps 推进精灵图集帧，到达区间末尾后回到起始帧循环
+pub(crate) fn animate_sprites(
+    mut commands: Commands,
+    time: Res<Time>,
+    entity_map: Res<UiEntityMap>,
+    mut sprites: Query<(Entity, &mut SpriteAnimation, &mut TextureAtlas)>,
+    mut finished: EventWriter<AnimationFinished>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut animation, mut atlas) in sprites.iter_mut() {
+        if animation.fps <= 0.0 {
+            continue;
+        }
+        let frame_secs = 1.0 / animation.fps;
+        animation.elapsed_secs += delta;
+        while animation.elapsed_secs >= frame_secs {
+            animation.elapsed_secs -= frame_secs;
+            let at_end = atlas.index >= animation.last;
+            if at_end && !animation.looping {
+                // 停在最后一帧
+                commands.entity(entity).remove::<SpriteAnimation>();
+                if let Some(key) = entity_map.key_for(entity) {
+                    finished.send(AnimationFinished(key.to_string()));
+                }
+                break;
+            }
+            atlas.index = if at_end || atlas.index < animation.first {
+                animation.first
+            } else {
+                atlas.index + 1
+            };
+        }
+    }
+}
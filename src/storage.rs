@@ -0,0 +1,109 @@
+// ─── 本地存储 ─────────────────────────────────────────────────────────────────
+//
+// save-to-local-storage / load-from-local-storage 在 Host 函数内同步读写 {save_dir}/{slot}.dat。
+// 写入先写临时文件再 rename，中途崩溃不会留下半个存档；{slot}.lock 上的文件锁
+// 防止多个游戏进程同时读写同一槽。所有组件共用同一目录，槽名不加命名空间前缀。
+
+use super::*;
+
+const APP_NAME: &str = "test_game";
+
+/// 存档槽名的最大长度（同时是文件名长度）
+pub(crate) const MAX_SLOT_LEN: usize = 64;
+
+/// 存档目录，默认为系统本地数据目录下的 test_game/saves
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct LocalStorageConfig {
+    pub save_dir: PathBuf,
+}
+
+impl Default for LocalStorageConfig {
+    fn default() -> Self {
+        let save_dir = dirs::data_local_dir()
+            .map_or_else(|| PathBuf::from("saves"), |dir| dir.join(APP_NAME).join("saves"));
+        LocalStorageConfig { save_dir }
+    }
+}
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct StorageLimitConfig {
+    pub max_bytes_per_slot: usize,
+    pub max_slots: usize,
+}
+
+impl Default for StorageLimitConfig {
+    fn default() -> Self {
+        StorageLimitConfig { max_bytes_per_slot: 1024 * 1024, max_slots: 16 }
+    }
+}
+
+/// HostState 持有的存储配置副本（wasm_tick 在配置变化时同步）
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LocalStorage {
+    pub(crate) config: LocalStorageConfig,
+    pub(crate) limits: StorageLimitConfig,
+}
+
+impl LocalStorage {
+    fn slot_path(&self, slot: &str, extension: &str) -> PathBuf {
+        self.config.save_dir.join(format!("{}.{}", slot, extension))
+    }
+
+    /// 参数或配额不合法时返回 Err（Guest 侧 trap）；磁盘读写失败返回 Ok(Err(原因))
+    pub(crate) fn save(&self, slot: &str, data: &[u8]) -> Result<Result<(), String>, BevyApiError> {
+        validate_slot(slot)?;
+        if data.len() > self.limits.max_bytes_per_slot {
+            return Err(BevyApiError::SlotTooLarge(data.len()));
+        }
+        let path = self.slot_path(slot, "dat");
+        if !path.exists() && self.slot_count() >= self.limits.max_slots {
+            return Err(BevyApiError::TooManySlots);
+        }
+        Ok(self.write_locked(slot, &path, data).map_err(|e| format!("写入 {} 失败: {}", path.display(), e)))
+    }
+
+    pub(crate) fn load(&self, slot: &str) -> Result<Option<Vec<u8>>, BevyApiError> {
+        validate_slot(slot)?;
+        let path = self.slot_path(slot, "dat");
+        if !path.exists() {
+            return Ok(None);
+        }
+        let result = self.open_lock(slot).and_then(|lock| {
+            fs2::FileExt::lock_shared(&lock)?;
+            std::fs::read(&path)
+        });
+        match result {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) => {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    eprintln!("[存档] 读取 {} 失败: {}", path.display(), e);
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// 锁在返回的句柄 drop 时释放
+    fn open_lock(&self, slot: &str) -> std::io::Result<std::fs::File> {
+        std::fs::create_dir_all(&self.config.save_dir)?;
+        std::fs::OpenOptions::new().create(true).write(true).open(self.slot_path(slot, "lock"))
+    }
+
+    fn write_locked(&self, slot: &str, path: &Path, data: &[u8]) -> std::io::Result<()> {
+        let lock = self.open_lock(slot)?;
+        fs2::FileExt::lock_exclusive(&lock)?;
+        let tmp = self.slot_path(slot, "dat.tmp");
+        let mut file = std::fs::File::create(&tmp)?;
+        std::io::Write::write_all(&mut file, data)?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, path)
+    }
+
+    fn slot_count(&self) -> usize {
+        let Ok(entries) = std::fs::read_dir(&self.config.save_dir) else { return 0; };
+        entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "dat"))
+            .count()
+    }
+}
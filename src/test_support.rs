@@ -0,0 +1,376 @@
+// ─── 测试支持 ─────────────────────────────────────────────────────────────────
+//
+// 供单元测试与 tests/ 下的集成测试共用：夹具组件、运行时构建与 Guest 调用封装。
+// 夹具由 build.rs 从 tests/fixtures/game_logic.wat 编码生成，缺失即视为构建错误，
+// 不会像读取 assets/game_logic.wasm 那样因为没有运行 npm run build 而静默跳过。
+// 只在单元测试与 test-support feature 下编译；tests/ 经 Cargo.toml 中对自身的 dev-dependency 开启该 feature。
+
+use super::*;
+use bevy::ecs::system::SystemId;
+
+pub use crate::exports::game::logic::game_logic::{KeyboardInput, PlayerState, UpdateResult};
+pub use crate::game::logic::bevy_api::Host as BevyApiHost;
+#[cfg(feature = "replay")]
+pub use crate::replay::{ReplayMode, ReplayPlayer, ReplayRecorder, REPLAY_RNG_SEED};
+
+/// build.rs 生成的夹具组件（实现 game-world，wit-hash 与当前宿主一致）
+pub const FIXTURE_PATH: &str = concat!(env!("OUT_DIR"), "/fixtures/game_logic.wasm");
+
+/// 同一夹具去掉 hot-reload 导出、按 game-world 编码的版本
+pub const MINIMAL_FIXTURE_PATH: &str = concat!(env!("OUT_DIR"), "/fixtures/game_logic_minimal.wasm");
+
+/// 读取夹具组件字节；文件不存在时直接 panic，测试不会空跑通过
+pub fn fixture_bytes() -> Vec<u8> {
+    read_fixture(FIXTURE_PATH)
+}
+
+pub fn minimal_fixture_bytes() -> Vec<u8> {
+    read_fixture(MINIMAL_FIXTURE_PATH)
+}
+
+fn read_fixture(path: &str) -> Vec<u8> {
+    std::fs::read(path).unwrap_or_else(|e| panic!("缺少测试夹具 {}（{}），请重新执行 cargo build", path, e))
+}
+
+/// 测试用 wasmtime 配置：开启组件模型与 epoch 中断（与 WasmPlugin 一致）
+pub fn component_config() -> Config {
+    let mut config = Config::new();
+    config.wasm_component_model(true);
+    config.epoch_interruption(true);
+    config
+}
+
+/// 以夹具构建只含 game_logic 一个组件的运行时
+pub fn fixture_runtime() -> WasmRuntime {
+    build_runtime(&fixture_bytes(), &component_config()).expect("构建运行时失败")
+}
+
+/// 以同一个夹具构建多个组件的运行时，tick 顺序即 names 的顺序；
+/// 与 WasmPlugin 加载多个组件时一致，每个组件的 UI key 以组件名为命名空间
+pub fn fixture_runtime_with(names: &[&str]) -> WasmRuntime {
+    let mut runtime = new_runtime(&component_config(), names.iter().map(|name| name.to_string()).collect())
+        .expect("创建运行时失败");
+    let component = Component::new(&runtime.engine, fixture_bytes()).expect("编译夹具失败");
+    for &name in names {
+        let namespace = if names.len() > 1 { Some(name.to_string()) } else { None };
+        let instance = instantiate_precompiled(&runtime.engine, &runtime.linker, HostState::new(namespace), &component)
+            .expect("实例化夹具失败");
+        runtime.instances.insert(name.to_string(), instance);
+    }
+    runtime
+}
+
+/// 按热重载流程把组件 name 替换为 wasm_bytes：旧实例 save-state，新实例 restore-state
+pub fn reload_component(runtime: &mut WasmRuntime, name: &str, wasm_bytes: &[u8]) -> wasmtime::Result<()> {
+    let component = Component::new(&runtime.engine, wasm_bytes)?;
+    let WasmRuntime { ref engine, ref linker, ref mut instances, .. } = *runtime;
+    let instance = instances.get_mut(name).unwrap_or_else(|| panic!("缺少 {} 实例", name));
+    reload_instance(engine, linker, name, instance, &component)
+}
+
+/// 以 bytes 编译组件，放入 WasmRuntimeResources 的缓存（与加载完成时 WasmPlugin 的写入相同）
+pub fn cache_component(resources: &mut WasmRuntimeResources, name: &str, bytes: &[u8]) {
+    let component = Component::new(&resources.engine, bytes).expect("编译组件失败");
+    resources.components.insert(name.to_string(), component);
+}
+
+/// 退出时单个组件 on-shutdown 的时限
+pub const SHUTDOWN_TIMEOUT: Duration = crate::SHUTDOWN_TIMEOUT;
+
+pub fn keys(right: bool, left: bool, up: bool, down: bool) -> KeyboardInput {
+    KeyboardInput { right, left, up, down }
+}
+
+/// 单个组件实例的 Guest 调用封装，隐藏 Store 与 GameWorld 的借用细节
+pub struct GuestInstance<'a> {
+    instance: &'a mut ComponentInstance,
+}
+
+impl WasmRuntime {
+    /// 取组件 name 的实例；不存在时 panic
+    pub fn guest(&mut self, name: &str) -> GuestInstance<'_> {
+        let instance = self.instances.get_mut(name).unwrap_or_else(|| panic!("缺少 {} 实例", name));
+        GuestInstance { instance }
+    }
+
+    /// wasm_tick 调用各组件的顺序
+    pub fn tick_order(&self) -> &[String] {
+        &self.order
+    }
+
+    /// 与退出流程相同：看门狗限时调用组件 name 的 on-shutdown
+    pub fn shutdown_guest(&mut self, name: &str) -> wasmtime::Result<()> {
+        let WasmRuntime { ref engine, ref mut instances, .. } = *self;
+        let instance = instances.get_mut(name).unwrap_or_else(|| panic!("缺少 {} 实例", name));
+        shutdown_instance(engine, instance, SHUTDOWN_TIMEOUT)
+    }
+}
+
+impl GuestInstance<'_> {
+    /// 该组件已经历的 wasm_tick 帧数
+    pub fn frame_count(&self) -> u64 {
+        self.instance.store.data().frame_count
+    }
+
+    pub fn host(&self) -> &HostState {
+        self.instance.store.data()
+    }
+
+    pub fn host_mut(&mut self) -> &mut HostState {
+        self.instance.store.data_mut()
+    }
+
+    /// 退出流程已调用 on-shutdown 的次数
+    pub fn shutdown_calls(&self) -> u32 {
+        self.instance.store.data().shutdown_calls
+    }
+
+    /// 与 wasm_tick 每帧开始时相同，重设本实例可消耗的 fuel
+    pub fn set_fuel(&mut self, fuel: u64) -> wasmtime::Result<()> {
+        self.instance.store.set_fuel(fuel)
+    }
+
+    pub fn fuel_remaining(&self) -> wasmtime::Result<u64> {
+        self.instance.store.get_fuel()
+    }
+
+    /// 组件是否导出了可选的 hot-reload 接口
+    pub fn has_hot_reload(&self) -> bool {
+        self.instance.hot_reload.is_some()
+    }
+
+    pub fn save_state(&mut self) -> wasmtime::Result<Vec<u8>> {
+        let hot_reload = self.instance.hot_reload.expect("组件未导出 hot-reload");
+        hot_reload.save_state(&mut self.instance.store)
+    }
+
+    pub fn restore_state(&mut self, bytes: &[u8]) -> wasmtime::Result<()> {
+        let hot_reload = self.instance.hot_reload.expect("组件未导出 hot-reload");
+        hot_reload.restore_state(&mut self.instance.store, bytes)
+    }
+
+    pub fn on_ui_event(&mut self, event: &str) -> wasmtime::Result<()> {
+        let ComponentInstance { ref game_world, ref mut store, .. } = *self.instance;
+        game_world.interface0.call_on_ui_event(store, event)
+    }
+
+    pub fn process_keyboard(&mut self, input: KeyboardInput) -> wasmtime::Result<KeyboardInput> {
+        let ComponentInstance { ref game_world, ref mut store, .. } = *self.instance;
+        game_world.interface0.call_process_keyboard(store, input)
+    }
+
+    pub fn update_game(&mut self, keyboard: KeyboardInput, state: PlayerState, delta: f32) -> wasmtime::Result<UpdateResult> {
+        let ComponentInstance { ref game_world, ref mut store, .. } = *self.instance;
+        game_world.interface0.call_update_game(store, keyboard, state, delta)
+    }
+}
+
+// ── wasm_tick 运行环境 ────────────────────────────────────────────────────────
+
+/// 最小的 wasm_tick 运行环境：只注册 wasm_tick 用到的资源与事件，每帧 0.1 秒
+pub fn tick_app(runtime: WasmRuntime) -> App {
+    let mut app = App::new();
+    let mut time = Time::<()>::default();
+    time.advance_by(Duration::from_millis(100));
+    app.insert_resource(time)
+        .insert_non_send_resource(runtime)
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<GameState>()
+        .init_resource::<PendingUiCommands>()
+        .init_resource::<PendingSceneTransitions>()
+        .init_resource::<PendingAudio>()
+        .init_resource::<PendingCamera>()
+        .init_resource::<FontLoadQueue>()
+        .init_resource::<PendingClipboard>()
+        .init_resource::<PendingWindowTitle>()
+        .init_resource::<PendingWindowIcon>()
+        .init_resource::<PendingPlayerTeleport>()
+        .init_resource::<PhysicsCommandQueue>()
+        .init_resource::<PendingFocusRequest>()
+        .init_resource::<PendingHttpRequests>()
+        .init_resource::<HttpResponseMap>()
+        .init_resource::<PendingLocale>()
+        .init_resource::<I18nBundle>()
+        .init_resource::<TextMeasurer>()
+        .init_resource::<FocusState>()
+        .init_resource::<WasmEventBus>()
+        .init_resource::<SharedState>()
+        .init_resource::<bevy::diagnostic::DiagnosticsStore>()
+        .insert_resource(PermissionRegistry {
+            granted: WasmPermissionConfig::default().granted.into_iter().collect(),
+        })
+        .init_resource::<QueueLimits>()
+        .init_resource::<DroppedCommandCounts>()
+        .init_resource::<GameTimestamp>()
+        .init_resource::<UiBoundsCache>()
+        .init_resource::<SpriteBoundsCache>()
+        .init_resource::<UiTreeDump>()
+        .init_resource::<UiAssetReadiness>()
+        .init_resource::<ClickEventQueue>()
+        .init_resource::<DespawnedUiKeys>()
+        .init_resource::<UiEventConfig>()
+        .init_resource::<WasmCallMetrics>()
+        .init_resource::<ClipboardCache>()
+        .init_resource::<InputValueReadback>()
+        .init_resource::<TextInputQueue>()
+        .init_resource::<WindowFocusQueue>()
+        .init_resource::<LocalStorageConfig>()
+        .init_resource::<StorageLimitConfig>()
+        .init_resource::<GamePaused>()
+        .init_resource::<TickConfig>()
+        .init_resource::<FixedTickState>()
+        .init_resource::<GuestTimers>()
+        .init_resource::<RetryQueue>()
+        .init_resource::<FrameCounter>()
+        .init_resource::<UiLayoutWarnings>()
+        .add_event::<SceneLoaded>()
+        .add_event::<TweenFinished>()
+        .add_event::<AnimationFinished>()
+        .add_event::<FocusChanged>()
+        .add_event::<LocaleChanged>()
+        .add_event::<DragMoved>()
+        .add_event::<WasmCallFailed>()
+        .add_event::<Collision>()
+        .add_event::<WindowResized>();
+    #[cfg(feature = "debug-draw")]
+    app.init_resource::<debug_draw::PendingDebugDraws>();
+    app
+}
+
+/// UI 系统的最小运行环境：资源服务器、字体 / 图片 / 图集资源与 UI 命令处理用到的资源和事件，
+/// 之后用 run_system_once 逐个驱动 process_ui_spawn / process_ui_mutations 等系统
+pub fn ui_world() -> World {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .init_asset::<Font>()
+        .init_asset::<Image>()
+        .init_asset::<TextureAtlasLayout>()
+        .init_resource::<UiEntityMap>()
+        .init_resource::<PersistentUiKeys>()
+        .init_resource::<FontRegistry>()
+        .init_resource::<FontConfig>()
+        .init_resource::<UiAssetHandles>()
+        .init_resource::<UiLayoutEngine>()
+        .init_resource::<ScreenSize>()
+        .init_resource::<UiLayoutWarnings>()
+        .init_resource::<UiStackOrder>()
+        .init_resource::<InputValueReadback>()
+        .init_resource::<ActiveTweens>()
+        .init_resource::<DespawnedUiKeys>()
+        .add_event::<TweenFinished>()
+        .add_event::<AnimationFinished>();
+    std::mem::take(&mut app.world)
+}
+
+/// 只含退出清理的 App：Update 中运行 shutdown_wasm_components，发送 AppExit 后 app.update() 即触发
+pub fn shutdown_app(runtime: WasmRuntime) -> App {
+    let mut app = App::new();
+    app.insert_non_send_resource(runtime)
+        .add_event::<AppExit>()
+        .add_systems(Update, shutdown_wasm_components);
+    app
+}
+
+/// 注册 wasm_tick，之后用 app.world.run_system(id) 逐帧驱动
+pub fn register_wasm_tick(app: &mut App) -> SystemId {
+    app.world.register_system(wasm_tick)
+}
+
+/// 本帧按下 key（与 UiEventConfig 的绑定配合触发 UI 事件）
+pub fn press_key(app: &mut App, key: KeyCode) {
+    app.world.resource_mut::<ButtonInput<KeyCode>>().press(key);
+}
+
+/// 把 key 绑定到 UI 事件 event（追加到 UiEventConfig，默认的 E → toggle_panel 保留）
+pub fn bind_ui_event(app: &mut App, key: KeyCode, event: &str) {
+    app.world.resource_mut::<UiEventConfig>().bindings.push((key, event.to_string()));
+}
+
+/// 结束本帧输入：清除 just_pressed，之后的帧不再触发按键绑定的 UI 事件
+pub fn clear_input(app: &mut App) {
+    app.world.resource_mut::<ButtonInput<KeyCode>>().clear();
+}
+
+/// GameState 中的玩家逻辑位置（wasm_tick 写入）
+pub fn player_position(app: &App) -> Vec2 {
+    app.world.resource::<GameState>().player_position
+}
+
+/// GameState 中的玩家速度（set-player-speed 经 wasm_tick 写入）
+pub fn player_speed(app: &App) -> f32 {
+    app.world.resource::<GameState>().player_speed
+}
+
+/// 模拟宿主原生系统直接改写玩家逻辑位置（如复活点）
+pub fn set_game_state_position(app: &mut App, position: Vec2) {
+    app.world.resource_mut::<GameState>().player_position = position;
+}
+
+/// 模拟宿主原生系统修改玩家生命值（如受到伤害）
+pub fn set_game_state_health(app: &mut App, health: f32) {
+    app.world.resource_mut::<GameState>().player_health = health;
+}
+
+/// 生成带 Player 标记的玩家实体，初始速度非零以便检查瞬移后速度清零
+pub fn spawn_player(app: &mut App) -> Entity {
+    app.world.spawn((Transform::default(), LinearVelocity(Vec2::X), Player)).id()
+}
+
+/// 运行一次 apply_player_teleport，应用本帧的 set-player-position 请求
+pub fn run_player_teleport(app: &mut App) {
+    use bevy::ecs::system::RunSystemOnce;
+    app.world.run_system_once(apply_player_teleport);
+}
+
+/// wasm_tick 汇总到 PendingUiCommands 的 Spawn 命令 key（含命名空间）
+pub fn pending_spawn_keys(app: &App) -> Vec<String> {
+    app.world.resource::<PendingUiCommands>().spawns.iter().map(|cmd| cmd.key().to_string()).collect()
+}
+
+// ── Host 捕获辅助：直接检查 HostState 队列，无需 Bevy 渲染 ─────────────────────
+
+impl HostState {
+    /// 按 key 查找已入队的 SpawnPanel，返回 (x, y, width, height)
+    pub fn captured_panel(&self, key: &str) -> Option<(f32, f32, f32, f32)> {
+        self.spawn_commands.iter().find_map(|cmd| match cmd {
+            UiSpawnCommand::SpawnPanel { key: k, x, y, width, height, .. } if k == key => {
+                Some((*x, *y, *width, *height))
+            }
+            _ => None,
+        })
+    }
+
+    /// 已入队的 SpawnText 中，父节点为 parent_key 的子节点 key
+    pub fn captured_text_keys(&self, parent_key: &str) -> Vec<&str> {
+        self.spawn_commands.iter().filter_map(|cmd| match cmd {
+            UiSpawnCommand::SpawnText { key, parent_key: p, .. } if p == parent_key => Some(key.as_str()),
+            _ => None,
+        }).collect()
+    }
+
+    /// 已入队的 SetVisible，按入队顺序返回 (key, visible)
+    pub fn captured_visibility(&self) -> Vec<(&str, bool)> {
+        self.mutation_commands.iter().filter_map(|cmd| match cmd {
+            UiMutationCommand::SetVisible { key, visible } => Some((key.as_str(), *visible)),
+            _ => None,
+        }).collect()
+    }
+
+    pub fn is_subscribed(&self, event_type: &str) -> bool {
+        self.event_subscriptions.contains(event_type)
+    }
+
+    pub fn captured_spawn_count(&self) -> usize {
+        self.spawn_commands.len()
+    }
+
+    pub fn captured_mutation_count(&self) -> usize {
+        self.mutation_commands.len()
+    }
+
+    /// 清空已捕获的命令，便于分段断言
+    pub fn clear_captured(&mut self) {
+        self.spawn_commands.clear();
+        self.mutation_commands.clear();
+    }
+}
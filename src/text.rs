@@ -0,0 +1,140 @@
+// ─── 字体与文字测量 ───────────────────────────────────────────────────────────
+//
+// load-font 注册的字体别名，以及 measure-text 同步返回时使用的字形度量。
+
+use super::*;
+
+// ─── 字体 ─────────────────────────────────────────────────────────────────────
+//
+// TS 调用 load-font 注册字体别名，wasm_tick 把请求转存到 FontLoadQueue，
+// process_font_loads 在 process_ui_spawn 之前加载，同一帧内创建的文字即可使用该别名。
+// 别名在所有组件间共享，后注册的同名别名覆盖先前的。
+
+/// FontConfig 的默认值
+pub(crate) const DEFAULT_FONT: &str = "fonts/FiraSans-Bold.ttf";
+
+/// 未指定字体或别名未注册时使用的字体（相对 assets/ 的路径）。
+/// init_resource 不覆盖已有资源，宿主可在添加插件前插入自己的配置
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct FontConfig {
+    pub default_font: String,
+}
+
+impl Default for FontConfig {
+    fn default() -> Self {
+        FontConfig { default_font: DEFAULT_FONT.to_string() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FontLoadRequest {
+    /// 相对 assets/ 的字体路径
+    pub(crate) path: String,
+    pub(crate) alias: String,
+}
+
+#[derive(Resource, Default)]
+pub(crate) struct FontLoadQueue {
+    pub(crate) requests: Vec<FontLoadRequest>,
+}
+
+/// 字体别名 → 字体句柄
+#[derive(Resource, Default)]
+pub(crate) struct FontRegistry {
+    pub(crate) map: HashMap<String, Handle<Font>>,
+}
+
+/// 加载 TS 请求的字体并登记别名
+pub(crate) fn process_font_loads(
+    mut queue: ResMut<FontLoadQueue>,
+    asset_server: Res<AssetServer>,
+    mut registry: ResMut<FontRegistry>,
+) {
+    for FontLoadRequest { path, alias } in queue.requests.drain(..) {
+        println!("[字体] 注册别名 {} → {}", alias, path);
+        let handle = asset_server.load(path);
+        registry.map.insert(alias, handle);
+    }
+}
+
+// ─── 文字测量 ─────────────────────────────────────────────────────────────────
+//
+// measure-text 在 Guest 调用中同步返回，不能等待渲染：update_text_measurer 在默认字体与 FontRegistry
+// 中的别名字体加载完成后把 ab_glyph 字体存入 TextMeasurer，wasm_tick 拷贝进 HostState
+// （FontArc 为引用计数，拷贝开销很小），同时清空 HostState 的测量缓存。
+// 结果按字形前进宽度与字距累加，不考虑自动换行与 Bevy 的像素对齐，只是近似值；
+// 字体尚未加载时按 FALLBACK_ADVANCE 估算。
+
+/// 字体未加载时每个字符的宽度与字号之比
+const FALLBACK_ADVANCE: f32 = 0.6;
+/// 字体未加载时行高与字号之比
+const FALLBACK_LINE_HEIGHT: f32 = 1.2;
+/// HostState 中 measure-text 结果缓存的条目数
+pub(crate) const TEXT_MEASURE_CACHE_SIZE: usize = 256;
+
+#[derive(Resource, Default, Clone)]
+pub(crate) struct TextMeasurer {
+    pub(crate) handle: Option<Handle<Font>>,
+    pub(crate) font:   Option<ab_glyph::FontArc>,
+    /// 别名 → (字体资源 id, 字形度量)；id 用于发现别名被重新注册到其他字体
+    aliases: HashMap<String, (AssetId<Font>, ab_glyph::FontArc)>,
+}
+
+impl TextMeasurer {
+    /// 多行文本按 `\n` 分行：宽度取最宽的一行，高度为行数 × 行高。
+    /// alias 为空、未注册或字体尚未加载时使用默认字体
+    pub(crate) fn measure(&self, text: &str, alias: &str, font_size: f32) -> Vec2 {
+        use ab_glyph::{Font as _, ScaleFont};
+
+        let lines: Vec<&str> = text.split('\n').collect();
+        let font = self.aliases.get(alias).map(|(_, font)| font).or(self.font.as_ref());
+        let Some(font) = font else {
+            let longest = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+            return Vec2::new(
+                longest as f32 * font_size * FALLBACK_ADVANCE,
+                lines.len() as f32 * font_size * FALLBACK_LINE_HEIGHT,
+            );
+        };
+        let scaled = font.as_scaled(ab_glyph::PxScale::from(font_size));
+        let line_width = |line: &str| {
+            let mut previous = None;
+            line.chars().fold(0.0, |width, c| {
+                let glyph = scaled.glyph_id(c);
+                let kern = previous.map_or(0.0, |prev| scaled.kern(prev, glyph));
+                previous = Some(glyph);
+                width + kern + scaled.h_advance(glyph)
+            })
+        };
+        let width = lines.iter().map(|line| line_width(line)).fold(0.0, f32::max);
+        let line_height = scaled.height() + scaled.line_gap();
+        Vec2::new(width, lines.len() as f32 * line_height - scaled.line_gap())
+    }
+}
+
+/// 默认字体与各别名字体在加载完成前每帧检查一次，已取得度量的不再读取；
+/// 没有 TextPlugin 的宿主始终使用估算值
+pub(crate) fn update_text_measurer(
+    config: Res<FontConfig>,
+    asset_server: Res<AssetServer>,
+    fonts: Option<Res<Assets<Font>>>,
+    registry: Option<Res<FontRegistry>>,
+    mut measurer: ResMut<TextMeasurer>,
+) {
+    let Some(fonts) = fonts else { return; };
+    if measurer.font.is_none() {
+        let handle = measurer.handle.get_or_insert_with(|| asset_server.load(config.default_font.clone())).clone();
+        if let Some(font) = fonts.get(&handle) {
+            measurer.font = Some(font.font.clone());
+        }
+    }
+    let Some(registry) = registry else { return; };
+    for (alias, handle) in &registry.map {
+        // 只读访问不触发变更检测，避免 wasm_tick 每帧拷贝
+        if measurer.aliases.get(alias).is_some_and(|(id, _)| *id == handle.id()) {
+            continue;
+        }
+        if let Some(font) = fonts.get(handle) {
+            measurer.aliases.insert(alias.clone(), (handle.id(), font.font.clone()));
+        }
+    }
+}
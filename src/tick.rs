@@ -0,0 +1,639 @@
+// ─── 每帧 WASM 调用 ───────────────────────────────────────────────────────────
+//
+// wasm_tick 是唯一访问 WasmRuntime 的每帧系统：按 tick 顺序依次调用各组件的 Guest，
+// 把 Bevy 侧的输入、事件与资源快照交给 HostState，再把 HostState 中排队的命令转存到各 Pending 队列，
+// 由主线程的消费系统真正操作 ECS。为满足 Bevy 系统参数个数上限，相关资源按用途打包为下面的 SystemParam。
+
+use super::*;
+use bevy::diagnostic::Diagnostics;
+use bevy_xpbd_2d::prelude::Contacts;
+
+/// wasm_tick 转存 HostState 命令的目标队列
+#[derive(SystemParam)]
+pub(crate) struct TickQueues<'w> {
+    ui:     ResMut<'w, PendingUiCommands>,
+    scenes: ResMut<'w, PendingSceneTransitions>,
+    audio:  ResMut<'w, PendingAudio>,
+    camera: ResMut<'w, PendingCamera>,
+    fonts:  ResMut<'w, FontLoadQueue>,
+    clipboard: ResMut<'w, PendingClipboard>,
+    window:  WindowRequests<'w>,
+    locale:  ResMut<'w, PendingLocale>,
+    player_teleport: ResMut<'w, PendingPlayerTeleport>,
+    physics: ResMut<'w, PhysicsCommandQueue>,
+    focus:   ResMut<'w, PendingFocusRequest>,
+    http:    ResMut<'w, PendingHttpRequests>,
+    events:  ResMut<'w, WasmEventBus>,
+    limits:  Res<'w, QueueLimits>,
+    dropped: ResMut<'w, DroppedCommandCounts>,
+    #[cfg(feature = "debug-draw")]
+    debug_draws: ResMut<'w, debug_draw::PendingDebugDraws>,
+}
+
+/// 窗口标题与图标请求
+#[derive(SystemParam)]
+pub(crate) struct WindowRequests<'w> {
+    title: ResMut<'w, PendingWindowTitle>,
+    icon:  ResMut<'w, PendingWindowIcon>,
+}
+
+/// wasm_tick 的时间与步进控制
+#[derive(SystemParam)]
+pub(crate) struct TickClock<'w> {
+    time:      Res<'w, Time>,
+    config:    Res<'w, TickConfig>,
+    fixed:     ResMut<'w, FixedTickState>,
+    timestamp: ResMut<'w, GameTimestamp>,
+    paused:    ResMut<'w, GamePaused>,
+    frame:     ResMut<'w, FrameCounter>,
+}
+
+/// wasm_tick 在变化时拷贝进 HostState、供 Guest 同步读取的资源
+#[derive(SystemParam)]
+pub(crate) struct HostSnapshots<'w> {
+    clipboard:      Res<'w, ClipboardCache>,
+    input_values:   Res<'w, InputValueReadback>,
+    storage:        Res<'w, LocalStorageConfig>,
+    storage_limits: Res<'w, StorageLimitConfig>,
+    permissions:    Res<'w, PermissionRegistry>,
+    ui_dump:        Res<'w, UiTreeDump>,
+    assets:         Res<'w, UiAssetReadiness>,
+    /// 唯一双向的一项：每个组件调用前拷贝，调用后写回 set-var 的修改
+    shared_state:   ResMut<'w, SharedState>,
+    i18n:           Res<'w, I18nBundle>,
+    text_measurer:  Res<'w, TextMeasurer>,
+}
+
+/// 失败通知的重试队列，以及重试次数用尽时发出的事件
+#[derive(SystemParam)]
+pub(crate) struct TickRetries<'w> {
+    queue:  ResMut<'w, RetryQueue>,
+    failed: EventWriter<'w, WasmCallFailed>,
+}
+
+/// wasm_tick 需要转告 Guest 的上一帧事件
+#[derive(SystemParam)]
+pub(crate) struct TickNotifications<'w, 's> {
+    scene_loaded:   EventReader<'w, 's, SceneLoaded>,
+    tween_finished: EventReader<'w, 's, TweenFinished>,
+    animation_finished: EventReader<'w, 's, AnimationFinished>,
+    clicks:         ResMut<'w, ClickEventQueue>,
+    despawned:      ResMut<'w, DespawnedUiKeys>,
+    focus_changed:  EventReader<'w, 's, FocusChanged>,
+    focus:          Res<'w, FocusState>,
+    layout_warnings: ResMut<'w, UiLayoutWarnings>,
+    http:           ResMut<'w, HttpResponseMap>,
+    locale_changed: EventReader<'w, 's, LocaleChanged>,
+    drag_moved:     EventReader<'w, 's, DragMoved>,
+    /// 到期的定时器；同时接收本帧 set-timer / cancel-timer 的请求
+    timers:         ResMut<'w, GuestTimers>,
+    resized:        EventReader<'w, 's, WindowResized>,
+    window_size:    Local<'s, WindowSizeDebounce>,
+    text_input:     ResMut<'w, TextInputQueue>,
+    window_focus:   ResMut<'w, WindowFocusQueue>,
+}
+
+/// 统一 WASM 调用系统
+/// 一帧只访问一次 WasmRuntime（NonSendMut），零锁开销。
+/// 将键盘处理、位置更新、UI 事件全部收拢在此。
+/// 多组件时按 WasmRuntime.order 依次 tick，玩家状态在组件间顺序传递（后者看到前者的结果）。
+pub(crate) fn wasm_tick(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut clock: TickClock,
+    mut game_state: ResMut<GameState>,
+    mut query: Query<(Entity, &mut Transform, &mut LinearVelocity), With<Player>>,
+    mut wasm: NonSendMut<WasmRuntime>,
+    mut queues: TickQueues,
+    bounds: BoundsSnapshots,
+    mut notifications: TickNotifications,
+    mut retries: TickRetries,
+    mut collisions: EventReader<Collision>,
+    ui_event_config: Res<UiEventConfig>,
+    mut metrics: ResMut<WasmCallMetrics>,
+    mut snapshots: HostSnapshots,
+    mut diagnostics: Diagnostics,
+    #[cfg(feature = "replay")] mut replay_recorder: Option<ResMut<replay::ReplayRecorder>>,
+    #[cfg(feature = "replay")] mut replay_player: Option<ResMut<replay::ReplayPlayer>>,
+) {
+    clock.frame.0 += 1;
+    let _span = info_span!(target: TRACE_TARGET, "wasm_tick", frame = clock.frame.0).entered();
+
+    // 回放模式：直接使用录制的结果，完全绕过 WASM 调用
+    #[cfg(feature = "replay")]
+    if let Some(player) = replay_player.as_deref_mut().filter(|player| player.replays_output()) {
+        if let Some(frame) = player.next_frame() {
+            let (x, y) = frame.player_position;
+            game_state.player_position = Vec2::new(x, y);
+            for (_, mut transform, mut velocity) in query.iter_mut() {
+                transform.translation.x = x;
+                transform.translation.y = y;
+                velocity.0 = Vec2::ZERO;
+            }
+            queues.ui.spawns.extend(frame.spawns);
+            queues.ui.mutations.extend(frame.mutations);
+        }
+        return;
+    }
+
+    // 录制模式：记下本帧开始前的队列长度，结束时只录制本帧新增的命令
+    #[cfg(feature = "replay")]
+    let (spawn_mark, mutation_mark) = (queues.ui.spawns.len(), queues.ui.mutations.len());
+
+    // 本帧交给 Guest 的输入：方向键、按 UiEventConfig 触发的 UI 事件（所有组件收到相同的序列）、
+    // detect_ui_clicks 收集的点击（只通知 key 所属的组件）与时间
+    let arrows = KeyboardInput {
+        right: keyboard_input.pressed(KeyCode::ArrowRight),
+        left:  keyboard_input.pressed(KeyCode::ArrowLeft),
+        up:    keyboard_input.pressed(KeyCode::ArrowUp),
+        down:  keyboard_input.pressed(KeyCode::ArrowDown),
+    };
+    let ui_events = ui_event_config.triggered(&keyboard_input);
+    let clicked: Vec<String> = notifications.clicks.0.drain(..).collect();
+    let (elapsed_secs, frame_delta) = (clock.time.elapsed_seconds_f64(), clock.time.delta_seconds());
+
+    // 输入回放：用录像中的输入与时间代替实时值，照常调用 WASM；录像播完后恢复实时输入
+    #[cfg(feature = "replay")]
+    let (arrows, ui_events, clicked, elapsed_secs, frame_delta) =
+        match replay_player.as_deref_mut().and_then(replay::ReplayPlayer::next_input) {
+            Some(frame) => (
+                KeyboardInput { right: frame.input.right, left: frame.input.left, up: frame.input.up, down: frame.input.down },
+                frame.input.ui_events,
+                frame.input.clicks,
+                frame.elapsed_secs,
+                frame.delta_secs,
+            ),
+            None => (arrows, ui_events, clicked, elapsed_secs, frame_delta),
+        };
+
+    // 以物理引擎积分（含碰撞响应）后的实际位置作为本帧起点
+    let player = query.get_single().ok().map(|(entity, transform, _)| (entity, transform.translation.truncate()));
+    let fixed_hz = clock.config.fixed_hz;
+    if let Some((_, position)) = player {
+        game_state.player_position = match fixed_hz {
+            // 固定步长：逻辑位置领先于渲染位置，只叠加物理引擎的修正量
+            Some(_) => clock.fixed.resync(position),
+            None => position,
+        };
+    }
+    // 本帧 update-game 的调用次数与 delta；暂停时不累加时间，恢复后不会一次性补跑
+    let (steps, step_delta) = match fixed_hz {
+        Some(hz) if !clock.paused.0 => (clock.fixed.advance(frame_delta, hz), 1.0 / hz),
+        Some(hz) => (0, 1.0 / hz),
+        None => (1, frame_delta),
+    };
+    // 最后一步之前的逻辑位置，作为插值起点
+    let mut before_last_step = None;
+    // 上一帧新产生的玩家碰撞法线；持续接触不重复通知
+    let collision_normals: Vec<Vec2> = match player {
+        Some((entity, _)) => collisions
+            .read()
+            .filter(|Collision(contacts)| !contacts.during_previous_frame)
+            .filter_map(|Collision(contacts)| player_collision_normal(entity, contacts))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    // 上一帧完成切换的场景，需通知每个组件
+    let loaded_scenes: Vec<String> = notifications.scene_loaded.read().map(|event| event.0.clone()).collect();
+    // 上一帧完成的补间，只通知 key 所属的组件
+    let finished_tweens: Vec<String> = notifications.tween_finished.read().map(|event| event.0.clone()).collect();
+    // 上一帧切换成功的语言，通知每个组件刷新文字
+    let locale_events: Vec<String> = notifications.locale_changed.read().map(|event| format!("locale-changed:{}", event.0)).collect();
+    // 上一帧播完的非循环精灵动画，同样只通知 key 所属的组件
+    let finished_animations: Vec<String> = notifications.animation_finished.read().map(|event| event.0.clone()).collect();
+    // 上一帧销毁的 key，同样只通知 key 所属的组件
+    let despawned: Vec<String> = notifications.despawned.0.drain(..).collect();
+    // 本帧的文本输入，所有组件收到相同的内容
+    let text_input = (!notifications.text_input.is_empty()).then(|| {
+        let TextInputQueue { typed, backspaces, enter } = std::mem::take(&mut *notifications.text_input);
+        TextInput { typed_chars: typed, backspaces, enter, focused_key: None }
+    });
+    // 上一帧 process_ui_spawn 产生的布局警告，转给 key 所属的组件等待 get-layout-warnings 读取
+    let layout_warnings: Vec<(String, UiLayoutWarning)> = notifications.layout_warnings.0.drain(..).collect();
+    // 上一帧完成的 HTTP 请求，交给发起请求的组件
+    let http_responses: Vec<(u32, HttpResult)> = std::mem::take(&mut notifications.http.completed);
+    // 本帧到期的定时器，只通知设置它的组件
+    let fired_timers: Vec<String> = std::mem::take(&mut notifications.timers.fired);
+    // 本帧拖拽产生的位置变化，只通知 key 所属的组件
+    let drags: Vec<DragMoved> = notifications.drag_moved.read().cloned().collect();
+    // 当前焦点的全局 key，随文本输入传给 key 所属的组件
+    let focused_key: Option<String> = notifications.focus.focused.as_ref().map(|(_, key)| key.clone());
+    // 上一帧的窗口尺寸变化（已去抖），通知每个组件
+    let resized = notifications.window_size.update(notifications.resized.read());
+    // 窗口焦点变化，通知每个组件（TS 可在失焦时暂停、降低音量）
+    let focus_events: Vec<bool> = notifications.window_focus.0.drain(..).collect();
+    // 上一帧的焦点变化，通知新旧 key 所属的组件
+    let focus_changes: Vec<FocusChanged> = notifications.focus_changed.read().cloned().collect();
+
+    let WasmRuntime { ref engine, ref linker, ref order, ref mut instances, fuel_per_frame, ref mut fallback, .. } = *wasm;
+
+    let tick_start = Instant::now();
+    let (mut keyboard_time, mut update_time, mut ui_event_time) = (Duration::ZERO, Duration::ZERO, Duration::ZERO);
+
+    // JS 回退后端替代全部组件，只有键盘映射、位置更新与 UI 事件三个入口
+    let order: &[String] = match fallback.as_deref_mut() {
+        Some(backend) => {
+            if !clock.paused.0 && steps > 0 {
+                let call_start = Instant::now();
+                let keyboard = backend.call_process_keyboard(arrows).unwrap_or_else(|e| {
+                    eprintln!("[键盘映射] JS 错误: {}", e);
+                    arrows
+                });
+                keyboard_time += call_start.elapsed();
+                for _ in 0..steps {
+                    let state = PlayerState {
+                        x:     game_state.player_position.x,
+                        y:     game_state.player_position.y,
+                        speed: game_state.player_speed,
+                    };
+                    before_last_step = Some(game_state.player_position);
+                    let call_start = Instant::now();
+                    match backend.call_update_game(keyboard, state, step_delta) {
+                        Ok(result) => game_state.player_position = Vec2::new(result.x, result.y),
+                        Err(e) => eprintln!("[位置更新] JS 错误: {}", e),
+                    }
+                    update_time += call_start.elapsed();
+                }
+            }
+            let call_start = Instant::now();
+            for event in &ui_events {
+                if let Err(e) = backend.call_on_ui_event(event) {
+                    eprintln!("[UI事件] JS 错误 {}: {}", event, e);
+                }
+            }
+            ui_event_time += call_start.elapsed();
+            &[]
+        }
+        None => order,
+    };
+
+    for name in order {
+        let Some(instance) = instances.get_mut(name) else { continue; };
+        // 有到期重试时先确认实例未因 trap 失效，失效则重新实例化（探测前补足 fuel，避免误判）
+        if retries.queue.has_due(name, tick_start) {
+            if let Some(fuel) = fuel_per_frame {
+                let _ = instance.store.set_fuel(fuel);
+            }
+            match revive_trapped_instance(engine, linker, instance) {
+                Ok(true) => println!("[WASM] 组件 {} 已因 trap 失效，重新实例化后重试", name),
+                Ok(false) => {}
+                Err(e) => {
+                    eprintln!("[WASM] 组件 {} 重新实例化失败，放弃到期的重试: {}", name, e);
+                    for failed in retries.queue.fail_due(name, tick_start, &e.to_string()) {
+                        retries.failed.send(failed);
+                    }
+                }
+            }
+        }
+        let ComponentInstance { ref game_world, ref mut store, .. } = *instance;
+
+        // ⓪ 更新时间快照（先于任何 Guest 调用，保证本帧 get-time 结果一致）
+        store.data_mut().frame_count += 1;
+        *clock.timestamp = GameTimestamp {
+            elapsed_secs,
+            delta_secs:   frame_delta,
+            frame_count:  store.data().frame_count,
+        };
+        store.data_mut().current_frame = clock.frame.0;
+        store.data_mut().timestamp = *clock.timestamp;
+        store.data_mut().queue_limits = *queues.limits;
+        if let Some(fuel) = fuel_per_frame {
+            if let Err(e) = store.set_fuel(fuel) {
+                eprintln!("[WASM] 设置 fuel 失败 [{}]: {}", name, e);
+            }
+        }
+        if bounds.ui.is_changed() {
+            store.data_mut().ui_bounds = bounds.ui.map.clone();
+        }
+        if bounds.sprites.is_changed() {
+            store.data_mut().sprite_bounds = bounds.sprites.map.clone();
+        }
+        // 前面的组件本帧可能刚写入，每次都拷贝
+        store.data_mut().sync_shared_vars(&snapshots.shared_state);
+        if snapshots.text_measurer.is_changed() {
+            store.data_mut().set_text_measurer(snapshots.text_measurer.clone());
+        }
+        if snapshots.i18n.is_changed() {
+            store.data_mut().i18n_strings = snapshots.i18n.strings.clone();
+        }
+        if snapshots.clipboard.is_changed() {
+            store.data_mut().clipboard_text = snapshots.clipboard.0.clone();
+        }
+        if snapshots.input_values.is_changed() {
+            store.data_mut().input_values = snapshots.input_values.values.clone();
+        }
+        if snapshots.ui_dump.is_changed() {
+            store.data_mut().ui_dump = snapshots.ui_dump.0.clone();
+        }
+        if snapshots.assets.is_changed() {
+            store.data_mut().ready_assets = snapshots.assets.0.clone();
+        }
+        if snapshots.storage.is_changed() || snapshots.storage_limits.is_changed() {
+            store.data_mut().storage = LocalStorage {
+                config: snapshots.storage.clone(),
+                limits: *snapshots.storage_limits,
+            };
+        }
+        store.data_mut().paused = clock.paused.0;
+        store.data_mut().player_position = game_state.player_position;
+        store.data_mut().player_speed = game_state.player_speed;
+        store.data_mut().player_health = game_state.player_health;
+        // 权限集合很小，每帧整体拷贝，新实例化的组件也能立即同步
+        store.data_mut().permissions = snapshots.permissions.granted.clone();
+        let host = store.data_mut();
+        let owned: Vec<(String, UiLayoutWarning)> = layout_warnings
+            .iter()
+            .filter_map(|(key, warning)| Some((host.local_key(key)?.to_string(), *warning)))
+            .collect();
+        host.push_layout_warnings(owned);
+        host.deliver_http_responses(&http_responses);
+
+        // ⓪ₐ 重试之前失败、退避已到期的通知（fuel 已重新设置）
+        for failed in retries.queue.retry_due(name, tick_start, |call| call.invoke(game_world, &mut *store)) {
+            eprintln!("[WASM] 重试 {} 次后仍失败，放弃 {:?}: {}", MAX_RETRY_ATTEMPTS, failed.call, failed.error);
+            retries.failed.send(failed);
+        }
+
+        // ⓪' 场景加载完成通知（先于本帧逻辑，TS 可在此重建场景 UI）
+        for scene in &loaded_scenes {
+            store.data_mut().current_scene = scene.clone();
+            if let Err(e) = game_world.interface0.call_on_scene_load(&mut *store, scene) {
+                eprintln!("[场景] on-scene-load WASM 错误 [{}]: {}", name, e);
+            }
+        }
+
+        // ⓪'' 窗口尺寸变化通知（先于 UI 事件，TS 可据此重新布局）
+        if let Some(size) = resized {
+            if let Err(e) = game_world.interface0.call_on_resize(&mut *store, size.x, size.y) {
+                eprintln!("[窗口] on-resize WASM 错误 [{}]: {}", name, e);
+            }
+        }
+        for &focused in &focus_events {
+            if let Err(e) = game_world.interface0.call_on_window_focus_changed(&mut *store, focused) {
+                eprintln!("[窗口] on-window-focus-changed WASM 错误 [{}]: {}", name, e);
+            }
+        }
+
+        // ①② 暂停时跳过移动，玩家停在原地（velocity_towards 得到零速度）
+        if !clock.paused.0 && steps > 0 {
+            // ① 键盘输入处理（processKeyboard 结果直接使用，无需中转存储）
+            let call_start = Instant::now();
+            let keyboard_result = game_world.interface0.call_process_keyboard(&mut *store, arrows);
+            keyboard_time += call_start.elapsed();
+            let keyboard = match keyboard_result {
+                Ok(mapped) => mapped,
+                Err(e) => {
+                    eprintln!("[键盘映射] WASM 错误 [{}]: {}", name, e);
+                    // 映射失败时降级使用原始输入，保证游戏不卡死
+                    arrows
+                }
+            };
+
+            // ② 玩家位置更新（固定步长时本帧可能调用多次）
+            for _ in 0..steps {
+                let state = PlayerState {
+                    x:     game_state.player_position.x,
+                    y:     game_state.player_position.y,
+                    speed: game_state.player_speed,
+                };
+                before_last_step = Some(game_state.player_position);
+
+                let call_start = Instant::now();
+                let update_result = game_world.interface0.call_update_game(
+                    &mut *store,
+                    keyboard,
+                    state,
+                    step_delta,
+                );
+                update_time += call_start.elapsed();
+                match update_result {
+                    // 只记录目标位置，循环结束后统一换算为速度
+                    Ok(result) => game_state.player_position = Vec2::new(result.x, result.y),
+                    Err(e) => eprintln!("[位置更新] WASM 错误 [{}]: {}", name, e),
+                }
+            }
+        }
+
+        // ②' 碰撞通知
+        for normal in &collision_normals {
+            if let Err(e) = game_world.interface0.call_on_collision(&mut *store, normal.x, normal.y) {
+                eprintln!("[碰撞] on-collision WASM 错误 [{}]: {}", name, e);
+            }
+        }
+
+        // ③ 文本输入与 UI 事件（按 UiEventConfig 绑定的按键触发）
+        let call_start = Instant::now();
+        if let Some(input) = &text_input {
+            // 焦点属于其他组件时对本组件显示为 none
+            let focused_key = focused_key.as_deref().and_then(|key| store.data().local_key(key)).map(str::to_string);
+            let input = TextInput { focused_key, ..input.clone() };
+            if let Err(e) = game_world.interface0.call_on_text_input(&mut *store, &input) {
+                eprintln!("[文本输入] on-text-input WASM 错误 [{}]: {}", name, e);
+            }
+        }
+        for event in &ui_events {
+            if let Err(e) = game_world.interface0.call_on_ui_event(&mut *store, event) {
+                eprintln!("[UI事件] WASM 错误 [{}] {}: {}", name, event, e);
+                retries.queue.schedule(WasmCall::OnUiEvent { component: name.clone(), event: event.clone() }, 0, tick_start);
+            }
+        }
+        for event in &locale_events {
+            if let Err(e) = game_world.interface0.call_on_ui_event(&mut *store, event) {
+                eprintln!("[本地化] on-ui-event WASM 错误 [{}]: {}", name, e);
+                retries.queue.schedule(WasmCall::OnUiEvent { component: name.clone(), event: event.clone() }, 0, tick_start);
+            }
+        }
+
+        // ③' 补间完成通知
+        for key in &finished_tweens {
+            let Some(local) = store.data().local_key(key) else { continue; };
+            let event = format!("tween-done:{}", local);
+            if let Err(e) = game_world.interface0.call_on_ui_event(&mut *store, &event) {
+                eprintln!("[补间] on-ui-event WASM 错误 [{}]: {}", name, e);
+                retries.queue.schedule(WasmCall::OnUiEvent { component: name.clone(), event }, 0, tick_start);
+            }
+        }
+        for timer in &fired_timers {
+            let Some(local) = store.data().local_key(timer) else { continue; };
+            let event = format!("timer:{}", local);
+            if let Err(e) = game_world.interface0.call_on_ui_event(&mut *store, &event) {
+                eprintln!("[定时器] on-ui-event WASM 错误 [{}] {}: {}", name, event, e);
+                retries.queue.schedule(WasmCall::OnUiEvent { component: name.clone(), event }, 0, tick_start);
+            }
+        }
+        for DragMoved { key, position } in &drags {
+            let Some(local) = store.data().local_key(key) else { continue; };
+            let event = format!("drag:{}:{}:{}", local, position.x, position.y);
+            if let Err(e) = game_world.interface0.call_on_ui_event(&mut *store, &event) {
+                eprintln!("[拖拽] on-ui-event WASM 错误 [{}] {}: {}", name, event, e);
+                retries.queue.schedule(WasmCall::OnUiEvent { component: name.clone(), event }, 0, tick_start);
+            }
+        }
+        for key in &finished_animations {
+            let Some(local) = store.data().local_key(key) else { continue; };
+            let local = local.to_string();
+            if let Err(e) = game_world.interface0.call_on_animation_complete(&mut *store, &local) {
+                eprintln!("[动画] on-animation-complete WASM 错误 [{}] {}: {}", name, local, e);
+            }
+        }
+        ui_event_time += call_start.elapsed();
+
+        // ③'' 点击通知
+        for key in &clicked {
+            let Some(local) = store.data().local_key(key) else { continue; };
+            let local = local.to_string();
+            if let Err(e) = game_world.interface0.call_on_entity_clicked(&mut *store, &local) {
+                eprintln!("[点击] on-entity-clicked WASM 错误 [{}] {}: {}", name, local, e);
+                retries.queue.schedule(WasmCall::OnEntityClicked { component: name.clone(), key: local }, 0, tick_start);
+            }
+        }
+
+        // ③'''' 焦点变化通知：其他组件的 key 对本组件显示为 none
+        for FocusChanged { old, new } in &focus_changes {
+            let host = store.data();
+            // 焦点实体被销毁：所属组件额外收到 on-ui-event("focus-lost")
+            let lost = new.is_none() && old.as_ref().is_some_and(|key| despawned.contains(key));
+            let old = old.as_deref().and_then(|key| host.local_key(key)).map(str::to_string);
+            let new = new.as_deref().and_then(|key| host.local_key(key)).map(str::to_string);
+            if old.is_none() && new.is_none() {
+                continue;
+            }
+            if let Err(e) = game_world.interface0.call_on_focus_changed(&mut *store, old.as_deref(), new.as_deref()) {
+                eprintln!("[焦点] on-focus-changed WASM 错误 [{}]: {}", name, e);
+            }
+            if lost && old.is_some() {
+                if let Err(e) = game_world.interface0.call_on_ui_event(&mut *store, "focus-lost") {
+                    eprintln!("[焦点] on-ui-event WASM 错误 [{}] focus-lost: {}", name, e);
+                    let event = "focus-lost".to_string();
+                    retries.queue.schedule(WasmCall::OnUiEvent { component: name.clone(), event }, 0, tick_start);
+                }
+            }
+        }
+
+        // ③''' 销毁通知：先转入本组件的 despawned_keys，再逐个回调
+        let host = store.data_mut();
+        let owned: Vec<String> = despawned.iter().filter_map(|key| host.local_key(key)).map(str::to_string).collect();
+        host.despawned_keys.extend(owned);
+        let keys: Vec<String> = store.data_mut().despawned_keys.drain(..).collect();
+        for key in keys {
+            let event = format!("despawned:{}", key);
+            if let Err(e) = game_world.interface0.call_on_ui_event(&mut *store, &event) {
+                eprintln!("[UI] on-ui-event WASM 错误 [{}] {}: {}", name, event, e);
+                retries.queue.schedule(WasmCall::OnUiEvent { component: name.clone(), event }, 0, tick_start);
+            }
+        }
+
+        // ④ 将本帧产生的 UI 命令转移到 PendingUiCommands，供后续 system 消费
+        let host = store.data_mut();
+        queues.ui.spawns.extend(host.spawn_commands.drain(..));
+        queues.ui.mutations.extend(host.mutation_commands.drain(..));
+        queues.scenes.transitions.extend(host.scene_commands.drain(..));
+        queues.audio.commands.extend(host.audio_commands.drain(..));
+        queues.physics.commands.extend(host.physics_commands.drain(..));
+        queues.http.0.extend(host.http_requests.drain(..));
+        for command in host.timer_commands.drain(..) {
+            notifications.timers.apply(command);
+        }
+        for (name, value) in host.shared_var_writes.drain(..) {
+            snapshots.shared_state.set(name, value);
+        }
+        queues.fonts.requests.extend(host.font_loads.drain(..));
+        queues.clipboard.commands.extend(host.clipboard_commands.drain(..));
+        queues.events.0.extend(host.posted_events.drain(..));
+        let dropped = std::mem::take(&mut host.dropped);
+        queues.dropped.spawns += dropped.spawns;
+        queues.dropped.mutations += dropped.mutations;
+        if let Some(request) = host.camera_request.take() {
+            queues.camera.request = Some(request);
+        }
+        clock.paused.set_if_neq(GamePaused(host.paused));
+        if let Some(title) = host.window_title.take() {
+            queues.window.title.0 = Some(title);
+        }
+        if let Some(path) = host.window_icon.take() {
+            queues.window.icon.0 = Some(path);
+        }
+        if let Some(locale) = host.locale_request.take() {
+            queues.locale.0 = Some(locale);
+        }
+        if let Some(position) = host.player_teleport.take() {
+            queues.player_teleport.0 = Some(position);
+        }
+        if let Some(request) = host.focus_request.take() {
+            queues.focus.0 = Some(request);
+        }
+        // 速度直接写回，后续组件与下一帧的 update-game 都使用新值
+        if let Some(speed) = host.player_speed_request.take() {
+            game_state.player_speed = speed;
+        }
+        #[cfg(feature = "debug-draw")]
+        queues.debug_draws.commands.extend(host.debug_draws.drain(..));
+    }
+
+    *metrics = WasmCallMetrics {
+        keyboard_us:    micros(keyboard_time),
+        update_game_us: micros(update_time),
+        ui_event_us:    micros(ui_event_time),
+        total_us:       micros(tick_start.elapsed()),
+        frame:          metrics.frame + 1,
+    };
+    diagnostics.add_measurement(&WASM_UPDATE_GAME_US, || metrics.update_game_us as f64);
+    diagnostics.add_measurement(&WASM_PROCESS_KEYBOARD_US, || metrics.keyboard_us as f64);
+    diagnostics.add_measurement(&WASM_ON_UI_EVENT_US, || metrics.ui_event_us as f64);
+    diagnostics.add_measurement(&WASM_TOTAL_US, || metrics.total_us as f64);
+
+    // 多组件时插值区间为最后一个组件的最后一步
+    if let Some(previous) = before_last_step {
+        clock.fixed.step_completed(previous, game_state.player_position);
+    }
+
+    // 目标位置 → 速度，由物理引擎在 PostUpdate 积分并处理碰撞
+    if let Some((_, start)) = player {
+        let target = match fixed_hz {
+            Some(hz) => clock.fixed.interpolated(hz),
+            None => game_state.player_position,
+        };
+        let velocity = velocity_towards(start, target, frame_delta);
+        for (_, _, mut linear_velocity) in query.iter_mut() {
+            linear_velocity.0 = velocity;
+        }
+    }
+
+    #[cfg(feature = "replay")]
+    if let Some(recorder) = replay_recorder.as_deref_mut() {
+        recorder.record(replay::ReplayFrame {
+            frame:        0, // 由 recorder 分配
+            elapsed_secs,
+            delta_secs:   frame_delta,
+            input: replay::RecordedInput {
+                right: arrows.right,
+                left:  arrows.left,
+                up:    arrows.up,
+                down:  arrows.down,
+                ui_events: ui_events.clone(),
+                clicks:    clicked.clone(),
+            },
+            player_position: (game_state.player_position.x, game_state.player_position.y),
+            spawns:          queues.ui.spawns[spawn_mark..].to_vec(),
+            mutations:       queues.ui.mutations[mutation_mark..].to_vec(),
+        });
+    }
+}
+
+/// 本帧从 start 移动到 target 所需的速度；delta 为 0（如暂停的首帧）时返回零速度
+pub(crate) fn velocity_towards(start: Vec2, target: Vec2, delta: f32) -> Vec2 {
+    if delta > 0.0 { (target - start) / delta } else { Vec2::ZERO }
+}
+
+/// 碰撞中把玩家推开的方向（法线指向玩家一侧）；与玩家无关时返回 None
+/// 玩家锁定了旋转，局部空间法线即世界空间法线
+fn player_collision_normal(player: Entity, contacts: &Contacts) -> Option<Vec2> {
+    let manifold = contacts.manifolds.first()?;
+    if contacts.entity1 == player {
+        Some(-manifold.normal1)
+    } else if contacts.entity2 == player {
+        Some(-manifold.normal2)
+    } else {
+        None
+    }
+}
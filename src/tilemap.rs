@@ -0,0 +1,107 @@
+// ─── 瓦片地图 ─────────────────────────────────────────────────────────────────
+//
+// spawn-tilemap 创建带 Tilemap 的根实体（key 指向它，despawn 与场景切换时连同瓦片一起清理），
+// 每个非空格子是一个子 SpriteSheetBundle，共用同一张纹理与 TextureAtlasLayout。
+// tiles 按行优先排列、第 0 行在最上方，根实体位于地图左下角（世界坐标）。
+// set-tile 经 Tilemap.tiles 找到格子实体直接改 TextureAtlas.index；空格子与非空格子互换时创建 / 销毁子实体。
+// 没有接入 bevy_ecs_tilemap，每格一个精灵，适合数千格以内的关卡。
+
+use super::*;
+
+/// tiles 中表示空格子的索引
+pub const EMPTY_TILE: u16 = u16::MAX;
+
+/// 单张地图的格子数上限
+pub(crate) const MAX_TILEMAP_TILES: usize = 65_536;
+
+#[derive(Component, Debug)]
+pub(crate) struct Tilemap {
+    pub(crate) cols: u32,
+    pub(crate) rows: u32,
+    pub(crate) tile_size: f32,
+    pub(crate) texture: Handle<Image>,
+    pub(crate) layout: Handle<TextureAtlasLayout>,
+    /// 图集格子数（tileset-columns × tileset-rows），set-tile 的索引须小于它
+    pub(crate) tile_count: u32,
+    /// 与 tilemap-config.tiles 一一对应，空格子为 None
+    pub(crate) tiles: Vec<Option<Entity>>,
+}
+
+impl Tilemap {
+    fn tile_bundle(&self, col: u32, row: u32, index: u16) -> SpriteSheetBundle {
+        // 第 0 行在最上方，精灵以中心定位
+        let position = Vec2::new(col as f32 + 0.5, (self.rows - 1 - row) as f32 + 0.5) * self.tile_size;
+        SpriteSheetBundle {
+            sprite: Sprite { custom_size: Some(Vec2::splat(self.tile_size)), ..default() },
+            texture: self.texture.clone(),
+            atlas: TextureAtlas { layout: self.layout.clone(), index: index as usize },
+            transform: Transform::from_translation(position.extend(0.0)),
+            ..default()
+        }
+    }
+
+    /// 在 origin（左下角的世界坐标）创建根实体与 tiles 中的非空格子，返回根实体
+    pub(crate) fn spawn(mut self, commands: &mut Commands, origin: Vec2, tiles: &[u16]) -> Entity {
+        let transform = Transform::from_translation(origin.extend(0.0));
+        let root = commands.spawn((SpatialBundle::from_transform(transform), WitUiPanel)).id();
+        self.tiles = Vec::with_capacity(tiles.len());
+        for (i, &index) in tiles.iter().enumerate() {
+            let (col, row) = (i as u32 % self.cols, i as u32 / self.cols);
+            let tile = (index != EMPTY_TILE)
+                .then(|| commands.spawn(self.tile_bundle(col, row, index)).set_parent(root).id());
+            self.tiles.push(tile);
+        }
+        commands.entity(root).insert(self);
+        root
+    }
+}
+
+/// process_ui_mutations 修改 TextureAtlas 所需的参数（SetAnimation / SetTile）
+#[derive(SystemParam)]
+pub(crate) struct AtlasWrites<'w, 's> {
+    pub(crate) animations: Query<'w, 's, (&'static mut SpriteAnimation, &'static mut TextureAtlas)>,
+    tilemaps:              Query<'w, 's, &'static mut Tilemap>,
+    tiles:                 Query<'w, 's, &'static mut TextureAtlas, Without<SpriteAnimation>>,
+}
+
+/// SetTile：修改 root 地图 (col, row) 格子的图集索引，越界或 root 不是瓦片地图时只打印警告
+pub(crate) fn apply_set_tile(
+    commands: &mut Commands,
+    atlases: &mut AtlasWrites,
+    root: Entity,
+    map_key: &str,
+    col: u32,
+    row: u32,
+    tile_index: u16,
+) {
+    let Ok(mut tilemap) = atlases.tilemaps.get_mut(root) else {
+        warn!("[UI] SetTile 失败：key={} 不是瓦片地图", map_key);
+        return;
+    };
+    if col >= tilemap.cols || row >= tilemap.rows || (tile_index != EMPTY_TILE && tile_index as u32 >= tilemap.tile_count) {
+        warn!(
+            "[UI] SetTile 越界：key={} ({}, {}) index={}，地图 {}x{}，图集 {} 格",
+            map_key, col, row, tile_index, tilemap.cols, tilemap.rows, tilemap.tile_count
+        );
+        return;
+    }
+    let slot = (row * tilemap.cols + col) as usize;
+    match (tilemap.tiles[slot], tile_index) {
+        (None, EMPTY_TILE) => {}
+        (Some(tile), EMPTY_TILE) => {
+            commands.entity(tile).despawn_recursive();
+            tilemap.tiles[slot] = None;
+        }
+        (Some(tile), index) => match atlases.tiles.get_mut(tile) {
+            Ok(mut atlas) => atlas.index = index as usize,
+            // 同一帧刚由 SetTile 创建、Commands 尚未应用
+            Err(_) => {
+                commands.entity(tile).insert(TextureAtlas { layout: tilemap.layout.clone(), index: index as usize });
+            }
+        },
+        (None, index) => {
+            let tile = commands.spawn(tilemap.tile_bundle(col, row, index)).set_parent(root).id();
+            tilemap.tiles[slot] = Some(tile);
+        }
+    }
+}
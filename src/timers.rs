@@ -0,0 +1,60 @@
+// ─── 定时器 ───────────────────────────────────────────────────────────────────
+//
+// set-timer / cancel-timer 只在 HostState 中登记请求，wasm_tick 在步骤④转存到 GuestTimers。
+// tick_timers 在 PreWasm 中按 Time 推进，到期的名字放入 GuestTimers.fired，
+// 同一帧的 wasm_tick 以 on-ui-event("timer:<name>") 通知设置它的组件。
+// 名字与 UI key 一样按组件加命名空间，不同组件的同名定时器互不影响；GamePaused 时不推进。
+
+use super::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TimerCommand {
+    /// 同名定时器已存在时重新计时
+    Set { name: String, seconds: f32, repeating: bool },
+    Cancel { name: String },
+}
+
+/// 进行中的定时器（全局名，按设置顺序排列，到期通知的顺序因此是确定的），
+/// 以及本帧到期、尚未交给组件的名字
+#[derive(Resource, Default)]
+pub struct GuestTimers {
+    pub(crate) timers: Vec<(String, Timer)>,
+    pub(crate) fired:  Vec<String>,
+}
+
+impl GuestTimers {
+    pub(crate) fn apply(&mut self, command: TimerCommand) {
+        match command {
+            TimerCommand::Set { name, seconds, repeating } => {
+                let mode = if repeating { TimerMode::Repeating } else { TimerMode::Once };
+                let timer = Timer::from_seconds(seconds, mode);
+                match self.timers.iter_mut().find(|(n, _)| *n == name) {
+                    Some((_, existing)) => *existing = timer,
+                    None => self.timers.push((name, timer)),
+                }
+            }
+            TimerCommand::Cancel { name } => self.timers.retain(|(n, _)| *n != name),
+        }
+    }
+}
+
+/// 一帧内重复定时器多次到期（如卡顿后）只通知一次，避免回调风暴
+pub(crate) fn tick_timers(time: Res<Time>, paused: Res<GamePaused>, mut timers: ResMut<GuestTimers>) {
+    if paused.0 || timers.timers.is_empty() {
+        return;
+    }
+    let delta = time.delta();
+    let GuestTimers { timers, fired } = &mut *timers;
+    timers.retain_mut(|(name, timer)| {
+        timer.tick(delta);
+        if timer.just_finished() {
+            fired.push(name.clone());
+        }
+        !(timer.mode() == TimerMode::Once && timer.finished())
+    });
+}
+
+pub(crate) fn add_timers(app: &mut App) {
+    app.init_resource::<GuestTimers>()
+        .add_systems(Update, tick_timers.in_set(WasmSchedule::PreWasm));
+}
@@ -0,0 +1,202 @@
+// ─── 补间动画 ─────────────────────────────────────────────────────────────────
+//
+// TS 调用 tween 后只入队一次命令，插值由 update_tweens 按 Time 逐帧推进，
+// 避免每帧跨边界调用 set-position 带来的抖动和开销。
+// 完成后发出 TweenFinished，下一帧 wasm_tick 以 on-ui-event("tween-done:<key>") 通知 TS。
+
+use super::*;
+
+/// 可补间的属性；位置使用 panel-config 坐标系（相对屏幕中心）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "replay", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) enum TweenProperty {
+    PositionX,
+    PositionY,
+    Opacity,
+    Scale,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "replay", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    /// 将线性进度 t（0~1）映射为缓动后的进度（二次曲线）
+    pub(crate) fn apply(self, t: f32) -> f32 {
+        match self {
+            Easing::Linear    => t,
+            Easing::EaseIn    => t * t,
+            Easing::EaseOut   => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut => {
+                if t < 0.5 { 2.0 * t * t } else { 1.0 - (-2.0 * t + 2.0).powi(2) / 2.0 }
+            }
+        }
+    }
+}
+
+impl From<TweenProp> for TweenProperty {
+    fn from(prop: TweenProp) -> Self {
+        match prop {
+            TweenProp::PositionX => TweenProperty::PositionX,
+            TweenProp::PositionY => TweenProperty::PositionY,
+            TweenProp::Opacity   => TweenProperty::Opacity,
+            TweenProp::Scale     => TweenProperty::Scale,
+        }
+    }
+}
+
+impl From<EasingKind> for Easing {
+    fn from(kind: EasingKind) -> Self {
+        match kind {
+            EasingKind::Linear    => Easing::Linear,
+            EasingKind::EaseIn    => Easing::EaseIn,
+            EasingKind::EaseOut   => Easing::EaseOut,
+            EasingKind::EaseInOut => Easing::EaseInOut,
+        }
+    }
+}
+
+pub(crate) struct Tween {
+    /// 全局 key（含命名空间），完成时原样放入 TweenFinished
+    pub(crate) key: String,
+    pub(crate) entity: Entity,
+    pub(crate) property: TweenProperty,
+    /// 起始值在第一次推进时从实体读取，保证从当前值平滑过渡
+    pub(crate) from: Option<f32>,
+    pub(crate) to: f32,
+    pub(crate) duration_secs: f32,
+    pub(crate) elapsed_secs: f32,
+    pub(crate) easing: Easing,
+}
+
+/// 进行中的补间；同一实体同一属性只保留最新的一个
+#[derive(Resource, Default)]
+pub(crate) struct ActiveTweens {
+    pub(crate) tweens: Vec<Tween>,
+}
+
+/// 补间完成事件，携带全局 key；被新补间覆盖或实体已销毁时不会发出
+#[derive(Event, Debug, Clone)]
+pub(crate) struct TweenFinished(pub(crate) String);
+
+/// update_tweens 读写的组件集合
+#[derive(SystemParam)]
+pub(crate) struct TweenTargets<'w, 's> {
+    styles:      Query<'w, 's, &'static mut Style>,
+    transforms:  Query<'w, 's, &'static mut Transform>,
+    texts:       Query<'w, 's, &'static mut Text>,
+    sprites:     Query<'w, 's, &'static mut Sprite>,
+    backgrounds: Query<'w, 's, &'static mut BackgroundColor>,
+}
+
+/// 绝对定位的 UI 节点 left/bottom 含 UI_ORIGIN 偏移，flex 子节点的相对偏移不含
+fn ui_origin_offset(style: &Style, property: TweenProperty) -> f32 {
+    match (style.position_type, property) {
+        (PositionType::Absolute, TweenProperty::PositionX) => UI_ORIGIN.x,
+        (PositionType::Absolute, _) => UI_ORIGIN.y,
+        _ => 0.0,
+    }
+}
+
+impl TweenTargets<'_, '_> {
+    /// 读取属性当前值；实体不存在或缺少对应组件时返回 None
+    fn get(&self, entity: Entity, property: TweenProperty) -> Option<f32> {
+        match property {
+            TweenProperty::PositionX | TweenProperty::PositionY => {
+                if let Ok(style) = self.styles.get(entity) {
+                    let val = if property == TweenProperty::PositionX { style.left } else { style.bottom };
+                    Some(match val {
+                        Val::Px(px) => px - ui_origin_offset(style, property),
+                        _ => 0.0,
+                    })
+                } else {
+                    let transform = self.transforms.get(entity).ok()?;
+                    Some(if property == TweenProperty::PositionX { transform.translation.x } else { transform.translation.y })
+                }
+            }
+            // 与 SetOpacity 相同的判断顺序：Text → Sprite → BackgroundColor
+            TweenProperty::Opacity => {
+                if let Ok(text) = self.texts.get(entity) {
+                    text.sections.first().map(|section| section.style.color.a())
+                } else if let Ok(sprite) = self.sprites.get(entity) {
+                    Some(sprite.color.a())
+                } else {
+                    self.backgrounds.get(entity).ok().map(|background| background.0.a())
+                }
+            }
+            TweenProperty::Scale => self.transforms.get(entity).ok().map(|transform| transform.scale.x),
+        }
+    }
+
+    /// 写入属性值；返回 false 表示目标已失效，补间应被丢弃
+    fn set(&mut self, entity: Entity, property: TweenProperty, value: f32) -> bool {
+        match property {
+            TweenProperty::PositionX | TweenProperty::PositionY => {
+                if let Ok(mut style) = self.styles.get_mut(entity) {
+                    let px = Val::Px(value + ui_origin_offset(&style, property));
+                    if property == TweenProperty::PositionX { style.left = px; } else { style.bottom = px; }
+                } else if let Ok(mut transform) = self.transforms.get_mut(entity) {
+                    if property == TweenProperty::PositionX {
+                        transform.translation.x = value;
+                    } else {
+                        transform.translation.y = value;
+                    }
+                } else {
+                    return false;
+                }
+            }
+            TweenProperty::Opacity => {
+                if let Ok(mut text) = self.texts.get_mut(entity) {
+                    for section in text.sections.iter_mut() {
+                        section.style.color.set_a(value);
+                    }
+                } else if let Ok(mut sprite) = self.sprites.get_mut(entity) {
+                    sprite.color.set_a(value);
+                } else if let Ok(mut background) = self.backgrounds.get_mut(entity) {
+                    background.0.set_a(value);
+                } else {
+                    return false;
+                }
+            }
+            TweenProperty::Scale => {
+                let Ok(mut transform) = self.transforms.get_mut(entity) else { return false; };
+                transform.scale = Vec3::new(value, value, transform.scale.z);
+            }
+        }
+        true
+    }
+}
+
+/// 按 Time 推进所有补间，完成时发出 TweenFinished
+pub(crate) fn update_tweens(
+    time: Res<Time>,
+    mut active: ResMut<ActiveTweens>,
+    mut targets: TweenTargets,
+    mut finished: EventWriter<TweenFinished>,
+) {
+    if active.tweens.is_empty() {
+        return;
+    }
+    let delta = time.delta_seconds();
+    active.tweens.retain_mut(|tween| {
+        // 实体已销毁（如场景切换）或缺少对应组件时静默丢弃
+        let Some(from) = tween.from.or_else(|| targets.get(tween.entity, tween.property)) else { return false; };
+        tween.from = Some(from);
+        tween.elapsed_secs += delta;
+        let t = if tween.duration_secs > 0.0 { (tween.elapsed_secs / tween.duration_secs).min(1.0) } else { 1.0 };
+        let value = from + (tween.to - from) * tween.easing.apply(t);
+        if !targets.set(tween.entity, tween.property, value) {
+            return false;
+        }
+        if t >= 1.0 {
+            finished.send(TweenFinished(tween.key.clone()));
+            return false;
+        }
+        true
+    });
+}
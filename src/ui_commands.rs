@@ -0,0 +1,1144 @@
+// ─── UI 命令 ──────────────────────────────────────────────────────────────────
+//
+// Host 函数入队的命令类型，以及在主线程消费 UI 命令的 process_ui_spawn / process_ui_mutations。
+// 两个系统只读写 PendingUiCommands 与 ECS，完全不接触 WasmRuntime。
+
+use super::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::ui::FocusPolicy;
+
+// ─── UI 命令队列 ─────────────────────────────────────────────────────────────
+//
+// TS 调用 bevy-api 时写入此队列；Bevy system 在主线程消费，
+// 真正操作 ECS，保证线程安全。
+// 拆分为 Spawn 命令和 Mutation 命令两类，分别由不同 system 处理。
+
+/// 面板边框；宽度在 process_ui_spawn 中截断到面板短边的一半
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(any(feature = "replay", feature = "ui-save"), derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct UiBorder {
+    pub(crate) width: f32,
+    pub(crate) color_r: f32, pub(crate) color_g: f32, pub(crate) color_b: f32, pub(crate) color_a: f32,
+}
+
+impl UiBorder {
+    pub(crate) fn color(&self) -> Color {
+        Color::rgba(self.color_r, self.color_g, self.color_b, self.color_a)
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(any(feature = "replay", feature = "ui-save"), derive(serde::Serialize, serde::Deserialize))]
+pub(crate) enum UiSpawnCommand {
+    SpawnPanel {
+        key: String,
+        /// 有父节点时参与父容器的 flex 布局（x/y 被忽略），否则按 x/y 绝对定位
+        parent_key: Option<String>,
+        x: f32, y: f32, width: f32, height: f32,
+        color_r: f32, color_g: f32, color_b: f32, color_a: f32,
+        /// 初始层级（ZIndex::Global），None 时使用 Bevy 默认的 ZIndex::Local(0)
+        z_index: Option<i32>,
+        /// 边框（Style.border + BorderColor），None 时无边框
+        border: Option<UiBorder>,
+        /// 圆角半径（CornerMasks 生成的遮罩），None 时为直角
+        corner_radius: Option<f32>,
+        /// 为 true 时场景切换不会销毁该面板（如血条等常驻 HUD）
+        persistent: bool,
+    },
+    SpawnText {
+        key: String,
+        parent_key: String,
+        text: String, font_size: f32,
+        color_r: f32, color_g: f32, color_b: f32,
+        /// load-font 注册的字体别名，None 或未注册时使用 FontConfig 的默认字体
+        font: Option<String>,
+        /// 相对 assets/ 的字体路径，优先于 font
+        font_path: Option<String>,
+    },
+    /// 非绝对定位的 flex 容器，子节点由 Bevy flexbox 自动排布
+    SpawnContainer {
+        key: String,
+        parent_key: Option<String>,
+        flex_direction: FlexDirection,
+        gap: f32, padding: f32,
+        align_items: AlignItems,
+        justify_content: JustifyContent,
+    },
+    /// 图文混排的横向 flex 行：相邻的文字段合并为一个多 section 的 TextBundle，图标为 ImageBundle
+    SpawnTextWithIcons {
+        key: String,
+        parent_key: Option<String>,
+        segments: Vec<TextSegment>,
+    },
+    /// 纹理图片节点，texture_path 相对 assets/；无父节点时作为根节点
+    SpawnImage {
+        key: String,
+        parent_key: Option<String>,
+        width: f32, height: f32,
+        texture_path: String,
+    },
+    /// 世界空间的精灵图集动画，x/y 为世界坐标，从第 0 帧循环播放全部帧
+    SpawnAnimatedSprite {
+        key: String,
+        x: f32, y: f32,
+        texture_path: String,
+        tile_width: f32, tile_height: f32,
+        columns: u32, rows: u32,
+        fps: f32,
+    },
+    /// 在已有的 UI 节点上播放精灵表动画：sheet_path 按 rows × cols 等分为帧，
+    /// 图片加载完成后从第 0 帧开始播放；非循环动画播完停在最后一帧并通知 TS
+    PlaySpriteAnimation {
+        key: String,
+        sheet_path: String,
+        rows: u32, cols: u32,
+        fps: f32,
+        looping: bool,
+    },
+    /// 单行文本输入框，绝对定位，规则同无父节点的 SpawnPanel
+    SpawnInputField {
+        key: String,
+        x: f32, y: f32, width: f32, height: f32,
+        placeholder: String,
+        max_length: usize,
+    },
+    /// 背景节点 + 子填充节点（key 为 `<key>.fill`），填充宽度为 value * width；定位规则同 SpawnPanel
+    SpawnProgressBar {
+        key: String,
+        parent_key: Option<String>,
+        x: f32, y: f32, width: f32, height: f32,
+        bg_color: [f32; 4],
+        fill_color: [f32; 4],
+        /// 0~1，入队前已钳制
+        value: f32,
+    },
+    /// 世界空间的瓦片地图，x/y 为左下角的世界坐标；tiles 行优先、第 0 行在最上方，EMPTY_TILE 为空格子
+    SpawnTilemap {
+        key: String,
+        x: f32, y: f32,
+        cols: u32, rows: u32,
+        tile_size: f32,
+        tileset_path: String,
+        tileset_columns: u32, tileset_rows: u32,
+        tiles: Vec<u16>,
+    },
+    /// 世界空间文字（Text2dBundle），x/y 为世界坐标，如伤害数字；不属于任何 UI 面板
+    SpawnWorldText {
+        key: String,
+        x: f32, y: f32,
+        text: String, font_size: f32,
+        color_r: f32, color_g: f32, color_b: f32,
+        font: Option<String>,
+    },
+    /// 跟随锚点生成的提示文字，位置按锚点上一帧的布局计算；锚点销毁时自动销毁
+    SpawnTooltip {
+        key: String,
+        anchor_key: String,
+        text: String,
+        max_width: f32,
+    },
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "replay", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) enum UiMutationCommand {
+    Despawn    { key: String },
+    SetVisible { key: String, visible: bool },
+    /// 把 UI 节点放到所有已设置层级的 UI 之上 / 之下（UiStackOrder 分配 ZIndex::Global）
+    BringToFront { key: String },
+    SendToBack { key: String },
+    /// 修改文字第一段的字号 / 颜色，None 的字段保持不变（alpha 不受影响）
+    SetTextStyle { key: String, font_size: Option<f32>, color_r: Option<f32>, color_g: Option<f32>, color_b: Option<f32> },
+    /// 把文字所有段落换成 FontRegistry 中的字体，font_size 为 None 时保持原字号
+    SetFont { key: String, font_alias: String, font_size: Option<f32> },
+    /// 添加 / 移除 Focusable，决定实体是否参与 Tab 焦点切换
+    SetFocusable { key: String, focusable: bool },
+    /// 添加 / 移除 Draggable，决定节点能否被鼠标拖动（见 handle_drag）
+    SetDraggable { key: String, draggable: bool },
+    /// 调整子节点在父节点 Children 中的位置（决定 UI 渲染层叠顺序）
+    ReorderChild { parent_key: String, child_key: String, new_index: u32 },
+    /// 把节点移到另一个父节点的子节点末尾，new_parent_key 为 None 时成为根节点
+    SetParent { key: String, new_parent_key: Option<String> },
+    /// UI 节点设置 ZIndex::Global；世界空间精灵映射到 Transform.translation.z
+    /// 负值的根 UI 节点改由背景 UI 相机渲染，绘制在游戏世界之下（见 UiStackOrder）
+    SetZIndex { key: String, z: i32 },
+    /// 绝对定位的 UI 节点改写 left/bottom（坐标系同 panel-config），世界空间实体改写 Transform 的 x/y
+    SetPosition { key: String, x: f32, y: f32 },
+    /// 只修改颜色 alpha（0~1），不影响 Visibility；仅作用于该 key 自身，不递归子节点
+    SetOpacity { key: String, alpha: f32 },
+    /// 把输入框的当前文本写入 InputValueReadback
+    GetInputValue { key: String },
+    /// 进度条填充宽度改为 value * 背景宽度（value 已钳制到 0~1）
+    SetProgress { key: String, value: f32 },
+    /// 切换精灵动画的帧区间（闭区间）与帧率
+    SetAnimation { key: String, start_index: u32, end_index: u32, fps: f32 },
+    /// 停止 play-sprite-animation 的动画并停在当前帧，不发出完成通知
+    StopAnimation { key: String },
+    /// 修改瓦片地图一个格子的图集索引，EMPTY_TILE 清空该格
+    SetTile { map_key: String, col: u32, row: u32, tile_index: u16 },
+    /// 在 duration_secs 内把属性从当前值插值到 to，由 update_tweens 逐帧推进
+    Tween { key: String, property: TweenProperty, to: f32, duration_secs: f32, easing: Easing },
+}
+
+// ─── 追踪字段 ────────────────────────────────────────────────────────────────
+//
+// 入队 / 消费时的 trace 事件只记录 key 与命令名，按 key 过滤即可跟踪单个节点：
+// RUST_LOG=game=trace
+
+/// UI 管线 span / 事件使用的 tracing target
+pub(crate) const TRACE_TARGET: &str = "game";
+
+impl UiSpawnCommand {
+    pub(crate) fn key(&self) -> &str {
+        match self {
+            UiSpawnCommand::SpawnPanel { key, .. }
+            | UiSpawnCommand::SpawnText { key, .. }
+            | UiSpawnCommand::SpawnContainer { key, .. }
+            | UiSpawnCommand::SpawnTextWithIcons { key, .. }
+            | UiSpawnCommand::SpawnImage { key, .. }
+            | UiSpawnCommand::SpawnAnimatedSprite { key, .. }
+            | UiSpawnCommand::PlaySpriteAnimation { key, .. }
+            | UiSpawnCommand::SpawnInputField { key, .. }
+            | UiSpawnCommand::SpawnProgressBar { key, .. }
+            | UiSpawnCommand::SpawnWorldText { key, .. }
+            | UiSpawnCommand::SpawnTilemap { key, .. }
+            | UiSpawnCommand::SpawnTooltip { key, .. } => key,
+        }
+    }
+
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            UiSpawnCommand::SpawnPanel { .. } => "SpawnPanel",
+            UiSpawnCommand::SpawnText { .. } => "SpawnText",
+            UiSpawnCommand::SpawnContainer { .. } => "SpawnContainer",
+            UiSpawnCommand::SpawnTextWithIcons { .. } => "SpawnTextWithIcons",
+            UiSpawnCommand::SpawnImage { .. } => "SpawnImage",
+            UiSpawnCommand::SpawnAnimatedSprite { .. } => "SpawnAnimatedSprite",
+            UiSpawnCommand::PlaySpriteAnimation { .. } => "PlaySpriteAnimation",
+            UiSpawnCommand::SpawnInputField { .. } => "SpawnInputField",
+            UiSpawnCommand::SpawnProgressBar { .. } => "SpawnProgressBar",
+            UiSpawnCommand::SpawnWorldText { .. } => "SpawnWorldText",
+            UiSpawnCommand::SpawnTilemap { .. } => "SpawnTilemap",
+            UiSpawnCommand::SpawnTooltip { .. } => "SpawnTooltip",
+        }
+    }
+}
+
+impl UiMutationCommand {
+    /// ReorderChild 以被移动的子节点为准
+    pub(crate) fn key(&self) -> &str {
+        match self {
+            UiMutationCommand::ReorderChild { child_key, .. } => child_key,
+            UiMutationCommand::SetTile { map_key, .. } => map_key,
+            UiMutationCommand::Despawn { key }
+            | UiMutationCommand::SetVisible { key, .. }
+            | UiMutationCommand::BringToFront { key }
+            | UiMutationCommand::SendToBack { key }
+            | UiMutationCommand::SetTextStyle { key, .. }
+            | UiMutationCommand::SetFont { key, .. }
+            | UiMutationCommand::SetFocusable { key, .. }
+            | UiMutationCommand::SetDraggable { key, .. }
+            | UiMutationCommand::SetParent { key, .. }
+            | UiMutationCommand::SetZIndex { key, .. }
+            | UiMutationCommand::SetPosition { key, .. }
+            | UiMutationCommand::SetOpacity { key, .. }
+            | UiMutationCommand::GetInputValue { key }
+            | UiMutationCommand::SetProgress { key, .. }
+            | UiMutationCommand::SetAnimation { key, .. }
+            | UiMutationCommand::StopAnimation { key }
+            | UiMutationCommand::Tween { key, .. } => key,
+        }
+    }
+
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            UiMutationCommand::Despawn { .. } => "Despawn",
+            UiMutationCommand::SetVisible { .. } => "SetVisible",
+            UiMutationCommand::BringToFront { .. } => "BringToFront",
+            UiMutationCommand::SendToBack { .. } => "SendToBack",
+            UiMutationCommand::SetTextStyle { .. } => "SetTextStyle",
+            UiMutationCommand::SetFont { .. } => "SetFont",
+            UiMutationCommand::SetFocusable { .. } => "SetFocusable",
+            UiMutationCommand::SetDraggable { .. } => "SetDraggable",
+            UiMutationCommand::ReorderChild { .. } => "ReorderChild",
+            UiMutationCommand::SetParent { .. } => "SetParent",
+            UiMutationCommand::SetZIndex { .. } => "SetZIndex",
+            UiMutationCommand::SetPosition { .. } => "SetPosition",
+            UiMutationCommand::SetOpacity { .. } => "SetOpacity",
+            UiMutationCommand::GetInputValue { .. } => "GetInputValue",
+            UiMutationCommand::SetProgress { .. } => "SetProgress",
+            UiMutationCommand::SetAnimation { .. } => "SetAnimation",
+            UiMutationCommand::StopAnimation { .. } => "StopAnimation",
+            UiMutationCommand::SetTile { .. } => "SetTile",
+            UiMutationCommand::Tween { .. } => "Tween",
+        }
+    }
+}
+
+/// 场景切换请求：TS 调用 load-scene 时写入，handle_scene_transitions 消费
+#[derive(Debug, Clone)]
+pub(crate) enum SceneTransitionCommand {
+    Load(String),
+}
+
+/// 音频命令：TS 调用 play-sound / play-music / stop-music 时写入，process_audio 消费
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum AudioCommand {
+    /// 一次性音效，播放结束后实体自动销毁
+    PlaySound { path: String, volume: f32 },
+    /// 背景音乐，按 key 跟踪；同一 key 再次播放会替换正在播放的音乐
+    PlayMusic { key: String, path: String, looped: bool, volume: f32 },
+    StopMusic { key: String },
+}
+
+/// 物理命令：TS 调用 set-gravity / apply-impulse 时写入，process_physics_commands 消费
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PhysicsCommand {
+    SetGravity(Vec2),
+    /// 一次性冲量，只作用于带 RigidBody 的实体
+    ApplyImpulse { key: String, impulse: Vec2 },
+}
+
+/// SpawnTextWithIcons 的一段
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(any(feature = "replay", feature = "ui-save"), derive(serde::Serialize, serde::Deserialize))]
+pub(crate) enum TextSegment {
+    /// 文字内容、字号、RGB 颜色（使用 FontConfig 的默认字体）
+    Text(String, f32, [f32; 3]),
+    /// 相对 assets/ 的图标路径与边长
+    Icon(String, f32),
+}
+
+/// 消费 Spawn 命令，创建实体，注册 key → Entity 映射
+/// 只访问 PendingUiCommands，完全不接触 WasmRuntime
+pub(crate) fn process_ui_spawn(
+    mut commands: Commands,
+    mut pending: ResMut<PendingUiCommands>,
+    asset_server: Res<AssetServer>,
+    mut entity_map: ResMut<UiEntityMap>,
+    mut atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut persistent_keys: ResMut<PersistentUiKeys>,
+    fonts: Res<FontRegistry>,
+    font_config: Res<FontConfig>,
+    mut asset_handles: ResMut<UiAssetHandles>,
+    mut stack_order: ResMut<UiStackOrder>,
+    layouts: Query<(&Node, &GlobalTransform)>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut layout: LayoutChecks,
+    mut corner_masks: CornerMasks,
+) {
+    let cmds: Vec<_> = pending.spawns.drain(..).collect();
+
+    if cmds.is_empty() {
+        return;
+    }
+    let _span = info_span!(target: TRACE_TARGET, "process_ui_spawn", count = cmds.len()).entered();
+
+    for cmd in cmds {
+        trace!(target: TRACE_TARGET, key = cmd.key(), kind = cmd.kind(), "spawn consumed");
+        for warning in layout.engine.validate_spawn(&cmd, &layout.screen) {
+            warn!("[UI] 布局警告 key={}: {:?}", cmd.key(), warning);
+            layout.warnings.0.push((cmd.key().to_string(), warning));
+        }
+        match cmd {
+            UiSpawnCommand::SpawnPanel { key, parent_key, x, y, width, height, color_r, color_g, color_b, color_a, z_index, border, corner_radius, persistent } => {
+                // 有父节点时交给父容器 flex 布局，否则绝对定位
+                let parent_entity = match &parent_key {
+                    Some(parent_key) => {
+                        let Some(parent) = entity_map.get_or_warn("SpawnPanel", parent_key) else { continue; };
+                        Some(parent)
+                    }
+                    None => None,
+                };
+                if let Some(z) = z_index {
+                    stack_order.observe(z);
+                }
+                let (position_type, left, bottom) = match parent_entity {
+                    Some(_) => (PositionType::Relative, Val::Auto, Val::Auto),
+                    None => (PositionType::Absolute, Val::Px(x + UI_ORIGIN.x), Val::Px(y + UI_ORIGIN.y)),
+                };
+                // 两侧边框之和不能超过面板尺寸，否则内容区为负
+                let border_width = border.map_or(0.0, |b| b.width.min(width.min(height) / 2.0));
+                let entity = commands.spawn((
+                    NodeBundle {
+                        style: Style {
+                            position_type,
+                            left,
+                            bottom,
+                            width:  Val::Px(width),
+                            height: Val::Px(height),
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            justify_content: JustifyContent::Center,
+                            padding: UiRect::all(Val::Px(12.0)),
+                            row_gap: Val::Px(8.0),
+                            border: UiRect::all(Val::Px(border_width)),
+                            ..default()
+                        },
+                        background_color: Color::rgba(color_r, color_g, color_b, color_a).into(),
+                        border_color: BorderColor(border.map_or(Color::NONE, |b| b.color())),
+                        z_index: z_index.map_or(ZIndex::Local(0), ZIndex::Global),
+                        // 阻止点击穿透到下层面板，嵌套时只有最上层收到 on-entity-clicked
+                        focus_policy: FocusPolicy::Block,
+                        ..default()
+                    },
+                    Interaction::default(),
+                    WitUiPanel,
+                )).id();
+
+                // Bevy 0.13 的 UI 没有 BorderRadius：以圆角遮罩作为 UiImage，由 BackgroundColor 着色
+                if let Some(mask) = corner_radius.and_then(|radius| corner_masks.get(width, height, radius)) {
+                    commands.entity(entity).insert((UiImage::new(mask), RoundedCorners));
+                }
+                match (parent_entity, z_index) {
+                    (Some(parent), _) => { commands.entity(parent).add_child(entity); }
+                    // 负层级的根面板绘制在游戏世界之下
+                    (None, Some(z)) => stack_order.assign_camera(&mut commands.entity(entity), z),
+                    (None, None) => {}
+                }
+                entity_map.insert(key.clone(), entity, parent_key.as_deref());
+                // 同一 key 以普通面板重新创建时取消常驻
+                if persistent {
+                    persistent_keys.0.insert(key.clone());
+                } else {
+                    persistent_keys.0.remove(&key);
+                }
+                println!("[UI] 创建面板 key={} entity={:?} 常驻={}", key, entity, persistent);
+            }
+
+            UiSpawnCommand::SpawnContainer { key, parent_key, flex_direction, gap, padding, align_items, justify_content } => {
+                let parent_entity = match &parent_key {
+                    Some(parent_key) => {
+                        let Some(parent) = entity_map.get_or_warn("SpawnContainer", parent_key) else { continue; };
+                        Some(parent)
+                    }
+                    None => None,
+                };
+                let entity = commands.spawn((
+                    NodeBundle {
+                        style: container_style(flex_direction, gap, padding, align_items, justify_content),
+                        ..default()
+                    },
+                    WitUiPanel,
+                )).id();
+
+                if let Some(parent) = parent_entity {
+                    commands.entity(parent).add_child(entity);
+                }
+                entity_map.insert(key.clone(), entity, parent_key.as_deref());
+                println!("[UI] 创建容器 key={} entity={:?}", key, entity);
+            }
+
+            UiSpawnCommand::SpawnAnimatedSprite { key, x, y, texture_path, tile_width, tile_height, columns, rows, fps } => {
+                let layout = TextureAtlasLayout::from_grid(
+                    Vec2::new(tile_width, tile_height),
+                    columns as usize,
+                    rows as usize,
+                    None,
+                    None,
+                );
+                let frame_count = columns as usize * rows as usize;
+                let texture: Handle<Image> = asset_server.load(texture_path);
+                asset_handles.map.insert(key.clone(), texture.clone().untyped());
+                let entity = commands.spawn((
+                    SpriteSheetBundle {
+                        // 显式设置显示尺寸，overlaps 据此计算包围盒
+                        sprite: Sprite { custom_size: Some(Vec2::new(tile_width, tile_height)), ..default() },
+                        texture,
+                        atlas: TextureAtlas { layout: atlas_layouts.add(layout), index: 0 },
+                        transform: Transform::from_xyz(x, y, 0.0),
+                        ..default()
+                    },
+                    SpriteAnimation { first: 0, last: frame_count - 1, fps, elapsed_secs: 0.0, frame_count, looping: true },
+                    WitUiPanel,
+                )).id();
+                entity_map.insert(key.clone(), entity, None);
+                println!("[UI] 创建动画精灵 key={} entity={:?} 帧数={}", key, entity, frame_count);
+            }
+
+            UiSpawnCommand::SpawnTilemap { key, x, y, cols, rows, tile_size, tileset_path, tileset_columns, tileset_rows, tiles } => {
+                let layout = TextureAtlasLayout::from_grid(
+                    Vec2::splat(tile_size),
+                    tileset_columns as usize,
+                    tileset_rows as usize,
+                    None,
+                    None,
+                );
+                let texture: Handle<Image> = asset_server.load(tileset_path);
+                asset_handles.map.insert(key.clone(), texture.clone().untyped());
+                let tilemap = Tilemap {
+                    cols, rows, tile_size, texture,
+                    layout: atlas_layouts.add(layout),
+                    tile_count: tileset_columns.saturating_mul(tileset_rows),
+                    tiles: Vec::new(),
+                };
+                let root = tilemap.spawn(&mut commands, Vec2::new(x, y), &tiles);
+                entity_map.insert(key.clone(), root, None);
+                println!("[UI] 创建瓦片地图 key={} entity={:?} {}x{}", key, root, cols, rows);
+            }
+
+            UiSpawnCommand::PlaySpriteAnimation { key, sheet_path, rows, cols, fps, looping } => {
+                // 目标可能是同一帧刚创建的节点（Commands 尚未应用），因此不检查是否已有 Node
+                let Some(entity) = entity_map.get_or_warn("PlaySpriteAnimation", &key) else { continue; };
+                let sheet: Handle<Image> = asset_server.load(sheet_path.clone());
+                asset_handles.map.insert(key.clone(), sheet.clone().untyped());
+                commands.entity(entity)
+                    .remove::<(SpriteAnimation, TextureAtlas, RoundedCorners)>()
+                    .insert((UiImage::new(sheet), PendingSpriteSheet { rows, cols, fps, looping }));
+                println!("[UI] 播放精灵表动画 key={} path={} {}x{} fps={} looping={}", key, sheet_path, rows, cols, fps, looping);
+            }
+
+            UiSpawnCommand::SpawnInputField { key, x, y, width, height, placeholder, max_length } => {
+                let state = InputFieldState { placeholder, max_length, ..default() };
+                let (value, color) = state.display();
+                let entity = commands.spawn((
+                    TextBundle::from_section(
+                        value,
+                        TextStyle { font: asset_server.load(&font_config.default_font), font_size: height * 0.6, color },
+                    )
+                    .with_style(Style {
+                        position_type: PositionType::Absolute,
+                        left:   Val::Px(x + UI_ORIGIN.x),
+                        bottom: Val::Px(y + UI_ORIGIN.y),
+                        width:  Val::Px(width),
+                        height: Val::Px(height),
+                        padding: UiRect::horizontal(Val::Px(6.0)),
+                        ..default()
+                    })
+                    .with_background_color(Color::rgba(0.1, 0.1, 0.1, 0.9)),
+                    Interaction::default(),
+                    FocusPolicy::Block,
+                    state,
+                    WitUiPanel,
+                )).id();
+                entity_map.insert(key.clone(), entity, None);
+                println!("[UI] 创建输入框 key={} entity={:?}", key, entity);
+            }
+
+            UiSpawnCommand::SpawnProgressBar { key, parent_key, x, y, width, height, bg_color, fill_color, value } => {
+                let parent_entity = match &parent_key {
+                    Some(parent_key) => {
+                        let Some(parent) = entity_map.get_or_warn("SpawnProgressBar", parent_key) else { continue; };
+                        Some(parent)
+                    }
+                    None => None,
+                };
+                let (position_type, left, bottom) = match parent_entity {
+                    Some(_) => (PositionType::Relative, Val::Auto, Val::Auto),
+                    None => (PositionType::Absolute, Val::Px(x + UI_ORIGIN.x), Val::Px(y + UI_ORIGIN.y)),
+                };
+                let [r, g, b, a] = fill_color;
+                let fill = commands.spawn(NodeBundle {
+                    style: Style {
+                        width:  Val::Px(value * width),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    background_color: Color::rgba(r, g, b, a).into(),
+                    ..default()
+                }).id();
+                let [r, g, b, a] = bg_color;
+                let entity = commands.spawn((
+                    NodeBundle {
+                        style: Style {
+                            position_type,
+                            left,
+                            bottom,
+                            width:  Val::Px(width),
+                            height: Val::Px(height),
+                            ..default()
+                        },
+                        background_color: Color::rgba(r, g, b, a).into(),
+                        ..default()
+                    },
+                    ProgressBar { fill, width },
+                    WitUiPanel,
+                )).add_child(fill).id();
+
+                if let Some(parent) = parent_entity {
+                    commands.entity(parent).add_child(entity);
+                }
+                entity_map.insert(key.clone(), entity, parent_key.as_deref());
+                entity_map.insert(format!("{}.fill", key), fill, Some(&key));
+                println!("[UI] 创建进度条 key={} entity={:?} value={}", key, entity, value);
+            }
+
+            UiSpawnCommand::SpawnTextWithIcons { key, parent_key, segments } => {
+                let parent_entity = match &parent_key {
+                    Some(parent_key) => {
+                        let Some(parent) = entity_map.get_or_warn("SpawnTextWithIcons", parent_key) else { continue; };
+                        Some(parent)
+                    }
+                    None => None,
+                };
+                let row = commands.spawn((
+                    NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            align_items:    AlignItems::Center,
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    WitUiPanel,
+                )).id();
+
+                let mut run = Vec::new();
+                for segment in segments {
+                    match segment {
+                        TextSegment::Text(value, font_size, [r, g, b]) => run.push(TextSection::new(
+                            value,
+                            TextStyle { font: asset_server.load(&font_config.default_font), font_size, color: Color::rgb(r, g, b) },
+                        )),
+                        TextSegment::Icon(path, size) => {
+                            push_text_run(&mut commands, row, &mut run);
+                            let icon = commands.spawn(ImageBundle {
+                                style: Style { width: Val::Px(size), height: Val::Px(size), ..default() },
+                                image: UiImage::new(asset_server.load(path)),
+                                ..default()
+                            }).id();
+                            commands.entity(row).add_child(icon);
+                        }
+                    }
+                }
+                push_text_run(&mut commands, row, &mut run);
+
+                if let Some(parent) = parent_entity {
+                    commands.entity(parent).add_child(row);
+                }
+                entity_map.insert(key.clone(), row, parent_key.as_deref());
+                println!("[UI] 创建图文混排 key={} entity={:?}", key, row);
+            }
+
+            UiSpawnCommand::SpawnImage { key, parent_key, width, height, texture_path } => {
+                let parent_entity = match &parent_key {
+                    Some(parent_key) => {
+                        let Some(parent) = entity_map.get_or_warn("SpawnImage", parent_key) else { continue; };
+                        Some(parent)
+                    }
+                    None => None,
+                };
+                let texture: Handle<Image> = asset_server.load(texture_path);
+                asset_handles.map.insert(key.clone(), texture.clone().untyped());
+                let entity = commands.spawn((
+                    ImageBundle {
+                        style: Style {
+                            width:  Val::Px(width),
+                            height: Val::Px(height),
+                            ..default()
+                        },
+                        image: UiImage::new(texture),
+                        ..default()
+                    },
+                    PendingUiImage { key: key.clone() },
+                    WitUiPanel,
+                )).id();
+
+                if let Some(parent) = parent_entity {
+                    commands.entity(parent).add_child(entity);
+                }
+                entity_map.insert(key.clone(), entity, parent_key.as_deref());
+                println!("[UI] 创建图片 key={} entity={:?}", key, entity);
+            }
+
+            UiSpawnCommand::SpawnWorldText { key, x, y, text, font_size, color_r, color_g, color_b, font } => {
+                let font = font_or_default(&fonts, &font_config, &asset_server, "SpawnWorldText", &key, font.as_deref(), None);
+                let entity = commands.spawn((
+                    Text2dBundle {
+                        text: Text::from_section(text, TextStyle { font, font_size, color: Color::rgb(color_r, color_g, color_b) }),
+                        transform: Transform::from_xyz(x, y, 0.0),
+                        ..default()
+                    },
+                    WitUiPanel,
+                )).id();
+                entity_map.insert(key.clone(), entity, None);
+                println!("[UI] 创建世界文字 key={} entity={:?} 位置=({}, {})", key, entity, x, y);
+            }
+
+            UiSpawnCommand::SpawnTooltip { key, anchor_key, text, max_width } => {
+                let Some(anchor) = entity_map.get_or_warn("SpawnTooltip", &anchor_key) else { continue; };
+                // 锚点需已完成一次布局（至少在上一帧创建），否则无法确定象限
+                let anchor_rect = match layouts.get(anchor) {
+                    Ok((node, transform)) if node.size() != Vec2::ZERO => {
+                        Rect::from_center_size(transform.translation().truncate(), node.size())
+                    }
+                    _ => {
+                        warn!("[UI] SpawnTooltip 失败：锚点 key={} 尚未完成布局", anchor_key);
+                        continue;
+                    }
+                };
+                let window = windows
+                    .get_single()
+                    .map(|w| Vec2::new(w.width(), w.height()))
+                    .unwrap_or(UI_ORIGIN * 2.0);
+                let label = commands.spawn(TextBundle::from_section(
+                    text,
+                    TextStyle { font: asset_server.load(&font_config.default_font), font_size: 14.0, color: Color::WHITE },
+                )).id();
+                let entity = commands.spawn((
+                    NodeBundle {
+                        style: tooltip_style(anchor_rect, window, max_width),
+                        background_color: Color::rgba(0.05, 0.05, 0.05, 0.9).into(),
+                        z_index: ZIndex::Global(TOOLTIP_Z_INDEX),
+                        ..default()
+                    },
+                    TooltipAnchor(anchor),
+                    WitUiPanel,
+                )).add_child(label).id();
+                entity_map.insert(key.clone(), entity, None);
+                println!("[UI] 创建 tooltip key={} anchor={} entity={:?}", key, anchor_key, entity);
+            }
+
+            UiSpawnCommand::SpawnText { key, parent_key, text, font_size, color_r, color_g, color_b, font, font_path } => {
+                let parent_entity = match entity_map.map.get(&parent_key) {
+                    Some(&e) => e,
+                    None => {
+                        eprintln!("[UI] SpawnText 失败：找不到父实体 key={}", parent_key);
+                        continue;
+                    }
+                };
+                let font = font_or_default(&fonts, &font_config, &asset_server, "SpawnText", &key, font.as_deref(), font_path.as_deref());
+                let text_entity = commands.spawn(
+                    TextBundle::from_section(
+                        text,
+                        TextStyle {
+                            font,
+                            font_size,
+                            color: Color::rgb(color_r, color_g, color_b),
+                        },
+                    )
+                ).id();
+                commands.entity(parent_entity).add_child(text_entity);
+                entity_map.insert(key.clone(), text_entity, Some(&parent_key));
+                println!("[UI] 创建文字 key={} entity={:?}", key, text_entity);
+            }
+        }
+    }
+}
+
+/// path（配置中的 font-path）优先于别名 alias；否则按 load-font 注册的别名取字体，未指定或未注册时使用默认字体
+fn font_or_default(
+    fonts: &FontRegistry,
+    config: &FontConfig,
+    asset_server: &AssetServer,
+    op: &str,
+    key: &str,
+    alias: Option<&str>,
+    path: Option<&str>,
+) -> Handle<Font> {
+    // 显式路径优先于别名
+    if let Some(path) = path {
+        return asset_server.load(path);
+    }
+    match alias.map(|alias| (alias, fonts.map.get(alias))) {
+        Some((_, Some(handle))) => handle.clone(),
+        Some((alias, None)) => {
+            warn!("[UI] {} key={} 使用的字体别名 {} 未注册，改用默认字体", op, key, alias);
+            asset_server.load(&config.default_font)
+        }
+        None => asset_server.load(&config.default_font),
+    }
+}
+
+/// 圆角遮罩单边的最大像素数，更大的面板按比例缩小生成（边缘略模糊）
+const MAX_CORNER_MASK_SIZE: f32 = 1024.0;
+
+/// 圆角面板的遮罩纹理：白色，圆角之外透明。相同像素尺寸与半径的面板共用一张
+#[derive(SystemParam)]
+pub(crate) struct CornerMasks<'w, 's> {
+    images: ResMut<'w, Assets<Image>>,
+    cache:  Local<'s, HashMap<(u32, u32, u32), Handle<Image>>>,
+}
+
+impl CornerMasks<'_, '_> {
+    /// width × height 面板的遮罩；半径截断到短边的一半，不足 1 像素时返回 None（直角，无需遮罩）
+    pub(crate) fn get(&mut self, width: f32, height: f32, radius: f32) -> Option<Handle<Image>> {
+        let scale = (MAX_CORNER_MASK_SIZE / width.max(height)).min(1.0);
+        let (w, h) = ((width * scale).ceil().max(1.0) as u32, (height * scale).ceil().max(1.0) as u32);
+        let r = (radius * scale).min(w.min(h) as f32 / 2.0).round() as u32;
+        if r == 0 {
+            return None;
+        }
+        let CornerMasks { images, cache } = self;
+        Some(cache.entry((w, h, r)).or_insert_with(|| images.add(rounded_rect_mask(w, h, r as f32))).clone())
+    }
+}
+
+/// 标记：节点的 UiImage 是圆角遮罩，节点本身仍是面板（UI 树快照据此不把它当作图片）
+#[derive(Component, Debug)]
+pub(crate) struct RoundedCorners;
+
+/// 按像素中心到圆角圆心的距离计算 alpha，边缘有一像素宽的抗锯齿
+fn rounded_rect_mask(width: u32, height: u32, radius: f32) -> Image {
+    let size = Vec2::new(width as f32, height as f32);
+    let mut data = Vec::with_capacity(width as usize * height as usize * 4);
+    for y in 0..height {
+        for x in 0..width {
+            let p = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+            let center = p.clamp(Vec2::splat(radius), size - radius);
+            let alpha = (radius + 0.5 - p.distance(center)).clamp(0.0, 1.0);
+            data.extend_from_slice(&[255, 255, 255, (alpha * 255.0).round() as u8]);
+        }
+    }
+    Image::new(
+        Extent3d { width, height, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
+    )
+}
+
+/// 把累积的文字段作为一个 TextBundle 追加到 row 末尾，并清空 run
+fn push_text_run(commands: &mut Commands, row: Entity, run: &mut Vec<TextSection>) {
+    if run.is_empty() {
+        return;
+    }
+    let text = commands.spawn(TextBundle::from_sections(std::mem::take(run))).id();
+    commands.entity(row).add_child(text);
+}
+
+/// tooltip 的绝对定位：锚点在左半屏时左对齐向右展开，在右半屏时右对齐向左展开；
+/// 在上半屏时显示在锚点下方，否则显示在上方。anchor 与 window 均为 UI 坐标（左上角原点，y 轴向下），
+/// 只设置朝外的一侧边距，tooltip 的实际尺寸由文字决定，不需要预先测量
+pub(crate) fn tooltip_style(anchor: Rect, window: Vec2, max_width: f32) -> Style {
+    let center = anchor.center();
+    let (left, right) = if center.x < window.x / 2.0 {
+        (Val::Px(anchor.min.x), Val::Auto)
+    } else {
+        (Val::Auto, Val::Px(window.x - anchor.max.x))
+    };
+    let (top, bottom) = if center.y < window.y / 2.0 {
+        (Val::Px(anchor.max.y + TOOLTIP_GAP), Val::Auto)
+    } else {
+        (Val::Auto, Val::Px(window.y - anchor.min.y + TOOLTIP_GAP))
+    };
+    Style {
+        position_type: PositionType::Absolute,
+        left,
+        right,
+        top,
+        bottom,
+        max_width: Val::Px(max_width.min(window.x)),
+        padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+        ..default()
+    }
+}
+
+/// 消费 Mutation 命令（despawn / set-visible / reorder-child / set-z-index / set-opacity），通过 key 查映射表操作实体
+/// 运行在 apply_deferred 之后，保证 process_ui_spawn 创建的实体已真正写入 World
+/// 只访问 PendingUiCommands，完全不接触 WasmRuntime
+pub(crate) fn process_ui_mutations(
+    mut commands: Commands,
+    mut pending: ResMut<PendingUiCommands>,
+    mut entity_map: ResMut<UiEntityMap>,
+    children_query: Query<&Children>,
+    ui_nodes: Query<Has<Parent>, With<Node>>,
+    mut world_transforms: Query<&mut Transform, Without<Node>>,
+    mut texts: Query<&mut Text>,
+    mut sprites: Query<&mut Sprite>,
+    mut backgrounds: Query<&mut BackgroundColor>,
+    mut tweens: ResMut<ActiveTweens>,
+    mut atlases: AtlasWrites,
+    mut despawned: ResMut<DespawnedUiKeys>,
+    mut stack_order: ResMut<UiStackOrder>,
+    mut input_fields: InputFieldReads,
+    fonts: Res<FontRegistry>,
+    mut style_writes: StyleWrites,
+) {
+    let cmds: Vec<_> = pending.mutations.drain(..).collect();
+
+    if cmds.is_empty() {
+        return;
+    }
+    let _span = info_span!(target: TRACE_TARGET, "process_ui_mutations", count = cmds.len()).entered();
+
+    for cmd in cmds {
+        trace!(target: TRACE_TARGET, key = cmd.key(), kind = cmd.kind(), "mutation consumed");
+        match cmd {
+            UiMutationCommand::Despawn { key } => {
+                let Some(entity) = entity_map.get_or_warn("Despawn", &key) else { continue; };
+                commands.entity(entity).despawn_recursive();
+                let removed = entity_map.remove_subtree(&key);
+                println!("[UI] 销毁实体 key={}（共 {} 个 key）", key, removed.len());
+                despawned.0.extend(removed);
+            }
+
+            UiMutationCommand::SetVisible { key, visible } => {
+                let Some(entity) = entity_map.get_or_warn("SetVisible", &key) else { continue; };
+                // 使用 Visibility 组件控制显隐，避免覆盖 Style 导致布局丢失
+                let visibility = if visible { Visibility::Visible } else { Visibility::Hidden };
+                commands.entity(entity).insert(visibility);
+                println!("[UI] 设置可见性 key={} visible={}", key, visible);
+            }
+
+            UiMutationCommand::GetInputValue { key } => {
+                let text = entity_map.get_or_warn("GetInputValue", &key)
+                    .and_then(|entity| input_fields.fields.get(entity).ok())
+                    .map(|state| state.text.clone());
+                match text {
+                    Some(text) => { input_fields.readback.values.insert(key, text); }
+                    None => { input_fields.readback.values.remove(&key); }
+                }
+            }
+
+            UiMutationCommand::StopAnimation { key } => {
+                let Some(entity) = entity_map.get_or_warn("StopAnimation", &key) else { continue; };
+                commands.entity(entity).remove::<(SpriteAnimation, PendingSpriteSheet)>();
+                println!("[UI] 停止动画 key={}", key);
+            }
+
+            UiMutationCommand::SetTile { map_key, col, row, tile_index } => {
+                let Some(root) = entity_map.get_or_warn("SetTile", &map_key) else { continue; };
+                apply_set_tile(&mut commands, &mut atlases, root, &map_key, col, row, tile_index);
+            }
+
+            UiMutationCommand::SetProgress { key, value } => {
+                let Some(entity) = entity_map.get_or_warn("SetProgress", &key) else { continue; };
+                let Ok(bar) = style_writes.bars.get(entity) else {
+                    warn!("[UI] SetProgress 失败：key={} 不是进度条", key);
+                    continue;
+                };
+                let (fill, width) = (bar.fill, bar.width);
+                if let Ok(mut style) = style_writes.styles.get_mut(fill) {
+                    style.width = Val::Px(value * width);
+                }
+            }
+
+            UiMutationCommand::SetFocusable { key, focusable } => {
+                let Some(entity) = entity_map.get_or_warn("SetFocusable", &key) else { continue; };
+                if focusable {
+                    commands.entity(entity).insert(Focusable);
+                } else {
+                    commands.entity(entity).remove::<Focusable>();
+                }
+                println!("[UI] 设置可聚焦 key={} focusable={}", key, focusable);
+            }
+
+            UiMutationCommand::SetDraggable { key, draggable } => {
+                let Some(entity) = entity_map.get_or_warn("SetDraggable", &key) else { continue; };
+                if draggable {
+                    commands.entity(entity).insert(Draggable);
+                } else {
+                    commands.entity(entity).remove::<Draggable>();
+                }
+                println!("[UI] 设置可拖拽 key={} draggable={}", key, draggable);
+            }
+
+            UiMutationCommand::ReorderChild { parent_key, child_key, new_index } => {
+                let Some(parent) = entity_map.get_or_warn("ReorderChild", &parent_key) else { continue; };
+                let Some(child) = entity_map.get_or_warn("ReorderChild", &child_key) else { continue; };
+                // 先移除自身再插入，因此可用的最大位置为「其余子节点数」
+                let sibling_count = children_query
+                    .get(parent)
+                    .map(|children| children.iter().filter(|&&c| c != child).count())
+                    .unwrap_or(0);
+                let mut index = new_index as usize;
+                if index > sibling_count {
+                    warn!(
+                        "[UI] ReorderChild 越界：key={} new_index={} 超出子节点数 {}，已移至末尾",
+                        child_key, new_index, sibling_count
+                    );
+                    index = sibling_count;
+                }
+                commands.entity(parent).insert_children(index, &[child]);
+                println!("[UI] 调整子节点顺序 parent={} child={} index={}", parent_key, child_key, index);
+            }
+
+            UiMutationCommand::SetParent { key, new_parent_key } => {
+                let Some(child) = entity_map.get_or_warn("SetParent", &key) else { continue; };
+                let new_parent = match &new_parent_key {
+                    Some(parent_key) => match entity_map.get_or_warn("SetParent", parent_key) {
+                        Some(parent) => Some(parent),
+                        None => continue,
+                    },
+                    None => None,
+                };
+                if !entity_map.reparent(&key, new_parent_key.as_deref()) {
+                    warn!("[UI] SetParent 失败：key={} 不能挂到自身或其后代 {} 之下", key, new_parent_key.unwrap_or_default());
+                    continue;
+                }
+                match new_parent {
+                    Some(parent) => commands.entity(child).set_parent(parent),
+                    None => commands.entity(child).remove_parent(),
+                };
+                println!("[UI] 移动节点 key={} parent={}", key, new_parent_key.as_deref().unwrap_or("<root>"));
+            }
+
+            UiMutationCommand::SetPosition { key, x, y } => {
+                let Some(entity) = entity_map.get_or_warn("SetPosition", &key) else { continue; };
+                if let Ok(mut style) = style_writes.styles.get_mut(entity) {
+                    if style.position_type != PositionType::Absolute {
+                        warn!("[UI] SetPosition 失败：key={} 参与父容器的 flex 布局，位置由布局决定", key);
+                        continue;
+                    }
+                    style.left = Val::Px(x + UI_ORIGIN.x);
+                    style.bottom = Val::Px(y + UI_ORIGIN.y);
+                } else if let Ok(mut transform) = world_transforms.get_mut(entity) {
+                    transform.translation.x = x;
+                    transform.translation.y = y;
+                } else {
+                    warn!("[UI] SetPosition 失败：key={} 既没有 Style 也没有 Transform", key);
+                    continue;
+                }
+            }
+
+            UiMutationCommand::SetZIndex { key, z } => {
+                let Some(entity) = entity_map.get_or_warn("SetZIndex", &key) else { continue; };
+                if let Ok(has_parent) = ui_nodes.get(entity) {
+                    // Global 脱离父子层级参与全局排序，数值越大越靠上
+                    commands.entity(entity).insert(ZIndex::Global(z));
+                    stack_order.observe(z);
+                    // 根节点按正负切换相机，子节点跟随根节点所在的相机
+                    if !has_parent {
+                        stack_order.assign_camera(&mut commands.entity(entity), z);
+                    }
+                } else if let Ok(mut transform) = world_transforms.get_mut(entity) {
+                    transform.translation.z = z as f32;
+                } else {
+                    warn!("[UI] SetZIndex 失败：key={} 既不是 UI 节点也没有 Transform", key);
+                    continue;
+                }
+                println!("[UI] 设置层级 key={} z={}", key, z);
+            }
+
+            UiMutationCommand::BringToFront { key } => {
+                let Some(entity) = entity_map.get_or_warn("BringToFront", &key) else { continue; };
+                if !ui_nodes.contains(entity) {
+                    warn!("[UI] BringToFront 失败：key={} 不是 UI 节点", key);
+                    continue;
+                }
+                let z = stack_order.front();
+                // 在背景相机上的根节点回到主相机，才能盖住所有 UI
+                let mut node = commands.entity(entity);
+                node.insert(ZIndex::Global(z));
+                stack_order.assign_camera(&mut node, z);
+                println!("[UI] 置顶 key={} z={}", key, z);
+            }
+
+            UiMutationCommand::SendToBack { key } => {
+                let Some(entity) = entity_map.get_or_warn("SendToBack", &key) else { continue; };
+                if !ui_nodes.contains(entity) {
+                    warn!("[UI] SendToBack 失败：key={} 不是 UI 节点", key);
+                    continue;
+                }
+                let z = stack_order.back();
+                commands.entity(entity).insert(ZIndex::Global(z));
+                println!("[UI] 置底 key={} z={}", key, z);
+            }
+
+            UiMutationCommand::SetOpacity { key, alpha } => {
+                let Some(entity) = entity_map.get_or_warn("SetOpacity", &key) else { continue; };
+                // 文字节点同样带 BackgroundColor（默认透明），因此先判断 Text
+                if let Ok(mut text) = texts.get_mut(entity) {
+                    for section in text.sections.iter_mut() {
+                        section.style.color.set_a(alpha);
+                    }
+                } else if let Ok(mut sprite) = sprites.get_mut(entity) {
+                    sprite.color.set_a(alpha);
+                } else if let Ok(mut background) = backgrounds.get_mut(entity) {
+                    background.0.set_a(alpha);
+                } else {
+                    warn!("[UI] SetOpacity 失败：key={} 没有可设置颜色的组件", key);
+                    continue;
+                }
+                println!("[UI] 设置透明度 key={} alpha={}", key, alpha);
+            }
+
+            UiMutationCommand::SetTextStyle { key, font_size, color_r, color_g, color_b } => {
+                let Some(entity) = entity_map.get_or_warn("SetTextStyle", &key) else { continue; };
+                let Ok(mut text) = texts.get_mut(entity) else {
+                    warn!("[UI] SetTextStyle 失败：key={} 不是文字节点", key);
+                    continue;
+                };
+                let Some(section) = text.sections.first_mut() else { continue; };
+                let style = &mut section.style;
+                if let Some(font_size) = font_size {
+                    style.font_size = font_size;
+                }
+                if let Some(r) = color_r {
+                    style.color.set_r(r);
+                }
+                if let Some(g) = color_g {
+                    style.color.set_g(g);
+                }
+                if let Some(b) = color_b {
+                    style.color.set_b(b);
+                }
+                println!("[UI] 设置文字样式 key={}", key);
+            }
+
+            UiMutationCommand::SetFont { key, font_alias, font_size } => {
+                let Some(entity) = entity_map.get_or_warn("SetFont", &key) else { continue; };
+                let Ok(mut text) = texts.get_mut(entity) else {
+                    warn!("[UI] SetFont 失败：key={} 不是文字节点", key);
+                    continue;
+                };
+                // 别名未注册（或 load-font 尚未处理）时保留原字体，字号也不改，避免只生效一半
+                let Some(font) = fonts.map.get(&font_alias) else {
+                    warn!("[UI] SetFont 失败：字体别名 {} 未注册，key={} 保持原字体", font_alias, key);
+                    continue;
+                };
+                for section in text.sections.iter_mut() {
+                    section.style.font = font.clone();
+                    if let Some(font_size) = font_size {
+                        section.style.font_size = font_size;
+                    }
+                }
+                println!("[UI] 设置字体 key={} font={}", key, font_alias);
+            }
+
+            UiMutationCommand::SetAnimation { key, start_index, end_index, fps } => {
+                let Some(entity) = entity_map.get_or_warn("SetAnimation", &key) else { continue; };
+                let Ok((mut animation, mut atlas)) = atlases.animations.get_mut(entity) else {
+                    warn!("[UI] SetAnimation 失败：key={} 不是动画精灵", key);
+                    continue;
+                };
+                let max_index = animation.frame_count - 1;
+                let (first, last) = (start_index as usize, end_index as usize);
+                if last > max_index {
+                    warn!("[UI] SetAnimation 越界：key={} end_index={} 超出最大帧 {}，已截断", key, end_index, max_index);
+                }
+                animation.first = first.min(max_index);
+                animation.last = last.min(max_index);
+                animation.fps = fps;
+                animation.elapsed_secs = 0.0;
+                atlas.index = animation.first;
+                println!("[UI] 切换动画 key={} 帧 {}..={} fps={}", key, animation.first, animation.last, fps);
+            }
+
+            UiMutationCommand::Tween { key, property, to, duration_secs, easing } => {
+                let Some(entity) = entity_map.get_or_warn("Tween", &key) else { continue; };
+                // 同一属性的新补间覆盖旧补间，从当前值继续过渡
+                tweens.tweens.retain(|t| !(t.entity == entity && t.property == property));
+                println!("[UI] 补间 key={} {:?} → {} ({}s, {:?})", key, property, to, duration_secs, easing);
+                tweens.tweens.push(Tween {
+                    key, entity, property,
+                    from: None, to, duration_secs,
+                    elapsed_secs: 0.0, easing,
+                });
+            }
+        }
+    }
+}
+
+/// flex 容器的 Style：相对定位，子节点按方向自动排布，gap 同时作用于行列间距
+pub(crate) fn container_style(
+    flex_direction: FlexDirection,
+    gap: f32,
+    padding: f32,
+    align_items: AlignItems,
+    justify_content: JustifyContent,
+) -> Style {
+    Style {
+        position_type: PositionType::Relative,
+        flex_direction,
+        align_items,
+        justify_content,
+        padding: UiRect::all(Val::Px(padding)),
+        row_gap: Val::Px(gap),
+        column_gap: Val::Px(gap),
+        ..default()
+    }
+}
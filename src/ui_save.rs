@@ -0,0 +1,67 @@
+// ─── UI 状态存档（feature = "ui-save"）───────────────────────────────────────
+//
+// Entity 只在本次运行内有效，UiEntityMap 无法直接存档。UiEntityLog 按顺序记录产生
+// 当前 UI 的 Spawn 命令：record_ui_spawns 在 process_ui_spawn 消费前抄录，
+// prune_ui_entity_log 在 Mutation 之后剔除 key 已不在 UiEntityMap 中的条目（销毁、场景切换、
+// 父节点不存在而创建失败）。读档时把 restore_ui_state 的结果放回 PendingUiCommands 即可重建 UI。
+//
+// 只记录 Spawn 命令：之后的 set-text、set-position 等修改不会存档，读档后由 TS 自行刷新。
+
+use super::{PendingUiCommands, UiEntityMap, UiSpawnCommand, WasmSchedule};
+use bevy::prelude::*;
+
+/// 仍然存活的 UI 节点及创建它的命令，按创建顺序排列（父节点总在子节点之前）
+#[derive(Resource, Default, Debug)]
+pub(crate) struct UiEntityLog {
+    pub(crate) entries: Vec<(String, UiSpawnCommand)>,
+}
+
+impl UiEntityLog {
+    pub(crate) fn record(&mut self, cmd: &UiSpawnCommand) {
+        let key = cmd.key();
+        match cmd {
+            // 播放动画作用于已有的精灵，只替换同一 key 之前的播放命令
+            UiSpawnCommand::PlaySpriteAnimation { .. } => {
+                self.entries.retain(|(k, c)| !(k == key && c.kind() == cmd.kind()));
+            }
+            // 同一 key 重新创建时，旧节点已被替换
+            _ => self.entries.retain(|(k, _)| k != key),
+        }
+        self.entries.push((key.to_string(), cmd.clone()));
+    }
+}
+
+pub(crate) fn save_ui_state(log: &UiEntityLog) -> Vec<u8> {
+    serde_json::to_vec(&log.entries).expect("UI 命令序列化失败")
+}
+
+/// 解析失败（存档损坏或来自不兼容的版本）时输出错误并返回空列表
+pub(crate) fn restore_ui_state(data: &[u8]) -> Vec<UiSpawnCommand> {
+    match serde_json::from_slice::<Vec<(String, UiSpawnCommand)>>(data) {
+        Ok(entries) => entries.into_iter().map(|(_, cmd)| cmd).collect(),
+        Err(e) => {
+            eprintln!("[UI 存档] 解析失败: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+pub(crate) fn record_ui_spawns(pending: Res<PendingUiCommands>, mut log: ResMut<UiEntityLog>) {
+    for cmd in &pending.spawns {
+        log.record(cmd);
+    }
+}
+
+pub(crate) fn prune_ui_entity_log(entity_map: Res<UiEntityMap>, mut log: ResMut<UiEntityLog>) {
+    log.entries.retain(|(key, _)| entity_map.map.contains_key(key));
+}
+
+pub struct UiSavePlugin;
+
+impl Plugin for UiSavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UiEntityLog>()
+            .add_systems(Update, record_ui_spawns.after(WasmSchedule::WasmTick).before(WasmSchedule::PostWasmSpawn))
+            .add_systems(Update, prune_ui_entity_log.after(WasmSchedule::PostWasmMutate));
+    }
+}
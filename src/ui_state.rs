@@ -0,0 +1,192 @@
+// ─── UI 运行期状态 ────────────────────────────────────────────────────────────
+//
+// UI 命令消费系统之外维护的 UI 状态：层叠顺序、转告 Guest 的帧间状态、tooltip 清理、
+// 纹理加载结果与 UI 错误日志。
+
+use super::*;
+use bevy::ecs::system::EntityCommands;
+
+// ─── UI 层叠顺序 ──────────────────────────────────────────────────────────────
+//
+// 记录已分配过的 ZIndex::Global 上下界（spawn 时的 z-index、set-z-index 与 bring-to-front /
+// send-to-back 都会更新），bring-to-front 分配 max+1、send-to-back 分配 min-1，
+// 多次调用后相对顺序与调用顺序一致。
+// Bevy 的 UI 总在所属相机的世界之后绘制，因此 z-index 为负的根节点改由背景 UI 相机渲染：
+// 背景相机先于主相机绘制且不渲染游戏世界，主相机不清屏，世界便盖在这些 UI 之上。
+
+#[derive(Resource, Default, Debug)]
+pub(crate) struct UiStackOrder {
+    pub(crate) max: i32,
+    pub(crate) min: i32,
+    /// setup 创建的背景 UI 相机；None 时（无窗口测试）负层级只影响 UI 之间的顺序
+    pub(crate) background_camera: Option<Entity>,
+}
+
+impl UiStackOrder {
+    pub(crate) fn observe(&mut self, z: i32) {
+        self.max = self.max.max(z);
+        self.min = self.min.min(z);
+    }
+
+    pub(crate) fn front(&mut self) -> i32 {
+        self.max = self.max.saturating_add(1);
+        self.max
+    }
+
+    pub(crate) fn back(&mut self) -> i32 {
+        self.min = self.min.saturating_sub(1);
+        self.min
+    }
+
+    /// 按 z 选择根 UI 节点所在的相机：负值交给背景 UI 相机，其余回到默认 UI 相机（主相机）
+    pub(crate) fn assign_camera(&self, entity: &mut EntityCommands, z: i32) {
+        match self.background_camera {
+            Some(camera) if z < 0 => {
+                entity.insert(TargetCamera(camera));
+            }
+            _ => {
+                entity.remove::<TargetCamera>();
+            }
+        }
+    }
+}
+
+// ─── 帧间状态 ─────────────────────────────────────────────────────────────────
+//
+// 由 Bevy 系统写入、下一次 wasm_tick 读取并转告 Guest 的状态。
+
+/// 经 process_ui_mutations 销毁的 key（含递归销毁的子节点），
+/// wasm_tick 转入所属组件的 HostState.despawned_keys 后以 on-ui-event("despawned:<key>") 通知
+#[derive(Resource, Default)]
+pub(crate) struct DespawnedUiKeys(pub(crate) Vec<String>);
+
+/// 暂停时 wasm_tick 跳过 process-keyboard / update-game，仍分发 UI 回调。
+/// 同一个 Store 既负责移动也负责菜单，无法用 run_if 只停掉其中一部分，因此在 wasm_tick 内部判断
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
+pub struct GamePaused(pub bool);
+
+/// 进度条背景节点上的组件；fill 为子填充节点，width 为背景宽度（像素）
+#[derive(Component, Debug)]
+pub(crate) struct ProgressBar {
+    pub(crate) fill: Entity,
+    pub(crate) width: f32,
+}
+
+// ─── Tooltip ──────────────────────────────────────────────────────────────────
+//
+// tooltip 是绝对定位的根节点，生成时按锚点所在的屏幕象限朝屏幕中心展开（见 tooltip_style），
+// 之后不再跟随锚点移动。锚点被销毁（despawn、场景切换或其父节点被销毁）后，
+// despawn_orphaned_tooltips 连同 key 一起清理，TS 同样会收到 despawned 通知。
+
+/// tooltip 与锚点之间的间距（像素）
+pub(crate) const TOOLTIP_GAP: f32 = 4.0;
+/// 高于 TS 设置的层级，低于 F2 耗时面板
+pub(crate) const TOOLTIP_Z_INDEX: i32 = i32::MAX - 1;
+
+/// 标记：tooltip 根节点及其锚点实体
+#[derive(Component, Debug)]
+pub(crate) struct TooltipAnchor(pub(crate) Entity);
+
+/// 锚点已不存在的 tooltip 随之销毁
+pub(crate) fn despawn_orphaned_tooltips(
+    mut commands: Commands,
+    tooltips: Query<(Entity, &TooltipAnchor)>,
+    entities: Query<()>,
+    mut entity_map: ResMut<UiEntityMap>,
+    mut despawned: ResMut<DespawnedUiKeys>,
+) {
+    for (tooltip, anchor) in &tooltips {
+        if entities.contains(anchor.0) {
+            continue;
+        }
+        commands.entity(tooltip).despawn_recursive();
+        if let Some(key) = entity_map.key_for(tooltip).map(str::to_string) {
+            println!("[UI] 锚点已销毁，移除 tooltip key={}", key);
+            despawned.0.extend(entity_map.remove_subtree(&key));
+        }
+    }
+}
+
+/// process_ui_mutations 修改 Style 所需的参数（SetProgress / SetPosition）
+#[derive(SystemParam)]
+pub(crate) struct StyleWrites<'w, 's> {
+    pub(crate) bars:   Query<'w, 's, &'static ProgressBar>,
+    pub(crate) styles: Query<'w, 's, &'static mut Style>,
+}
+
+// ─── 纹理加载状态 ─────────────────────────────────────────────────────────────
+//
+// process_ui_spawn 记下图片与动画精灵的纹理句柄，update_asset_readiness 在 PreWasm 阶段查询
+// AssetServer，已加载完成的 key 写入 UiAssetReadiness；wasm_tick 拷贝进 HostState 供 asset-ready 读取。
+// key 从 UiEntityMap 中移除（销毁）后句柄一并丢弃。
+
+/// 带 key 的 UI 实体所用纹理的句柄（key 为全局 key）
+#[derive(Resource, Default)]
+pub(crate) struct UiAssetHandles {
+    pub(crate) map: HashMap<String, UntypedHandle>,
+}
+
+/// 纹理已加载完成的 key；集合不变时不触发变更检测
+#[derive(Resource, Default, PartialEq)]
+pub(crate) struct UiAssetReadiness(pub(crate) HashSet<String>);
+
+/// 丢弃已销毁 key 的句柄，并按 AssetServer 的加载状态刷新 UiAssetReadiness
+pub(crate) fn update_asset_readiness(
+    asset_server: Res<AssetServer>,
+    entity_map: Res<UiEntityMap>,
+    mut handles: ResMut<UiAssetHandles>,
+    mut readiness: ResMut<UiAssetReadiness>,
+) {
+    if entity_map.is_changed() {
+        handles.map.retain(|key, _| entity_map.map.contains_key(key));
+    }
+    let ready = handles.map.iter()
+        .filter(|(_, handle)| asset_server.get_load_state(handle.id()) == Some(LoadState::Loaded))
+        .map(|(key, _)| key.clone())
+        .collect();
+    readiness.set_if_neq(UiAssetReadiness(ready));
+}
+
+// ─── UI 错误日志 Resource ─────────────────────────────────────────────────────
+
+/// 运行期发生、无法同步返回给 TS 的 UI 错误（如纹理加载失败），按发生顺序追加
+#[derive(Resource, Default)]
+pub(crate) struct UiErrorLog {
+    pub(crate) entries: Vec<String>,
+}
+
+impl UiErrorLog {
+    pub(crate) fn push(&mut self, entry: String) {
+        warn!("[UI] {}", entry);
+        self.entries.push(entry);
+    }
+}
+
+/// 标记：纹理仍在加载的图片节点，由 check_ui_image_loads 在加载结束后移除
+#[derive(Component)]
+pub(crate) struct PendingUiImage {
+    pub(crate) key: String,
+}
+
+/// 检查图片纹理加载结果：失败时换成品红色占位块并记录错误
+pub(crate) fn check_ui_image_loads(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut images: Query<(Entity, &PendingUiImage, &mut UiImage, &mut BackgroundColor)>,
+    mut error_log: ResMut<UiErrorLog>,
+) {
+    for (entity, pending, mut image, mut background) in images.iter_mut() {
+        match asset_server.get_load_state(image.texture.id()) {
+            Some(LoadState::Loaded) => {}
+            Some(LoadState::Failed) => {
+                let path = image.texture.path().map(|p| p.to_string()).unwrap_or_default();
+                // 默认句柄为白色 1x1 纹理，由 BackgroundColor 染成品红
+                image.texture = Handle::default();
+                background.0 = Color::rgb(1.0, 0.0, 1.0);
+                error_log.push(format!("纹理加载失败：key={} path={}", pending.key, path));
+            }
+            _ => continue,
+        }
+        commands.entity(entity).remove::<PendingUiImage>();
+    }
+}
@@ -0,0 +1,76 @@
+// ─── WASM 加载配置 ────────────────────────────────────────────────────────────
+//
+// 按优先级从高到低 tick，优先级相同的按注册顺序。只有一个组件时 UI key 保持原样；
+// 多个组件并存时，每个组件的 key 自动加上 `组件名/` 前缀，避免互相覆盖，
+// 因此组件之间不能通过 key 引用对方创建的实体。
+// 各组件都实现同一个 game-world（例如 ai_logic、ui_logic 分别编译），宿主只按名字区分。
+
+use super::*;
+
+#[derive(Clone)]
+pub(crate) struct WasmInterface {
+    pub(crate) name: String,
+    /// wasm 资源路径，相对 assets/ 目录
+    pub(crate) path: PathBuf,
+    pub(crate) priority: i32,
+    pub(crate) capabilities: CapabilityProfile,
+}
+
+/// 组件列表，默认只有 assets/game_logic.wasm（组件名 game_logic）
+#[derive(Resource, Clone)]
+pub struct WasmConfig {
+    pub(crate) components: Vec<WasmInterface>,
+}
+
+impl Default for WasmConfig {
+    fn default() -> Self {
+        WasmConfig {
+            components: vec![WasmInterface {
+                name: "game_logic".to_string(),
+                path: PathBuf::from("game_logic.wasm"),
+                priority: 0,
+                capabilities: CapabilityProfile::FULL,
+            }],
+        }
+    }
+}
+
+impl WasmConfig {
+    /// 添加组件（同名的覆盖），path 相对 assets/ 目录，可带 `assets/` 前缀；priority 越大越先 tick
+    pub fn with_component(mut self, name: &str, path: impl AsRef<Path>, priority: i32) -> Self {
+        let path = path.as_ref();
+        self.register(name, path.strip_prefix("assets").unwrap_or(path), priority);
+        self
+    }
+
+    pub(crate) fn register(&mut self, name: &str, path: &Path, priority: i32) {
+        self.register_with_profile(name, path, priority, CapabilityProfile::FULL);
+    }
+
+    pub(crate) fn register_with_profile(&mut self, name: &str, path: &Path, priority: i32, capabilities: CapabilityProfile) {
+        let interface = WasmInterface { name: name.to_string(), path: path.to_path_buf(), priority, capabilities };
+        match self.components.iter_mut().find(|c| c.name == name) {
+            Some(existing) => *existing = interface,
+            None => self.components.push(interface),
+        }
+    }
+
+    /// 用 path 对应的组件替换默认的 game_logic（组件名取文件名），优先级不变
+    pub(crate) fn set_primary(&mut self, path: &Path) {
+        let name = WasmSource::File(path.to_path_buf()).component_name();
+        match self.components.iter_mut().find(|c| c.name == "game_logic") {
+            Some(primary) => {
+                primary.name = name;
+                primary.path = path.to_path_buf();
+            }
+            None => self.register(&name, path, 0),
+        }
+    }
+
+    /// 组件名按 tick 顺序排列（sort_by_key 是稳定排序，同优先级保持注册顺序）
+    pub(crate) fn tick_order(&self) -> Vec<String> {
+        let mut sorted: Vec<&WasmInterface> = self.components.iter().collect();
+        sorted.sort_by_key(|c| std::cmp::Reverse(c.priority));
+        sorted.into_iter().map(|c| c.name.clone()).collect()
+    }
+}
@@ -0,0 +1,144 @@
+// ─── WASM 插件 ────────────────────────────────────────────────────────────────
+//
+// WasmSchedule 划分 Update 中 WASM 相关系统的阶段；WasmPlugin 创建 WasmRuntime，注册组件加载、
+// 命令队列与标准系统链。GameLogicPlugin 经同一个 add_wasm_core 完成这部分注册。
+
+use super::*;
+use bevy::diagnostic::{Diagnostic, RegisterDiagnostic};
+
+/// WASM 相关系统在 Update 中的阶段，按 PreWasm → WasmTick → PostWasmSpawn → PostWasmMutate 的顺序执行。
+/// 集成方的系统可以放进某个阶段，或用 before / after 固定在阶段之间：
+/// - 给 Guest 准备本帧输入：`my_system.in_set(WasmSchedule::PreWasm)`
+/// - 在 Guest 产出命令之后、UI 实体创建之前检查命令：
+///   `my_system.after(WasmSchedule::WasmTick).before(WasmSchedule::PostWasmSpawn)`
+/// - 读取本帧创建 / 修改后的 UI：`my_system.after(WasmSchedule::PostWasmMutate)`
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WasmSchedule {
+    /// 实例化新加载的组件，收集点击、焦点、键入、剪贴板等需要在本帧交给 Guest 的输入
+    PreWasm,
+    /// wasm_tick：派发事件、调用所有组件并把 HostState 的命令转存到各个待处理队列
+    WasmTick,
+    /// 加载字体、创建 UI 实体（Commands 尚未应用）
+    PostWasmSpawn,
+    /// 应用 Commands 后修改 / 销毁 UI 实体，推进 tween 与场景切换
+    PostWasmMutate,
+}
+
+/// 配置 WasmSchedule 的先后顺序
+pub(crate) fn configure_wasm_schedule(app: &mut App) {
+    app.configure_sets(Update, (
+        WasmSchedule::PreWasm,
+        WasmSchedule::WasmTick,
+        WasmSchedule::PostWasmSpawn,
+        WasmSchedule::PostWasmMutate,
+    ).chain());
+}
+
+/// 创建 WasmRuntime 并注册 wasm 资源类型、加载器、命令队列与标准系统链，
+/// 负责组件的加载 / 实例化生命周期。用法：`app.add_plugins(WasmPlugin::new("assets/game_logic.wasm"))`
+pub struct WasmPlugin {
+    /// 主组件路径，可带 `assets/` 前缀；组件名取文件名（不含扩展名），替换默认的 game_logic
+    pub wasm_path: PathBuf,
+    /// 每个组件每帧可消耗的 fuel，None 表示不计量
+    pub fuel_per_frame: Option<u64>,
+}
+
+impl Default for WasmPlugin {
+    fn default() -> Self {
+        WasmPlugin::new("assets/game_logic.wasm")
+    }
+}
+
+impl WasmPlugin {
+    pub fn new(wasm_path: impl Into<PathBuf>) -> Self {
+        WasmPlugin { wasm_path: wasm_path.into(), fuel_per_frame: None }
+    }
+
+    pub fn with_fuel(mut self, per_frame: u64) -> Self {
+        self.fuel_per_frame = Some(per_frame);
+        self
+    }
+
+    /// 注册一个导出 game-logic 接口的组件，priority 越大越先 tick；同名组件覆盖旧的路径与优先级。
+    /// 需在 Startup 之前调用（通常在其他插件的 build 中），之后注册的组件不会被加载
+    pub fn add_wasm_interface(app: &mut App, interface_name: &str, wasm_path: &Path, priority: i32) {
+        app.world
+            .get_resource_or_insert_with(WasmConfig::default)
+            .register(interface_name, wasm_path, priority);
+    }
+
+    /// 注册第三方 mod（优先级 0），其 Host 调用受 profile 限制：被禁止的调用 trap，
+    /// 与未授予权限时相同。时机要求同 add_wasm_interface
+    pub fn register_mod_with_profile(app: &mut App, name: &str, wasm_path: &Path, profile: CapabilityProfile) {
+        app.world
+            .get_resource_or_insert_with(WasmConfig::default)
+            .register_with_profile(name, wasm_path, 0, profile);
+    }
+}
+
+impl Plugin for WasmPlugin {
+    fn build(&self, app: &mut App) {
+        // AssetServer 的路径相对 assets/ 目录
+        let asset_path = self.wasm_path.strip_prefix("assets").unwrap_or(&self.wasm_path);
+        app.world
+            .get_resource_or_insert_with(WasmConfig::default)
+            .set_primary(asset_path);
+        add_wasm_core(app, self.fuel_per_frame);
+    }
+}
+
+/// WasmPlugin 与 GameLogicPlugin 共用：创建 WasmRuntime，注册组件加载与标准系统链。
+/// 组件列表取自调用前已写入的 WasmConfig 资源
+pub(crate) fn add_wasm_core(app: &mut App, fuel_per_frame: Option<u64>) {
+    let mut builder = WasmRuntimeBuilder::new();
+    if let Some(fuel) = fuel_per_frame {
+        builder = builder.fuel(fuel);
+    }
+    let wasm_runtime = builder.build().expect("创建 wasmtime 运行时失败");
+    app.insert_resource(WasmRuntimeResources::new(&wasm_runtime));
+
+    // 关键：用 non_send 注册，Bevy 调度器保证线程安全，无需 Mutex
+    app.insert_non_send_resource(wasm_runtime)
+        .init_asset::<WasmComponentAsset>()
+        .init_asset_loader::<WasmComponentLoader>()
+        .init_resource::<UiEntityMap>()
+        .init_resource::<PendingUiCommands>()
+        .init_resource::<WasmCallMetrics>()
+        .init_resource::<WasmPermissionConfig>()
+        .init_resource::<PermissionRegistry>()
+        .init_resource::<GameVersion>()
+        .init_resource::<WasmEventBus>()
+        .init_resource::<SharedState>()
+        .init_resource::<FontConfig>()
+        .init_resource::<UiAssetHandles>()
+        .init_resource::<UiAssetReadiness>()
+        .init_resource::<FrameCounter>()
+        .init_resource::<ScreenSize>()
+        .init_resource::<UiLayoutEngine>()
+        .init_resource::<UiLayoutWarnings>()
+        .init_resource::<TextMeasurer>()
+        .add_event::<WasmCompatibilityError>()
+        .register_diagnostic(Diagnostic::new(WASM_UPDATE_GAME_US).with_suffix("us"))
+        .register_diagnostic(Diagnostic::new(WASM_PROCESS_KEYBOARD_US).with_suffix("us"))
+        .register_diagnostic(Diagnostic::new(WASM_ON_UI_EVENT_US).with_suffix("us"))
+        .register_diagnostic(Diagnostic::new(WASM_TOTAL_US).with_suffix("us"))
+        .add_systems(Startup, (apply_permission_config, load_wasm_components))
+        .add_systems(Update, (instantiate_loaded_components, update_asset_readiness, update_screen_size, update_text_measurer).in_set(WasmSchedule::PreWasm))
+        .add_systems(Update, show_missing_logic_notice.after(instantiate_loaded_components))
+        // 唯一接触 WASM 的系统，零锁开销
+        .add_systems(Update, (forward_bevy_events, dispatch_wasm_events, wasm_tick).chain().in_set(WasmSchedule::WasmTick))
+        // 以下系统只读 PendingUiCommands，不接触 WASM
+        .add_systems(Update, (process_font_loads, process_ui_spawn).chain().in_set(WasmSchedule::PostWasmSpawn))
+        .add_systems(Update, (
+            apply_deferred,
+            process_ui_mutations,
+            despawn_orphaned_tooltips,
+            update_tweens,
+            handle_scene_transitions,
+        ).chain().in_set(WasmSchedule::PostWasmMutate));
+    http::add_http(app);
+    i18n::add_i18n(app);
+    timers::add_timers(app);
+    retry::add_retry(app);
+    configure_wasm_schedule(app);
+}
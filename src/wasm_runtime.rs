@@ -0,0 +1,1941 @@
+// ─── WASM 运行时 ──────────────────────────────────────────────────────────────
+//
+// bindgen! 生成的 WIT 绑定、Store 的 Host 数据（HostState）与 bevy-api 实现、参数校验，
+// 以及 WasmRuntime 的构建、实例化和 AssetServer 加载流程。
+// 生成的类型以 pub(crate) use 引入，wasm_tick 等系统经 lib.rs 的 glob 导入直接使用。
+
+use super::*;
+use lru::LruCache;
+use rand::Rng;
+use std::num::NonZeroUsize;
+use wasmtime::component::{bindgen, Func, Instance};
+use wasmtime::Store;
+
+// ─── WIT 绑定生成 ─────────────────────────────────────────────────────────────
+//
+// bindgen! 读取 wit/game.wit，自动生成全部类型和 trait，
+// 彻底消除手写 build_args / parse_output 桥接代码。
+
+bindgen!({
+    world: "game-world",
+    path: "wit/game.wit",
+});
+
+// 引入 bindgen! 生成的类型
+pub(crate) use game::logic::bevy_api::{
+    AlignMode, AnimatedSpriteConfig, ContainerConfig, EasingKind, FlexDir, GameStateSnapshot, GameTime, Host as BevyApiHost,
+    HttpResponse as WitHttpResponse, ImageConfig, InputFieldConfig, JustifyMode, LayoutWarning, LayoutWarningKind, PanelBorder, PanelConfig, PermissionResult, PermissionType as WitPermissionType,
+    ProgressBarConfig, RichTextConfig, SegmentKind, TextConfig, TilemapConfig, TweenProp, UiRect as WitUiRect, Vec2 as WitVec2,
+};
+pub(crate) use exports::game::logic::game_logic::{KeyboardInput, PlayerState, TextInput, UpdateResult};
+
+// ─── wasmtime Store 的 Host 数据 ──────────────────────────────────────────────
+
+pub struct HostState {
+    /// TS 调用 bevy-api 时写入的 Spawn 命令队列
+    pub(crate) spawn_commands: Vec<UiSpawnCommand>,
+    /// TS 调用 bevy-api 时写入的 Mutation 命令队列
+    pub(crate) mutation_commands: Vec<UiMutationCommand>,
+    /// 单调递增的帧计数，每次 wasm_tick 加一
+    pub(crate) frame_count: u64,
+    /// 本帧时间快照（wasm_tick 开始时从 GameTimestamp 拷贝），供 get-time 读取
+    pub(crate) timestamp: GameTimestamp,
+    /// 全局帧号快照（wasm_tick 开始时从 FrameCounter 拷贝），供 current-frame 读取
+    pub(crate) current_frame: u64,
+    /// 已调用 on-shutdown 的次数，shutdown_wasm_components 保证至多为 1
+    pub(crate) shutdown_calls: u32,
+    /// UI key 命名空间：多组件并存时为组件名，所有 key 会加上 `组件名/` 前缀
+    pub(crate) key_namespace: Option<String>,
+    /// UI 布局快照（wasm_tick 从 UiBoundsCache 拷贝），供 query-ui-bounds 读取
+    pub(crate) ui_bounds: HashMap<String, Rect>,
+    /// 带 key 的精灵在世界坐标中的 AABB（wasm_tick 从 SpriteBoundsCache 拷贝），供 overlaps 读取
+    pub(crate) sprite_bounds: HashMap<String, Rect>,
+    /// tag → 打上该标签的 key（已加命名空间），由 tag 写入、nearest 读取
+    pub(crate) tags: HashMap<String, HashSet<String>>,
+    /// TS 调用 load-scene 时写入的场景切换队列
+    pub(crate) scene_commands: Vec<SceneTransitionCommand>,
+    /// 当前已完成加载的场景名（SceneLoaded 后更新），供 get-current-scene 读取
+    pub(crate) current_scene: String,
+    /// TS 调用音频接口时写入的命令队列
+    pub(crate) audio_commands: Vec<AudioCommand>,
+    /// TS 调用 set-gravity / apply-impulse 时写入的物理命令队列
+    pub(crate) physics_commands: Vec<PhysicsCommand>,
+    /// 已被销毁、尚未通知 TS 的 key（不含命名空间）
+    pub(crate) despawned_keys: Vec<String>,
+    /// TS 调用 load-font 时写入的字体加载请求
+    pub(crate) font_loads: Vec<FontLoadRequest>,
+    /// 本帧的调试绘制命令
+    #[cfg(feature = "debug-draw")]
+    pub(crate) debug_draws: Vec<debug_draw::DebugDrawCommand>,
+    /// 最近一次 set-camera 请求（同帧多次调用只保留最后一次）
+    pub(crate) camera_request: Option<CameraRequest>,
+    /// TS 调用 clipboard-write 时写入的命令队列
+    pub(crate) clipboard_commands: Vec<ClipboardCommand>,
+    /// 剪贴板文本快照（wasm_tick 从 ClipboardCache 拷贝），供 clipboard-read 读取
+    pub(crate) clipboard_text: Option<String>,
+    /// 存档目录与配额（wasm_tick 在 LocalStorageConfig / StorageLimitConfig 变化时同步）
+    pub(crate) storage: LocalStorage,
+    /// 输入框文本快照（wasm_tick 从 InputValueReadback 拷贝，key 为全局 key），供 get-input-value 读取
+    pub(crate) input_values: HashMap<String, String>,
+    /// UI 树 JSON 快照（wasm_tick 从 UiTreeDump 拷贝），供 dump-ui 读取
+    pub(crate) ui_dump: String,
+    /// 纹理已加载完成的全局 key（wasm_tick 从 UiAssetReadiness 拷贝），供 asset-ready 读取
+    pub(crate) ready_assets: HashSet<String>,
+    /// 最近一次 set-window-title / set-window-icon 请求（同帧多次调用只保留最后一次）
+    pub(crate) window_title: Option<String>,
+    pub(crate) window_icon: Option<String>,
+    /// 暂停状态：wasm_tick 每帧从 GamePaused 拷贝，set-paused 直接修改并在组件调用结束后写回
+    pub(crate) paused: bool,
+    /// 玩家位置快照（每个组件 tick 开始时从 GameState 拷贝），供 get-player-position 读取
+    pub(crate) player_position: Vec2,
+    /// 最近一次 set-player-position 请求（同帧多次调用只保留最后一次）
+    pub(crate) player_teleport: Option<Vec2>,
+    /// 最近一次 set-focus 请求，内层 None 表示清除焦点
+    pub(crate) focus_request: Option<Option<String>>,
+    /// 尚未被 get-layout-warnings 取走的布局警告（key 不含命名空间），最多保留 MAX_LAYOUT_WARNINGS 条
+    pub(crate) layout_warnings: VecDeque<(String, UiLayoutWarning)>,
+    /// 本帧 http-request 登记的请求，wasm_tick 转存到 PendingHttpRequests
+    pub(crate) http_requests: Vec<HttpRequest>,
+    /// 本帧 set-timer / cancel-timer 的请求（名字已加命名空间），wasm_tick 转存到 GuestTimers
+    pub(crate) timer_commands: Vec<TimerCommand>,
+    /// 本组件发起、尚未完成的请求句柄；wasm_tick 据此把 HttpResponseMap 中的结果分给发起方
+    pub(crate) http_pending: HashSet<u32>,
+    /// 已完成、尚未被 poll-request 取走的结果
+    pub(crate) http_responses: HashMap<u32, HttpResult>,
+    /// 默认字体与别名字体的字形度量（wasm_tick 从 TextMeasurer 拷贝），供 measure-text 使用
+    pub(crate) text_measurer: TextMeasurer,
+    /// measure-text 结果缓存，键为 (文本, 字体别名, 四舍五入后的字号)；text_measurer 更新时清空
+    pub(crate) text_measure_cache: LruCache<(String, String, u32), Vec2>,
+    /// 最近一次 set-locale 请求（同帧多次调用只保留最后一次）
+    pub(crate) locale_request: Option<String>,
+    /// 当前语言的字符串（wasm_tick 在 I18nBundle 变化时拷贝），供 translate 读取
+    pub(crate) i18n_strings: HashMap<String, String>,
+    /// SharedState 快照（含本组件尚未写回的修改），供 get-var 读取
+    pub(crate) shared_vars: HashMap<String, f64>,
+    /// 本组件 set-var 的写入，按调用顺序由 wasm_tick 写回 SharedState
+    pub(crate) shared_var_writes: Vec<(String, f64)>,
+    /// 玩家速度（每个组件 tick 开始时从 GameState 拷贝，set-player-speed 立即修改），供 get-player-speed 读取
+    pub(crate) player_speed: f32,
+    /// 本帧 set-player-speed 请求，wasm_tick 在组件调用结束后写回 GameState
+    pub(crate) player_speed_request: Option<f32>,
+    /// 玩家生命值（每个组件 tick 开始时从 GameState 拷贝），供 query-game-state 读取
+    pub(crate) player_health: f32,
+    /// post-event 投递的事件，wasm_tick 转存到 WasmEventBus
+    pub(crate) posted_events: Vec<(String, Vec<u8>)>,
+    /// subscribe-to-bevy-event 订阅的宿主事件名（不含 `bevy:` 前缀）
+    pub(crate) event_subscriptions: HashSet<String>,
+    /// 已授予的权限（wasm_tick 每帧从 PermissionRegistry 拷贝），敏感 Host 函数调用前检查
+    pub(crate) permissions: HashSet<PermissionType>,
+    /// 注册时为本组件指定的能力配置（见 CapabilityProfile）
+    pub(crate) capabilities: CapabilityProfile,
+    /// get-random-* 使用的随机数发生器，每个组件实例独立
+    pub(crate) rng: WasmRng,
+    /// UI 命令队列上限（wasm_tick 每帧从 QueueLimits 拷贝）
+    pub(crate) queue_limits: QueueLimits,
+    /// 因超出上限被丢弃、尚未汇总到 DroppedCommandCounts 的命令数
+    pub(crate) dropped: DroppedCommandCounts,
+}
+
+impl HostState {
+    pub fn new(key_namespace: Option<String>) -> Self {
+        HostState {
+            spawn_commands:    Vec::new(),
+            mutation_commands: Vec::new(),
+            frame_count:       0,
+            current_frame:     0,
+            shutdown_calls:    0,
+            timestamp:         GameTimestamp::default(),
+            key_namespace,
+            ui_bounds:         HashMap::new(),
+            sprite_bounds:     HashMap::new(),
+            tags:              HashMap::new(),
+            scene_commands:    Vec::new(),
+            current_scene:     String::new(),
+            audio_commands:    Vec::new(),
+            physics_commands:  Vec::new(),
+            font_loads:        Vec::new(),
+            despawned_keys:    Vec::new(),
+            #[cfg(feature = "debug-draw")]
+            debug_draws:       Vec::new(),
+            camera_request:    None,
+            clipboard_commands: Vec::new(),
+            clipboard_text:    None,
+            storage:           LocalStorage::default(),
+            input_values:      HashMap::new(),
+            ui_dump:           "[]".to_string(),
+            ready_assets:      HashSet::new(),
+            window_title:      None,
+            window_icon:       None,
+            paused:            false,
+            player_position:   Vec2::ZERO,
+            player_teleport:   None,
+            focus_request:     None,
+            layout_warnings:   VecDeque::new(),
+            http_requests:     Vec::new(),
+            timer_commands:    Vec::new(),
+            http_pending:      HashSet::new(),
+            http_responses:    HashMap::new(),
+            text_measurer:     TextMeasurer::default(),
+            text_measure_cache: LruCache::new(NonZeroUsize::new(TEXT_MEASURE_CACHE_SIZE).unwrap()),
+            locale_request:    None,
+            i18n_strings:      HashMap::new(),
+            shared_vars:       HashMap::new(),
+            shared_var_writes: Vec::new(),
+            player_speed:      GameState::default().player_speed,
+            player_speed_request: None,
+            player_health:     GameState::default().player_health,
+            posted_events:     Vec::new(),
+            event_subscriptions: HashSet::new(),
+            permissions:       WasmPermissionConfig::default().granted.into_iter().collect(),
+            capabilities:      CapabilityProfile::FULL,
+            rng:               WasmRng::from_entropy(),
+            queue_limits:      QueueLimits::default(),
+            dropped:           DroppedCommandCounts::default(),
+        }
+    }
+
+    /// 将 TS 传入的 key 转换为全局唯一 key（单组件时原样返回）
+    pub(crate) fn scoped_key(&self, key: String) -> String {
+        match &self.key_namespace {
+            Some(ns) => format!("{}/{}", ns, key),
+            None => key,
+        }
+    }
+
+    /// 已校验的 text-config → SpawnText 命令（key 加命名空间）
+    pub(crate) fn text_command(&self, config: TextConfig) -> UiSpawnCommand {
+        UiSpawnCommand::SpawnText {
+            key:        self.scoped_key(config.key),
+            parent_key: self.scoped_key(config.parent_key),
+            text:       config.text,
+            font_size:  config.font_size,
+            color_r:    config.color_r,
+            color_g:    config.color_g,
+            color_b:    config.color_b,
+            font:       config.font,
+            font_path:  config.font_path,
+        }
+    }
+
+    /// 校验 panel-config 并转换为 SpawnPanel 命令（key 加命名空间）
+    pub(crate) fn panel_command(&self, config: PanelConfig, persistent: bool) -> wasmtime::Result<UiSpawnCommand> {
+        validate_key(&config.key)?;
+        if let Some(parent) = &config.parent_key {
+            validate_key(parent)?;
+        }
+        validate_dimensions(&[config.width, config.height])?;
+        if !(config.x.is_finite() && config.y.is_finite()) {
+            return Err(BevyApiError::InvalidDimensions.into());
+        }
+        validate_color(&[config.color_r, config.color_g, config.color_b, config.color_a])?;
+        if let Some(radius) = config.corner_radius {
+            validate_dimensions(&[radius])?;
+        }
+        if let Some(border) = &config.border {
+            validate_dimensions(&[border.width])?;
+            validate_color(&[border.color_r, border.color_g, border.color_b, border.color_a])?;
+        }
+        Ok(UiSpawnCommand::SpawnPanel {
+            key:        self.scoped_key(config.key),
+            parent_key: config.parent_key.map(|p| self.scoped_key(p)),
+            x: config.x, y: config.y,
+            width: config.width, height: config.height,
+            color_r: config.color_r, color_g: config.color_g,
+            color_b: config.color_b, color_a: config.color_a,
+            z_index: config.z_index,
+            border: config.border.map(UiBorder::from),
+            corner_radius: config.corner_radius,
+            persistent,
+        })
+    }
+
+    /// 取走本组件发起的请求结果，等待 poll-request 读取
+    pub(crate) fn deliver_http_responses(&mut self, completed: &[(u32, HttpResult)]) {
+        for (handle, result) in completed {
+            if self.http_pending.remove(handle) {
+                self.http_responses.insert(*handle, result.clone());
+            }
+        }
+    }
+
+    /// 用 SharedState 刷新快照；尚未写回的 set-var 覆盖在快照之上，保证组件读到自己的写入
+    pub(crate) fn sync_shared_vars(&mut self, shared: &SharedState) {
+        self.shared_vars.clone_from(&shared.0);
+        for (name, value) in &self.shared_var_writes {
+            self.shared_vars.insert(name.clone(), *value);
+        }
+    }
+
+    /// 字体加载完成后替换度量；之前缓存的可能是估算值，一并清空
+    pub(crate) fn set_text_measurer(&mut self, measurer: TextMeasurer) {
+        self.text_measurer = measurer;
+        self.text_measure_cache.clear();
+    }
+
+    /// 敏感 Host 函数的入口检查，权限未授予时本次调用 trap
+    pub(crate) fn require(&self, permission: PermissionType) -> Result<(), BevyApiError> {
+        if self.permissions.contains(&permission) {
+            Ok(())
+        } else {
+            Err(BevyApiError::PermissionDenied(permission))
+        }
+    }
+
+    /// 能力配置的入口检查，与 require 相同，不允许时本次调用 trap
+    pub(crate) fn require_capability(&self, capability: Capability) -> Result<(), BevyApiError> {
+        if self.capabilities.allows(capability) {
+            Ok(())
+        } else {
+            Err(BevyApiError::CapabilityDenied(capability))
+        }
+    }
+
+    /// 入队 Spawn 命令；超出上限时丢弃并计数。所有 spawn 都经过这里，能力检查集中在此
+    pub(crate) fn push_spawn(&mut self, command: UiSpawnCommand) -> Result<(), BevyApiError> {
+        self.require_capability(Capability::SpawnUi)?;
+        if self.spawn_commands.len() >= self.queue_limits.max_pending_spawns {
+            self.record_dropped_spawns(1);
+            return Ok(());
+        }
+        trace!(target: TRACE_TARGET, key = command.key(), kind = command.kind(), "spawn enqueued");
+        self.spawn_commands.push(command);
+        Ok(())
+    }
+
+    /// 入队 Mutation 命令；超出上限时丢弃并计数
+    pub(crate) fn push_mutation(&mut self, command: UiMutationCommand) {
+        if self.mutation_commands.len() >= self.queue_limits.max_pending_mutations {
+            self.dropped.mutations += 1;
+            // 按 2 的幂次节流，持续刷屏时日志量只随丢弃数对数增长
+            if self.dropped.mutations.is_power_of_two() {
+                warn!("[UI] mutation 队列已满（上限 {}），已丢弃 {} 条", self.queue_limits.max_pending_mutations, self.dropped.mutations);
+            }
+            return;
+        }
+        trace!(target: TRACE_TARGET, key = command.key(), kind = command.kind(), "mutation enqueued");
+        self.mutation_commands.push(command);
+    }
+
+    /// 整批入队 Spawn 命令；剩余容量放不下整批时整批丢弃，保持"要么全建、要么不建"
+    pub(crate) fn push_spawn_batch(&mut self, commands: Vec<UiSpawnCommand>) -> Result<(), BevyApiError> {
+        self.require_capability(Capability::SpawnUi)?;
+        if self.spawn_commands.len() + commands.len() > self.queue_limits.max_pending_spawns {
+            self.record_dropped_spawns(commands.len() as u64);
+            return Ok(());
+        }
+        for command in &commands {
+            trace!(target: TRACE_TARGET, key = command.key(), kind = command.kind(), "spawn enqueued");
+        }
+        self.spawn_commands.extend(commands);
+        Ok(())
+    }
+
+    /// TS 长期不轮询时丢弃最旧的警告
+    pub(crate) fn push_layout_warnings(&mut self, warnings: Vec<(String, UiLayoutWarning)>) {
+        self.layout_warnings.extend(warnings);
+        let excess = self.layout_warnings.len().saturating_sub(MAX_LAYOUT_WARNINGS);
+        self.layout_warnings.drain(..excess);
+    }
+
+    pub(crate) fn record_dropped_spawns(&mut self, count: u64) {
+        let before = self.dropped.spawns;
+        self.dropped.spawns += count;
+        // 节流规则同 push_mutation：丢弃数越过 2 的幂次时才输出
+        if (before + 1..=self.dropped.spawns).any(u64::is_power_of_two) {
+            warn!("[UI] spawn 队列已满（上限 {}），已丢弃 {} 条", self.queue_limits.max_pending_spawns, self.dropped.spawns);
+        }
+    }
+
+    /// 是否还有未被 wasm_tick 转存的命令
+    pub(crate) fn has_pending_commands(&self) -> bool {
+        let pending = !self.spawn_commands.is_empty()
+            || !self.mutation_commands.is_empty()
+            || !self.scene_commands.is_empty()
+            || !self.audio_commands.is_empty()
+            || !self.physics_commands.is_empty()
+            || !self.font_loads.is_empty()
+            || self.camera_request.is_some()
+            || !self.clipboard_commands.is_empty()
+            || self.window_title.is_some()
+            || self.window_icon.is_some()
+            || self.focus_request.is_some()
+            || !self.http_requests.is_empty()
+            || !self.timer_commands.is_empty()
+            || !self.shared_var_writes.is_empty()
+            || self.locale_request.is_some();
+        #[cfg(feature = "debug-draw")]
+        let pending = pending || !self.debug_draws.is_empty();
+        pending
+    }
+
+    /// scoped_key 的逆操作：属于本组件的全局 key 去掉命名空间前缀，否则返回 None
+    pub(crate) fn local_key<'a>(&self, key: &'a str) -> Option<&'a str> {
+        match &self.key_namespace {
+            Some(ns) => key.strip_prefix(ns.as_str())?.strip_prefix('/'),
+            None => Some(key),
+        }
+    }
+
+    /// 组件间事件总是接收；宿主转发的 `bevy:` 事件只在订阅后接收
+    pub(crate) fn wants_event(&self, event_type: &str) -> bool {
+        match event_type.strip_prefix(BEVY_EVENT_PREFIX) {
+            Some(name) => self.event_subscriptions.contains(name),
+            None => true,
+        }
+    }
+}
+
+// ─── bevy-api 参数校验 ────────────────────────────────────────────────────────
+
+/// UI key 的最大字节长度（命名空间前缀不计入）
+pub(crate) const MAX_KEY_LEN: usize = 128;
+
+/// 每个组件最多缓存的布局警告条数
+pub(crate) const MAX_LAYOUT_WARNINGS: usize = 256;
+
+/// bevy-api 参数校验失败的原因
+///
+/// 实现 std::error::Error 后由 anyhow 的通用 From 转换为 wasmtime::Error，
+/// Host 函数内直接 `?` 即可；Guest 侧表现为本次调用 trap，错误信息带具体原因。
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum BevyApiError {
+    /// key 为空字符串
+    EmptyKey,
+    /// key 超过 MAX_KEY_LEN，携带实际长度
+    KeyTooLong(usize),
+    /// 颜色/透明度分量不在 0.0~1.0 或为 NaN
+    InvalidColor,
+    /// 尺寸、字号、间距为负数或非有限值
+    InvalidDimensions,
+    /// 音量为负数或非有限值
+    InvalidVolume,
+    /// 随机数范围 min > max 或为非有限值
+    InvalidRange,
+    /// 存档槽名为空、过长或含有字母数字、`-`、`_` 以外的字符
+    InvalidSlot,
+    /// 存档数据超过 StorageLimitConfig.max_bytes_per_slot，携带实际字节数
+    SlotTooLarge(usize),
+    /// 新建存档槽会超过 StorageLimitConfig.max_slots
+    TooManySlots,
+    /// 进度值为 NaN 或无穷大
+    InvalidProgress,
+    /// 调用需要的权限未被授予（见 PermissionRegistry）
+    PermissionDenied(PermissionType),
+    /// 组件注册时的 CapabilityProfile 不允许该调用
+    CapabilityDenied(Capability),
+    /// post-event 的事件类型使用了宿主保留的 `bevy:` 前缀
+    ReservedEventType,
+    /// http-request 的方法不在 HTTP_METHODS 中，或 url 不是 http(s) 地址
+    InvalidHttpRequest,
+    /// locale 为空、过长或含有字母数字、`-`、`_` 以外的字符
+    InvalidLocale,
+}
+
+impl std::fmt::Display for BevyApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BevyApiError::EmptyKey => write!(f, "UI key 不能为空"),
+            BevyApiError::KeyTooLong(len) => write!(f, "UI key 长度 {} 超过上限 {}", len, MAX_KEY_LEN),
+            BevyApiError::InvalidColor => write!(f, "颜色分量必须在 0.0~1.0 之间"),
+            BevyApiError::InvalidDimensions => write!(f, "尺寸必须为非负有限值"),
+            BevyApiError::InvalidVolume => write!(f, "音量必须为非负有限值"),
+            BevyApiError::InvalidRange => write!(f, "随机数范围必须满足 min <= max 且为有限值"),
+            BevyApiError::InvalidSlot => write!(f, "存档槽名只能包含字母、数字、- 和 _，长度 1~{}", MAX_SLOT_LEN),
+            BevyApiError::SlotTooLarge(len) => write!(f, "存档数据 {} 字节超过单槽上限", len),
+            BevyApiError::TooManySlots => write!(f, "存档槽数量已达上限"),
+            BevyApiError::InvalidProgress => write!(f, "进度必须为有限值"),
+            BevyApiError::PermissionDenied(permission) => write!(f, "未授予权限 {:?}", permission),
+            BevyApiError::CapabilityDenied(capability) => write!(f, "组件的能力配置不允许 {:?}", capability),
+            BevyApiError::ReservedEventType => write!(f, "事件类型前缀 {} 由宿主保留", BEVY_EVENT_PREFIX),
+            BevyApiError::InvalidLocale => write!(f, "locale 只能包含字母、数字、- 和 _，长度 1~{}", MAX_LOCALE_LEN),
+            BevyApiError::InvalidHttpRequest => write!(f, "HTTP 方法须为 {} 之一，url 须以 http:// 或 https:// 开头", HTTP_METHODS.join(" / ")),
+        }
+    }
+}
+
+impl std::error::Error for BevyApiError {}
+
+/// 超出 0~1 的进度按钳制处理（如伤害溢出），只拒绝 NaN / 无穷大
+pub(crate) fn validate_progress(value: f32) -> Result<f32, BevyApiError> {
+    if value.is_finite() {
+        Ok(value.clamp(0.0, 1.0))
+    } else {
+        Err(BevyApiError::InvalidProgress)
+    }
+}
+
+pub(crate) fn validate_key(key: &str) -> Result<(), BevyApiError> {
+    match key.len() {
+        0 => Err(BevyApiError::EmptyKey),
+        len if len > MAX_KEY_LEN => Err(BevyApiError::KeyTooLong(len)),
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn validate_color(components: &[f32]) -> Result<(), BevyApiError> {
+    if components.iter().all(|c| (0.0..=1.0).contains(c)) {
+        Ok(())
+    } else {
+        Err(BevyApiError::InvalidColor)
+    }
+}
+
+pub(crate) fn validate_dimensions(values: &[f32]) -> Result<(), BevyApiError> {
+    if values.iter().all(|v| v.is_finite() && *v >= 0.0) {
+        Ok(())
+    } else {
+        Err(BevyApiError::InvalidDimensions)
+    }
+}
+
+pub(crate) fn validate_volume(volume: f32) -> Result<(), BevyApiError> {
+    if volume.is_finite() && volume >= 0.0 {
+        Ok(())
+    } else {
+        Err(BevyApiError::InvalidVolume)
+    }
+}
+
+pub(crate) fn validate_slot(slot: &str) -> Result<(), BevyApiError> {
+    let valid_char = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+    if (1..=MAX_SLOT_LEN).contains(&slot.len()) && slot.chars().all(valid_char) {
+        Ok(())
+    } else {
+        Err(BevyApiError::InvalidSlot)
+    }
+}
+
+pub(crate) fn validate_text_config(config: &TextConfig) -> Result<(), BevyApiError> {
+    validate_key(&config.key)?;
+    validate_key(&config.parent_key)?;
+    if let Some(font) = &config.font {
+        validate_key(font)?;
+    }
+    validate_dimensions(&[config.font_size])?;
+    validate_color(&[config.color_r, config.color_g, config.color_b])
+}
+
+// ─── 实现 WIT 生成的 bevy-api Host trait ──────────────────────────────────────
+
+impl BevyApiHost for HostState {
+    fn spawn_panel(&mut self, config: PanelConfig) -> wasmtime::Result<()> {
+        let command = self.panel_command(config, false)?;
+        self.push_spawn(command)?;
+        Ok(())
+    }
+
+    fn spawn_persistent_panel(&mut self, config: PanelConfig) -> wasmtime::Result<()> {
+        let command = self.panel_command(config, true)?;
+        self.push_spawn(command)?;
+        Ok(())
+    }
+
+    fn spawn_text(&mut self, config: TextConfig) -> wasmtime::Result<()> {
+        validate_text_config(&config)?;
+        // 按路径加载字体与 load-font 一样需要读文件权限
+        if config.font_path.is_some() {
+            self.require(PermissionType::ReadFile)?;
+        }
+        let command = self.text_command(config);
+        self.push_spawn(command)?;
+        Ok(())
+    }
+
+    fn batch_spawn_text(&mut self, configs: Vec<TextConfig>) -> wasmtime::Result<()> {
+        // 先整体校验：任一项非法则整批不入队，避免只创建出半个界面
+        for config in &configs {
+            validate_text_config(config)?;
+        }
+        if configs.iter().any(|config| config.font_path.is_some()) {
+            self.require(PermissionType::ReadFile)?;
+        }
+        let commands = configs.into_iter().map(|config| self.text_command(config)).collect();
+        self.push_spawn_batch(commands)?;
+        Ok(())
+    }
+
+    fn spawn_texts(&mut self, configs: Vec<TextConfig>) -> wasmtime::Result<()> {
+        self.batch_spawn_text(configs)
+    }
+
+    fn spawn_panels(&mut self, configs: Vec<PanelConfig>) -> wasmtime::Result<()> {
+        // 全部转换成功（即全部校验通过）后才入队
+        let commands = configs
+            .into_iter()
+            .map(|config| self.panel_command(config, false))
+            .collect::<wasmtime::Result<Vec<_>>>()?;
+        self.push_spawn_batch(commands)?;
+        Ok(())
+    }
+
+    fn spawn_container(&mut self, config: ContainerConfig) -> wasmtime::Result<()> {
+        validate_key(&config.key)?;
+        if let Some(parent) = &config.parent_key {
+            validate_key(parent)?;
+        }
+        validate_dimensions(&[config.gap, config.padding])?;
+        let key = self.scoped_key(config.key);
+        let parent_key = config.parent_key.map(|p| self.scoped_key(p));
+        self.push_spawn(UiSpawnCommand::SpawnContainer {
+            key,
+            parent_key,
+            flex_direction: config.flex_direction.into(),
+            gap:            config.gap,
+            padding:        config.padding,
+            align_items:     config.align_items.into(),
+            justify_content: config.justify_content.into(),
+        })?;
+        Ok(())
+    }
+
+    fn spawn_text_with_icons(&mut self, config: RichTextConfig) -> wasmtime::Result<()> {
+        validate_key(&config.key)?;
+        if let Some(parent) = &config.parent_key {
+            validate_key(parent)?;
+        }
+        let mut segments = Vec::with_capacity(config.segments.len());
+        for segment in config.segments {
+            validate_dimensions(&[segment.size])?;
+            segments.push(match segment.kind {
+                SegmentKind::Text => {
+                    let color = [segment.color_r, segment.color_g, segment.color_b];
+                    validate_color(&color)?;
+                    TextSegment::Text(segment.content, segment.size, color)
+                }
+                // 图标与 spawn-image 一样按路径读取纹理
+                SegmentKind::Icon => {
+                    self.require(PermissionType::ReadFile)?;
+                    TextSegment::Icon(segment.content, segment.size)
+                }
+            });
+        }
+        let key = self.scoped_key(config.key);
+        let parent_key = config.parent_key.map(|p| self.scoped_key(p));
+        self.push_spawn(UiSpawnCommand::SpawnTextWithIcons { key, parent_key, segments })?;
+        Ok(())
+    }
+
+    fn spawn_image(&mut self, config: ImageConfig) -> wasmtime::Result<()> {
+        self.require(PermissionType::ReadFile)?;
+        validate_key(&config.key)?;
+        if let Some(parent) = &config.parent_key {
+            validate_key(parent)?;
+        }
+        validate_dimensions(&[config.width, config.height])?;
+        let key = self.scoped_key(config.key);
+        let parent_key = config.parent_key.map(|p| self.scoped_key(p));
+        self.push_spawn(UiSpawnCommand::SpawnImage {
+            key,
+            parent_key,
+            width:        config.width,
+            height:       config.height,
+            texture_path: config.texture_path,
+        })?;
+        Ok(())
+    }
+
+    fn spawn_animated_sprite(&mut self, config: AnimatedSpriteConfig) -> wasmtime::Result<()> {
+        self.require(PermissionType::ReadFile)?;
+        validate_key(&config.key)?;
+        validate_dimensions(&[config.tile_width, config.tile_height, config.fps])?;
+        let frames = config.columns as u64 * config.rows as u64;
+        if frames == 0 || frames > MAX_SPRITE_FRAMES || !(config.x.is_finite() && config.y.is_finite()) {
+            return Err(BevyApiError::InvalidDimensions.into());
+        }
+        let key = self.scoped_key(config.key);
+        self.push_spawn(UiSpawnCommand::SpawnAnimatedSprite {
+            key,
+            x: config.x, y: config.y,
+            texture_path: config.texture_path,
+            tile_width:   config.tile_width,
+            tile_height:  config.tile_height,
+            columns:      config.columns,
+            rows:         config.rows,
+            fps:          config.fps,
+        })?;
+        Ok(())
+    }
+
+    fn spawn_tilemap(&mut self, config: TilemapConfig) -> wasmtime::Result<()> {
+        self.require(PermissionType::ReadFile)?;
+        validate_key(&config.key)?;
+        validate_dimensions(&[config.tile_size])?;
+        let cells = config.cols as u64 * config.rows as u64;
+        let tile_count = config.tileset_columns as u64 * config.tileset_rows as u64;
+        let valid = config.tile_size > 0.0
+            && config.x.is_finite() && config.y.is_finite()
+            && cells > 0 && cells <= MAX_TILEMAP_TILES as u64
+            && config.tiles.len() as u64 == cells
+            && tile_count > 0
+            && config.tiles.iter().all(|&index| index == EMPTY_TILE || (index as u64) < tile_count);
+        if !valid {
+            return Err(BevyApiError::InvalidDimensions.into());
+        }
+        let key = self.scoped_key(config.key);
+        self.push_spawn(UiSpawnCommand::SpawnTilemap {
+            key,
+            x: config.x, y: config.y,
+            cols:            config.cols,
+            rows:            config.rows,
+            tile_size:       config.tile_size,
+            tileset_path:    config.tileset_path,
+            tileset_columns: config.tileset_columns,
+            tileset_rows:    config.tileset_rows,
+            tiles:           config.tiles,
+        })?;
+        Ok(())
+    }
+
+    fn set_tile(&mut self, map_key: String, col: u32, row: u32, tile_index: u16) -> wasmtime::Result<()> {
+        validate_key(&map_key)?;
+        let map_key = self.scoped_key(map_key);
+        self.push_mutation(UiMutationCommand::SetTile { map_key, col, row, tile_index });
+        Ok(())
+    }
+
+    fn set_animation(&mut self, key: String, start_index: u32, end_index: u32, fps: f32) -> wasmtime::Result<()> {
+        validate_key(&key)?;
+        validate_dimensions(&[fps])?;
+        if start_index > end_index {
+            return Err(BevyApiError::InvalidDimensions.into());
+        }
+        let key = self.scoped_key(key);
+        self.push_mutation(UiMutationCommand::SetAnimation { key, start_index, end_index, fps });
+        Ok(())
+    }
+
+    fn play_sprite_animation(&mut self, key: String, sheet_path: String, rows: u32, cols: u32, fps: f32, looping: bool) -> wasmtime::Result<()> {
+        self.require(PermissionType::ReadFile)?;
+        validate_key(&key)?;
+        validate_dimensions(&[fps])?;
+        let frames = rows as u64 * cols as u64;
+        if frames == 0 || frames > MAX_SPRITE_FRAMES {
+            return Err(BevyApiError::InvalidDimensions.into());
+        }
+        let key = self.scoped_key(key);
+        self.push_spawn(UiSpawnCommand::PlaySpriteAnimation { key, sheet_path, rows, cols, fps, looping })?;
+        Ok(())
+    }
+
+    fn stop_animation(&mut self, key: String) -> wasmtime::Result<()> {
+        validate_key(&key)?;
+        let key = self.scoped_key(key);
+        self.push_mutation(UiMutationCommand::StopAnimation { key });
+        Ok(())
+    }
+
+    fn despawn(&mut self, key: String) -> wasmtime::Result<()> {
+        validate_key(&key)?;
+        let key = self.scoped_key(key);
+        self.push_mutation(UiMutationCommand::Despawn { key });
+        Ok(())
+    }
+
+    fn set_visible(&mut self, key: String, visible: bool) -> wasmtime::Result<()> {
+        validate_key(&key)?;
+        let key = self.scoped_key(key);
+        self.push_mutation(UiMutationCommand::SetVisible { key, visible });
+        Ok(())
+    }
+
+    fn set_text_style(
+        &mut self,
+        key: String,
+        font_size: Option<f32>,
+        r: Option<f32>,
+        g: Option<f32>,
+        b: Option<f32>,
+    ) -> wasmtime::Result<()> {
+        validate_key(&key)?;
+        if let Some(font_size) = font_size {
+            validate_dimensions(&[font_size])?;
+        }
+        let color: Vec<f32> = [r, g, b].into_iter().flatten().collect();
+        validate_color(&color)?;
+        let key = self.scoped_key(key);
+        self.push_mutation(UiMutationCommand::SetTextStyle { key, font_size, color_r: r, color_g: g, color_b: b });
+        Ok(())
+    }
+
+    fn set_font(&mut self, key: String, font_alias: String, font_size: Option<f32>) -> wasmtime::Result<()> {
+        validate_key(&key)?;
+        validate_key(&font_alias)?;
+        if let Some(font_size) = font_size {
+            validate_dimensions(&[font_size])?;
+        }
+        let key = self.scoped_key(key);
+        self.push_mutation(UiMutationCommand::SetFont { key, font_alias, font_size });
+        Ok(())
+    }
+
+    fn bring_to_front(&mut self, key: String) -> wasmtime::Result<()> {
+        validate_key(&key)?;
+        let key = self.scoped_key(key);
+        self.push_mutation(UiMutationCommand::BringToFront { key });
+        Ok(())
+    }
+
+    fn send_to_back(&mut self, key: String) -> wasmtime::Result<()> {
+        validate_key(&key)?;
+        let key = self.scoped_key(key);
+        self.push_mutation(UiMutationCommand::SendToBack { key });
+        Ok(())
+    }
+
+    fn spawn_input_field(&mut self, config: InputFieldConfig) -> wasmtime::Result<()> {
+        validate_key(&config.key)?;
+        validate_dimensions(&[config.width, config.height])?;
+        if !(config.x.is_finite() && config.y.is_finite()) {
+            return Err(BevyApiError::InvalidDimensions.into());
+        }
+        let key = self.scoped_key(config.key);
+        self.push_spawn(UiSpawnCommand::SpawnInputField {
+            key,
+            x: config.x, y: config.y,
+            width:  config.width,
+            height: config.height,
+            placeholder: config.placeholder,
+            max_length:  config.max_length as usize,
+        })?;
+        Ok(())
+    }
+
+    fn spawn_world_text(&mut self, config: WorldTextConfig) -> wasmtime::Result<()> {
+        validate_key(&config.key)?;
+        if let Some(font) = &config.font {
+            validate_key(font)?;
+        }
+        validate_dimensions(&[config.font_size])?;
+        if !(config.x.is_finite() && config.y.is_finite()) {
+            return Err(BevyApiError::InvalidDimensions.into());
+        }
+        validate_color(&[config.color_r, config.color_g, config.color_b])?;
+        self.push_spawn(UiSpawnCommand::SpawnWorldText {
+            key:       self.scoped_key(config.key),
+            x: config.x, y: config.y,
+            text:      config.text,
+            font_size: config.font_size,
+            color_r:   config.color_r,
+            color_g:   config.color_g,
+            color_b:   config.color_b,
+            font:      config.font,
+        })?;
+        Ok(())
+    }
+
+    fn set_position(&mut self, key: String, x: f32, y: f32) -> wasmtime::Result<()> {
+        validate_key(&key)?;
+        if !(x.is_finite() && y.is_finite()) {
+            return Err(BevyApiError::InvalidDimensions.into());
+        }
+        let key = self.scoped_key(key);
+        self.push_mutation(UiMutationCommand::SetPosition { key, x, y });
+        Ok(())
+    }
+
+    fn spawn_tooltip(&mut self, key: String, anchor_key: String, text: String, max_width: f32) -> wasmtime::Result<()> {
+        validate_key(&key)?;
+        validate_key(&anchor_key)?;
+        validate_dimensions(&[max_width])?;
+        self.push_spawn(UiSpawnCommand::SpawnTooltip {
+            key:        self.scoped_key(key),
+            anchor_key: self.scoped_key(anchor_key),
+            text,
+            max_width,
+        })?;
+        Ok(())
+    }
+
+    fn spawn_progress_bar(&mut self, config: ProgressBarConfig) -> wasmtime::Result<()> {
+        validate_key(&config.key)?;
+        if let Some(parent) = &config.parent_key {
+            validate_key(parent)?;
+        }
+        validate_dimensions(&[config.width, config.height])?;
+        if !(config.x.is_finite() && config.y.is_finite()) {
+            return Err(BevyApiError::InvalidDimensions.into());
+        }
+        let bg_color = [config.bg_r, config.bg_g, config.bg_b, config.bg_a];
+        let fill_color = [config.fill_r, config.fill_g, config.fill_b, config.fill_a];
+        validate_color(&bg_color)?;
+        validate_color(&fill_color)?;
+        let value = validate_progress(config.value)?;
+        self.push_spawn(UiSpawnCommand::SpawnProgressBar {
+            key:        self.scoped_key(config.key),
+            parent_key: config.parent_key.map(|p| self.scoped_key(p)),
+            x: config.x, y: config.y,
+            width: config.width, height: config.height,
+            bg_color,
+            fill_color,
+            value,
+        })?;
+        Ok(())
+    }
+
+    fn set_progress(&mut self, key: String, value: f32) -> wasmtime::Result<()> {
+        validate_key(&key)?;
+        let value = validate_progress(value)?;
+        let key = self.scoped_key(key);
+        self.push_mutation(UiMutationCommand::SetProgress { key, value });
+        Ok(())
+    }
+
+    fn request_input_value(&mut self, key: String) -> wasmtime::Result<()> {
+        validate_key(&key)?;
+        let key = self.scoped_key(key);
+        self.push_mutation(UiMutationCommand::GetInputValue { key });
+        Ok(())
+    }
+
+    fn get_input_value(&mut self, key: String) -> wasmtime::Result<Option<String>> {
+        let key = self.scoped_key(key);
+        Ok(self.input_values.get(&key).cloned())
+    }
+
+    fn set_focusable(&mut self, key: String, focusable: bool) -> wasmtime::Result<()> {
+        validate_key(&key)?;
+        let key = self.scoped_key(key);
+        self.push_mutation(UiMutationCommand::SetFocusable { key, focusable });
+        Ok(())
+    }
+
+    fn set_draggable(&mut self, key: String, draggable: bool) -> wasmtime::Result<()> {
+        validate_key(&key)?;
+        let key = self.scoped_key(key);
+        self.push_mutation(UiMutationCommand::SetDraggable { key, draggable });
+        Ok(())
+    }
+
+    fn set_z_index(&mut self, key: String, z: i32) -> wasmtime::Result<()> {
+        validate_key(&key)?;
+        let key = self.scoped_key(key);
+        self.push_mutation(UiMutationCommand::SetZIndex { key, z });
+        Ok(())
+    }
+
+    fn set_opacity(&mut self, key: String, alpha: f32) -> wasmtime::Result<()> {
+        validate_key(&key)?;
+        // 超出范围的 alpha 截断而非报错（见 WIT 注释），只拒绝 NaN
+        if alpha.is_nan() {
+            return Err(BevyApiError::InvalidColor.into());
+        }
+        let key = self.scoped_key(key);
+        self.push_mutation(UiMutationCommand::SetOpacity { key, alpha: alpha.clamp(0.0, 1.0) });
+        Ok(())
+    }
+
+    fn tween(&mut self, key: String, property: TweenProp, to: f32, duration_secs: f32, easing: EasingKind) -> wasmtime::Result<()> {
+        validate_key(&key)?;
+        validate_dimensions(&[duration_secs])?;
+        if !to.is_finite() {
+            return Err(BevyApiError::InvalidDimensions.into());
+        }
+        let property = TweenProperty::from(property);
+        // 与 set-opacity 一致，透明度目标值截断到 0~1
+        let to = if property == TweenProperty::Opacity { to.clamp(0.0, 1.0) } else { to };
+        let key = self.scoped_key(key);
+        self.push_mutation(UiMutationCommand::Tween { key, property, to, duration_secs, easing: easing.into() });
+        Ok(())
+    }
+
+    fn reorder_child(&mut self, parent_key: String, child_key: String, new_index: u32) -> wasmtime::Result<()> {
+        validate_key(&parent_key)?;
+        validate_key(&child_key)?;
+        let parent_key = self.scoped_key(parent_key);
+        let child_key = self.scoped_key(child_key);
+        self.push_mutation(UiMutationCommand::ReorderChild { parent_key, child_key, new_index });
+        Ok(())
+    }
+
+    fn get_layout_warnings(&mut self) -> wasmtime::Result<Vec<LayoutWarning>> {
+        Ok(self
+            .layout_warnings
+            .drain(..)
+            .map(|(key, warning)| LayoutWarning {
+                key,
+                kind: match warning {
+                    UiLayoutWarning::ZeroSize => LayoutWarningKind::ZeroSize,
+                    UiLayoutWarning::PositionNegative => LayoutWarningKind::PositionNegative,
+                    UiLayoutWarning::PartiallyOffScreen => LayoutWarningKind::PartiallyOffScreen,
+                    UiLayoutWarning::OffScreen => LayoutWarningKind::OffScreen,
+                },
+            })
+            .collect())
+    }
+
+    fn set_focus(&mut self, key: String) -> wasmtime::Result<()> {
+        let request = if key.is_empty() {
+            None
+        } else {
+            validate_key(&key)?;
+            Some(self.scoped_key(key))
+        };
+        self.focus_request = Some(request);
+        Ok(())
+    }
+
+    fn set_parent(&mut self, key: String, new_parent_key: String) -> wasmtime::Result<()> {
+        validate_key(&key)?;
+        let new_parent_key = if new_parent_key.is_empty() {
+            None
+        } else {
+            validate_key(&new_parent_key)?;
+            Some(self.scoped_key(new_parent_key))
+        };
+        let key = self.scoped_key(key);
+        self.push_mutation(UiMutationCommand::SetParent { key, new_parent_key });
+        Ok(())
+    }
+
+    fn asset_ready(&mut self, key: String) -> wasmtime::Result<bool> {
+        validate_key(&key)?;
+        let key = self.scoped_key(key);
+        Ok(self.ready_assets.contains(&key))
+    }
+
+    fn measure_text(&mut self, text: String, font_alias: String, font_size: f32) -> wasmtime::Result<WitVec2> {
+        validate_dimensions(&[font_size])?;
+        // 按取整后的字号测量，命中缓存与否结果一致
+        let font_size = font_size.round();
+        let key = (text, font_alias, font_size as u32);
+        let size = match self.text_measure_cache.get(&key) {
+            Some(size) => *size,
+            None => {
+                let size = self.text_measurer.measure(&key.0, &key.1, font_size);
+                self.text_measure_cache.put(key, size);
+                size
+            }
+        };
+        Ok(WitVec2 { x: size.x, y: size.y })
+    }
+
+    fn query_ui_bounds(&mut self, key: String) -> wasmtime::Result<Option<WitUiRect>> {
+        validate_key(&key)?;
+        let key = self.scoped_key(key);
+        Ok(self.ui_bounds.get(&key).map(|rect| WitUiRect {
+            x:      rect.min.x,
+            y:      rect.min.y,
+            width:  rect.width(),
+            height: rect.height(),
+        }))
+    }
+
+    fn overlaps(&mut self, key_a: String, key_b: String) -> wasmtime::Result<bool> {
+        validate_key(&key_a)?;
+        validate_key(&key_b)?;
+        let (key_a, key_b) = (self.scoped_key(key_a), self.scoped_key(key_b));
+        let (Some(a), Some(b)) = (self.sprite_bounds.get(&key_a), self.sprite_bounds.get(&key_b)) else {
+            return Ok(false);
+        };
+        // 边缘恰好相接不算重叠
+        Ok(!a.intersect(*b).is_empty())
+    }
+
+    fn tag(&mut self, key: String, tag: String) -> wasmtime::Result<()> {
+        validate_key(&key)?;
+        validate_key(&tag)?;
+        let key = self.scoped_key(key);
+        self.tags.entry(tag).or_default().insert(key);
+        Ok(())
+    }
+
+    fn nearest(&mut self, from_key: String, tag: String) -> wasmtime::Result<Option<String>> {
+        validate_key(&from_key)?;
+        validate_key(&tag)?;
+        let from_key = self.scoped_key(from_key);
+        let (Some(from), Some(tagged)) = (self.sprite_bounds.get(&from_key), self.tags.get(&tag)) else {
+            return Ok(None);
+        };
+        let origin = from.center();
+        // 只考虑快照中存在的实体；距离相同时取 key 字典序较小者，保证结果稳定
+        let nearest = tagged
+            .iter()
+            .filter(|key| **key != from_key)
+            .filter_map(|key| self.sprite_bounds.get(key).map(|rect| (rect.center().distance_squared(origin), key)))
+            .min_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(b.1)))
+            .map(|(_, key)| key);
+        Ok(nearest.and_then(|key| self.local_key(key)).map(str::to_string))
+    }
+
+    fn dump_ui(&mut self) -> wasmtime::Result<String> {
+        Ok(self.ui_dump.clone())
+    }
+
+    fn load_font(&mut self, path: String, alias: String) -> wasmtime::Result<()> {
+        self.require(PermissionType::ReadFile)?;
+        validate_key(&alias)?;
+        self.font_loads.push(FontLoadRequest { path, alias });
+        Ok(())
+    }
+
+    fn play_sound(&mut self, path: String, volume: f32) -> wasmtime::Result<()> {
+        self.require(PermissionType::Audio)?;
+        self.require_capability(Capability::PlayAudio)?;
+        validate_volume(volume)?;
+        self.audio_commands.push(AudioCommand::PlaySound { path, volume });
+        Ok(())
+    }
+
+    fn play_music(&mut self, key: String, path: String, looped: bool, volume: f32) -> wasmtime::Result<()> {
+        self.require(PermissionType::Audio)?;
+        self.require_capability(Capability::PlayAudio)?;
+        validate_key(&key)?;
+        validate_volume(volume)?;
+        let key = self.scoped_key(key);
+        self.audio_commands.push(AudioCommand::PlayMusic { key, path, looped, volume });
+        Ok(())
+    }
+
+    fn stop_music(&mut self, key: String) -> wasmtime::Result<()> {
+        validate_key(&key)?;
+        let key = self.scoped_key(key);
+        self.audio_commands.push(AudioCommand::StopMusic { key });
+        Ok(())
+    }
+
+    fn set_camera(&mut self, x: f32, y: f32, zoom: f32) -> wasmtime::Result<()> {
+        if !(x.is_finite() && y.is_finite() && zoom.is_finite()) {
+            return Err(BevyApiError::InvalidDimensions.into());
+        }
+        self.camera_request = Some(CameraRequest { position: Vec2::new(x, y), zoom });
+        Ok(())
+    }
+
+    fn clipboard_write(&mut self, text: String) -> wasmtime::Result<()> {
+        self.require(PermissionType::Clipboard)?;
+        // 同一帧内紧接着的 clipboard-read 应读到刚写入的内容
+        self.clipboard_text = Some(text.clone());
+        self.clipboard_commands.push(ClipboardCommand::Write(text));
+        Ok(())
+    }
+
+    fn clipboard_read(&mut self) -> wasmtime::Result<Option<String>> {
+        self.require(PermissionType::Clipboard)?;
+        Ok(self.clipboard_text.clone())
+    }
+
+    fn save_to_local_storage(&mut self, slot: String, data: Vec<u8>) -> wasmtime::Result<Result<(), String>> {
+        self.require(PermissionType::WriteFile)?;
+        self.require_capability(Capability::SaveData)?;
+        Ok(self.storage.save(&slot, &data)?)
+    }
+
+    fn load_from_local_storage(&mut self, slot: String) -> wasmtime::Result<Option<Vec<u8>>> {
+        self.require(PermissionType::ReadFile)?;
+        Ok(self.storage.load(&slot)?)
+    }
+
+    fn get_random_f32(&mut self) -> wasmtime::Result<f32> {
+        Ok(self.rng.0.gen::<f32>())
+    }
+
+    fn get_random_range(&mut self, min: f32, max: f32) -> wasmtime::Result<f32> {
+        if !(min.is_finite() && max.is_finite()) || min > max {
+            return Err(BevyApiError::InvalidRange.into());
+        }
+        if min == max {
+            return Ok(min);
+        }
+        Ok(self.rng.0.gen_range(min..max))
+    }
+
+    fn set_paused(&mut self, paused: bool) -> wasmtime::Result<()> {
+        self.paused = paused;
+        Ok(())
+    }
+
+    fn paused(&mut self) -> wasmtime::Result<bool> {
+        Ok(self.paused)
+    }
+
+    fn request_permission(&mut self, permission: WitPermissionType) -> wasmtime::Result<PermissionResult> {
+        if self.permissions.contains(&permission.into()) {
+            Ok(PermissionResult::Granted)
+        } else {
+            Ok(PermissionResult::Denied)
+        }
+    }
+
+    fn get_player_position(&mut self) -> wasmtime::Result<WitVec2> {
+        Ok(WitVec2 { x: self.player_position.x, y: self.player_position.y })
+    }
+
+    fn query_game_state(&mut self) -> wasmtime::Result<GameStateSnapshot> {
+        Ok(GameStateSnapshot {
+            x:     self.player_position.x,
+            y:     self.player_position.y,
+            speed: self.player_speed,
+            health: self.player_health,
+        })
+    }
+
+    fn set_player_position(&mut self, x: f32, y: f32) -> wasmtime::Result<()> {
+        if !(x.is_finite() && y.is_finite()) {
+            return Err(BevyApiError::InvalidDimensions.into());
+        }
+        // 本帧后续的 get-player-position 立即看到新位置
+        self.player_position = Vec2::new(x, y);
+        self.player_teleport = Some(self.player_position);
+        Ok(())
+    }
+
+    fn set_gravity(&mut self, x: f32, y: f32) -> wasmtime::Result<()> {
+        if !(x.is_finite() && y.is_finite()) {
+            return Err(BevyApiError::InvalidDimensions.into());
+        }
+        self.physics_commands.push(PhysicsCommand::SetGravity(Vec2::new(x, y)));
+        Ok(())
+    }
+
+    fn apply_impulse(&mut self, entity_key: String, ix: f32, iy: f32) -> wasmtime::Result<()> {
+        validate_key(&entity_key)?;
+        if !(ix.is_finite() && iy.is_finite()) {
+            return Err(BevyApiError::InvalidDimensions.into());
+        }
+        let key = self.scoped_key(entity_key);
+        self.physics_commands.push(PhysicsCommand::ApplyImpulse { key, impulse: Vec2::new(ix, iy) });
+        Ok(())
+    }
+
+    fn set_locale(&mut self, locale: String) -> wasmtime::Result<()> {
+        self.require(PermissionType::ReadFile)?;
+        validate_locale(&locale)?;
+        self.locale_request = Some(locale);
+        Ok(())
+    }
+
+    fn translate(&mut self, key: String) -> wasmtime::Result<String> {
+        Ok(self.i18n_strings.get(&key).cloned().unwrap_or(key))
+    }
+
+    fn set_var(&mut self, name: String, value: f64) -> wasmtime::Result<()> {
+        validate_key(&name)?;
+        self.shared_vars.insert(name.clone(), value);
+        self.shared_var_writes.push((name, value));
+        Ok(())
+    }
+
+    fn get_var(&mut self, name: String) -> wasmtime::Result<Option<f64>> {
+        Ok(self.shared_vars.get(&name).copied())
+    }
+
+    fn http_request(&mut self, method: String, url: String, body: Option<Vec<u8>>) -> wasmtime::Result<u32> {
+        self.require(PermissionType::Network)?;
+        self.require_capability(Capability::Http)?;
+        let method = method.to_ascii_uppercase();
+        if !HTTP_METHODS.contains(&method.as_str()) || !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(BevyApiError::InvalidHttpRequest.into());
+        }
+        let handle = next_request_handle();
+        self.http_requests.push(HttpRequest { handle, method, url, body });
+        self.http_pending.insert(handle);
+        Ok(handle)
+    }
+
+    fn poll_request(&mut self, handle: u32) -> wasmtime::Result<Option<Result<WitHttpResponse, String>>> {
+        Ok(self
+            .http_responses
+            .remove(&handle)
+            .map(|result| result.map(|response| WitHttpResponse { status: response.status, body: response.body })))
+    }
+
+    fn get_player_speed(&mut self) -> wasmtime::Result<f32> {
+        Ok(self.player_speed)
+    }
+
+    fn set_player_speed(&mut self, speed: f32) -> wasmtime::Result<()> {
+        validate_dimensions(&[speed])?;
+        self.player_speed = speed;
+        self.player_speed_request = Some(speed);
+        Ok(())
+    }
+
+    fn set_window_title(&mut self, title: String) -> wasmtime::Result<()> {
+        self.window_title = Some(title);
+        Ok(())
+    }
+
+    fn set_window_icon(&mut self, path: String) -> wasmtime::Result<()> {
+        self.require(PermissionType::ReadFile)?;
+        self.window_icon = Some(path);
+        Ok(())
+    }
+
+    fn draw_debug_line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, color: (f32, f32, f32, f32)) -> wasmtime::Result<()> {
+        #[cfg(feature = "debug-draw")]
+        self.debug_draws.push(debug_draw::DebugDrawCommand::Line {
+            start: Vec2::new(x1, y1),
+            end:   Vec2::new(x2, y2),
+            color: debug_draw::debug_color(color),
+        });
+        #[cfg(not(feature = "debug-draw"))]
+        let _ = (x1, y1, x2, y2, color);
+        Ok(())
+    }
+
+    fn draw_debug_circle(&mut self, x: f32, y: f32, radius: f32, color: (f32, f32, f32, f32)) -> wasmtime::Result<()> {
+        #[cfg(feature = "debug-draw")]
+        self.debug_draws.push(debug_draw::DebugDrawCommand::Circle {
+            center: Vec2::new(x, y),
+            radius,
+            color:  debug_draw::debug_color(color),
+        });
+        #[cfg(not(feature = "debug-draw"))]
+        let _ = (x, y, radius, color);
+        Ok(())
+    }
+
+    fn load_scene(&mut self, name: String) -> wasmtime::Result<()> {
+        self.scene_commands.push(SceneTransitionCommand::Load(name));
+        Ok(())
+    }
+
+    fn get_current_scene(&mut self) -> wasmtime::Result<String> {
+        Ok(self.current_scene.clone())
+    }
+
+    fn post_event(&mut self, event_type: String, payload: Vec<u8>) -> wasmtime::Result<()> {
+        validate_key(&event_type)?;
+        if event_type.starts_with(BEVY_EVENT_PREFIX) {
+            return Err(BevyApiError::ReservedEventType.into());
+        }
+        self.posted_events.push((event_type, payload));
+        Ok(())
+    }
+
+    fn subscribe_to_bevy_event(&mut self, event_type: String) -> wasmtime::Result<()> {
+        validate_key(&event_type)?;
+        self.event_subscriptions.insert(event_type);
+        Ok(())
+    }
+
+    fn get_time(&mut self) -> wasmtime::Result<GameTime> {
+        Ok(GameTime {
+            elapsed_secs: self.timestamp.elapsed_secs,
+            delta_secs:   self.timestamp.delta_secs,
+            frame_count:  self.timestamp.frame_count,
+        })
+    }
+
+    fn set_timer(&mut self, name: String, seconds: f32, repeating: bool) -> wasmtime::Result<()> {
+        validate_key(&name)?;
+        validate_dimensions(&[seconds])?;
+        // 间隔为 0 的重复定时器每帧都会到期，没有意义
+        if repeating && seconds == 0.0 {
+            return Err(BevyApiError::InvalidDimensions.into());
+        }
+        let name = self.scoped_key(name);
+        self.timer_commands.push(TimerCommand::Set { name, seconds, repeating });
+        Ok(())
+    }
+
+    fn cancel_timer(&mut self, name: String) -> wasmtime::Result<()> {
+        validate_key(&name)?;
+        let name = self.scoped_key(name);
+        self.timer_commands.push(TimerCommand::Cancel { name });
+        Ok(())
+    }
+
+    fn current_frame(&mut self) -> wasmtime::Result<u64> {
+        Ok(self.current_frame)
+    }
+
+    fn log(&mut self, msg: String) -> wasmtime::Result<()> {
+        // 使用 debug! 避免生产环境性能损耗，发布时自动关闭
+        bevy::log::debug!("[TS] {}", msg);
+        Ok(())
+    }
+}
+
+// ─── WIT 布局类型 → Bevy / 宿主布局类型 ───────────────────────────────────────
+
+impl From<FlexDir> for FlexDirection {
+    fn from(dir: FlexDir) -> Self {
+        match dir {
+            FlexDir::Row    => FlexDirection::Row,
+            FlexDir::Column => FlexDirection::Column,
+        }
+    }
+}
+
+impl From<AlignMode> for AlignItems {
+    fn from(mode: AlignMode) -> Self {
+        match mode {
+            AlignMode::Start   => AlignItems::FlexStart,
+            AlignMode::Center  => AlignItems::Center,
+            AlignMode::End     => AlignItems::FlexEnd,
+            AlignMode::Stretch => AlignItems::Stretch,
+        }
+    }
+}
+
+impl From<JustifyMode> for JustifyContent {
+    fn from(mode: JustifyMode) -> Self {
+        match mode {
+            JustifyMode::Start        => JustifyContent::FlexStart,
+            JustifyMode::Center       => JustifyContent::Center,
+            JustifyMode::End          => JustifyContent::FlexEnd,
+            JustifyMode::SpaceBetween => JustifyContent::SpaceBetween,
+            JustifyMode::SpaceAround  => JustifyContent::SpaceAround,
+        }
+    }
+}
+
+impl From<PanelBorder> for UiBorder {
+    fn from(border: PanelBorder) -> Self {
+        UiBorder {
+            width: border.width,
+            color_r: border.color_r, color_g: border.color_g, color_b: border.color_b, color_a: border.color_a,
+        }
+    }
+}
+
+// ─── WASM 运行时（NonSend Resource）───────────────────────────────────────────
+//
+// 去掉 Arc<Mutex<>>，直接持有 wasmtime 运行时。
+// 通过 Bevy 的 NonSend 调度机制保证线程安全，零锁开销。
+
+/// 单个 WASM Component 实例：独立的 Store 与 Guest 调用句柄
+pub(crate) struct ComponentInstance {
+    pub(crate) store: Store<HostState>,
+    /// WIT 生成的 GameWorld，通过 interface0 字段访问 Guest 调用句柄
+    pub(crate) game_world: GameWorld,
+    /// 可选的 hot-reload 导出；组件按 game-world 构建（未导出）时为 None
+    pub(crate) hot_reload: Option<HotReloadExports>,
+    /// 实例化所用的组件，实例 trap 失效后据此重新实例化（见 retry.rs）
+    pub(crate) component: Component,
+}
+
+/// 组件导出的 hot-reload 接口（见 game-world-reloadable）。
+/// bindgen! 只按 game-world 生成绑定，这里在实例化后按名称查找，缺失不影响加载
+#[derive(Clone, Copy)]
+pub(crate) struct HotReloadExports {
+    save_state: Func,
+    restore_state: Func,
+}
+
+impl HotReloadExports {
+    const INTERFACE: &'static str = "game:logic/hot-reload@0.1.0";
+
+    /// 两个函数都导出时才视为支持热重载状态
+    fn find(store: &mut Store<HostState>, instance: &Instance) -> Option<Self> {
+        let mut exports = instance.exports(&mut *store);
+        let mut hot_reload = exports.instance(Self::INTERFACE)?;
+        Some(HotReloadExports {
+            save_state:    hot_reload.func("save-state")?,
+            restore_state: hot_reload.func("restore-state")?,
+        })
+    }
+
+    pub(crate) fn save_state(&self, store: &mut Store<HostState>) -> wasmtime::Result<Vec<u8>> {
+        let func = self.save_state.typed::<(), (Vec<u8>,)>(&*store)?;
+        let (bytes,) = func.call(&mut *store, ())?;
+        func.post_return(&mut *store)?;
+        Ok(bytes)
+    }
+
+    pub(crate) fn restore_state(&self, store: &mut Store<HostState>, bytes: &[u8]) -> wasmtime::Result<()> {
+        let func = self.restore_state.typed::<(&[u8],), ()>(&*store)?;
+        func.call(&mut *store, (bytes,))?;
+        func.post_return(&mut *store)?;
+        Ok(())
+    }
+}
+
+/// 可同时加载多个 Component（如 logic + ui），共享同一个 PendingUiCommands
+/// 组件字节由 AssetServer 异步加载，加载完成后才出现在 instances 中
+pub struct WasmRuntime {
+    /// Engine / Linker 在所有组件间共享，热重载时复用
+    pub(crate) engine: Engine,
+    pub(crate) linker: Linker<HostState>,
+    /// 组件名 → wasm 资源句柄
+    pub(crate) handles: HashMap<String, Handle<WasmComponentAsset>>,
+    pub(crate) instances: HashMap<String, ComponentInstance>,
+    /// tick 顺序：Startup 时按 WasmConfig 的优先级重排（见 WasmConfig::tick_order）
+    pub(crate) order: Vec<String>,
+    /// 组件名 → 能力配置，Startup 时取自 WasmConfig；未登记的组件不受限制
+    pub(crate) profiles: HashMap<String, CapabilityProfile>,
+    /// 每帧开始时为每个组件重新设置的 fuel；None 表示不计量
+    pub(crate) fuel_per_frame: Option<u64>,
+    /// seed_wasm_rng 设置的种子，之后实例化的组件同样按它播种
+    pub(crate) rng_seed: Option<u64>,
+    /// 加载或实例化失败、目前没有实例的组件；全部组件都失败时显示 MissingLogicNotice
+    pub(crate) failed: HashSet<String>,
+    /// 设置后 wasm_tick 只调用该后端，不再 tick 任何组件实例（见 use_js_fallback）
+    pub(crate) fallback: Option<Box<dyn GameLogicBackend>>,
+}
+
+impl WasmRuntime {
+    /// 用已编译的 component 重新实例化组件 name，不读取、不重新编译 wasm（如"再玩一次"）。
+    /// 新实例使用全新的 Store 与 HostState（命令队列、帧计数、订阅等全部清空，随机数种子按 seed_wasm_rng 重新播种），
+    /// 实例化成功后才替换旧实例；已创建的 UI 实体不受影响，需要时由调用方先切换场景
+    pub fn reset(&mut self, name: &str, engine: &Engine, component: &Component, linker: &Linker<HostState>) -> wasmtime::Result<()> {
+        let namespace = if self.order.len() > 1 { Some(name.to_string()) } else { None };
+        let mut host_state = HostState::new(namespace);
+        host_state.capabilities = self.profile(name);
+        if let Some(seed) = self.rng_seed {
+            host_state.rng = WasmRng::seeded(seed, name);
+        }
+        let fresh = instantiate_precompiled(engine, linker, host_state, component)?;
+        self.instances.insert(name.to_string(), fresh);
+        self.failed.remove(name);
+        Ok(())
+    }
+
+    pub(crate) fn profile(&self, name: &str) -> CapabilityProfile {
+        self.profiles.get(name).copied().unwrap_or_default()
+    }
+
+    /// 固定所有组件的随机数种子，用于测试与回放复现。
+    /// 每个组件的实际种子由 seed 与组件名共同决定，同名组件在不同运行中得到相同序列
+    pub fn seed_wasm_rng(&mut self, seed: u64) {
+        self.rng_seed = Some(seed);
+        for (name, instance) in self.instances.iter_mut() {
+            instance.store.data_mut().rng = WasmRng::seeded(seed, name);
+        }
+    }
+}
+
+/// 缓存 Engine、Linker 与各组件最近一次成功实例化的 Component，供 WasmRuntime::reset 跳过读盘与编译。
+/// 由 WasmPlugin 插入；Component 在异步加载或热重载成功后写入
+#[derive(Resource)]
+pub struct WasmRuntimeResources {
+    pub engine: Engine,
+    pub linker: Linker<HostState>,
+    pub(crate) components: HashMap<String, Component>,
+}
+
+impl WasmRuntimeResources {
+    /// 与 runtime 共享同一个 Engine 与 Linker（均为引用计数，克隆开销很小）
+    pub fn new(runtime: &WasmRuntime) -> Self {
+        WasmRuntimeResources {
+            engine: runtime.engine.clone(),
+            linker: runtime.linker.clone(),
+            components: HashMap::new(),
+        }
+    }
+
+    pub fn component(&self, name: &str) -> Option<&Component> {
+        self.components.get(name)
+    }
+
+    /// 用缓存重置所有已编译的组件；单个组件失败不影响其他组件，返回失败的组件名与原因
+    pub fn reset_all(&self, runtime: &mut WasmRuntime) -> Vec<(String, wasmtime::Error)> {
+        let mut errors = Vec::new();
+        for name in runtime.order.clone() {
+            let Some(component) = self.components.get(&name) else { continue; };
+            if let Err(e) = runtime.reset(&name, &self.engine, component, &self.linker) {
+                errors.push((name, e));
+            }
+        }
+        errors
+    }
+}
+
+// ─── 初始化 WASM 运行时 ───────────────────────────────────────────────────────
+//
+// WasmRuntimeBuilder 汇总引擎配置：source 同步读取并实例化一个组件（测试、工具、单文件发布），
+// components 只登记组件名，字节由 load_wasm_components 通过 AssetServer 异步加载（游戏使用）。
+
+/// 构建 WasmRuntime 的各步失败原因
+#[derive(Debug)]
+pub enum WasmInitError {
+    /// 读取 WasmSource::File 失败（文件不存在、无权限等）
+    FileNotFound { path: PathBuf, source: std::io::Error },
+    /// 创建 Engine 失败（配置不被当前平台支持）
+    Engine(wasmtime::Error),
+    /// 注册 bevy-api 到 Linker 失败
+    Linker(wasmtime::Error),
+    /// 字节不是合法的 WASM Component
+    Parse(wasmtime::Error),
+    /// 实例化失败（import 不匹配、start 函数 trap 等）
+    Instantiation(wasmtime::Error),
+}
+
+impl std::fmt::Display for WasmInitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WasmInitError::FileNotFound { path, source } => write!(f, "读取 {} 失败: {}", path.display(), source),
+            WasmInitError::Engine(e) => write!(f, "创建 wasmtime Engine 失败: {}", e),
+            WasmInitError::Linker(e) => write!(f, "注册 bevy-api 失败: {}", e),
+            WasmInitError::Parse(e) => write!(f, "解析 WASM Component 失败: {}", e),
+            WasmInitError::Instantiation(e) => write!(f, "实例化 WASM Component 失败: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for WasmInitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WasmInitError::FileNotFound { source, .. } => Some(source),
+            WasmInitError::Engine(e) | WasmInitError::Linker(e)
+            | WasmInitError::Parse(e) | WasmInitError::Instantiation(e) => Some(e.as_ref()),
+        }
+    }
+}
+
+/// 同步加载的组件字节来源
+pub enum WasmSource {
+    /// 开发时的默认方式：从磁盘读取，改完 TS 重新 build 即可生效
+    File(PathBuf),
+    /// 编进可执行文件的字节，如 `include_bytes!("../assets/game_logic.wasm")`，不访问文件系统
+    Embedded(&'static [u8]),
+}
+
+impl Default for WasmSource {
+    fn default() -> Self {
+        WasmSource::File(PathBuf::from("assets/game_logic.wasm"))
+    }
+}
+
+impl WasmSource {
+    /// 组件名：文件取文件名（不含扩展名），内嵌字节固定为 game_logic
+    pub(crate) fn component_name(&self) -> String {
+        match self {
+            WasmSource::File(path) => path.file_stem()
+                .map_or_else(|| "game_logic".to_string(), |stem| stem.to_string_lossy().into_owned()),
+            WasmSource::Embedded(_) => "game_logic".to_string(),
+        }
+    }
+
+    pub(crate) fn bytes(&self) -> Result<std::borrow::Cow<'static, [u8]>, WasmInitError> {
+        match self {
+            WasmSource::File(path) => std::fs::read(path)
+                .map(std::borrow::Cow::Owned)
+                .map_err(|source| WasmInitError::FileNotFound { path: path.clone(), source }),
+            WasmSource::Embedded(bytes) => Ok(std::borrow::Cow::Borrowed(bytes)),
+        }
+    }
+}
+
+/// WasmRuntime 的构建器，默认开启 epoch 中断、不限制 fuel
+pub struct WasmRuntimeBuilder {
+    source: Option<WasmSource>,
+    components: Vec<String>,
+    fuel: Option<u64>,
+    epoch_interruption: bool,
+}
+
+impl Default for WasmRuntimeBuilder {
+    fn default() -> Self {
+        WasmRuntimeBuilder {
+            source: None,
+            components: Vec::new(),
+            fuel: None,
+            epoch_interruption: true,
+        }
+    }
+}
+
+impl WasmRuntimeBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// build 时同步读取并实例化该文件，组件名取文件名（不含扩展名），排在 components 之后 tick
+    pub fn wasm_path(self, path: impl Into<PathBuf>) -> Self {
+        self.source(WasmSource::File(path.into()))
+    }
+
+    /// 同 wasm_path，但可以传入内嵌字节
+    pub fn source(mut self, source: WasmSource) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// 由 AssetServer 异步加载的组件名，按 tick 顺序排列
+    pub fn components(mut self, names: impl IntoIterator<Item = String>) -> Self {
+        self.components.extend(names);
+        self
+    }
+
+    /// 每个组件每帧可消耗的 fuel（约等于执行的 WASM 指令数），耗尽时本帧后续调用 trap
+    pub fn fuel(mut self, per_frame: u64) -> Self {
+        self.fuel = Some(per_frame);
+        self
+    }
+
+    /// 关闭后 on-shutdown 超时无法强制中断，退出时可能被失控的 TS 卡住
+    pub fn epoch_interruption(mut self, enabled: bool) -> Self {
+        self.epoch_interruption = enabled;
+        self
+    }
+
+    pub fn build(self) -> Result<WasmRuntime, WasmInitError> {
+        self.build_inner(false)
+    }
+
+    /// 同 build，但 source 缺失或无效时不返回错误：组件照常登记、没有实例，wasm_tick 跳过它，
+    /// 窗口显示 "No game logic loaded"。Engine / Linker 失败仍返回错误
+    pub fn build_or_fallback(self) -> Result<WasmRuntime, WasmInitError> {
+        self.build_inner(true)
+    }
+
+    pub(crate) fn build_inner(self, fallback: bool) -> Result<WasmRuntime, WasmInitError> {
+        // 启用 Component Model
+        let mut config = Config::new();
+        config.wasm_component_model(true);
+        // epoch 中断供 on-shutdown 超时使用（见 shutdown_instance）
+        config.epoch_interruption(self.epoch_interruption);
+        config.consume_fuel(self.fuel.is_some());
+
+        let engine = Engine::new(&config).map_err(WasmInitError::Engine)?;
+        let linker = new_linker(&engine).map_err(WasmInitError::Linker)?;
+        let mut runtime = WasmRuntime {
+            engine,
+            linker,
+            handles: HashMap::new(),
+            instances: HashMap::new(),
+            order: self.components,
+            profiles: HashMap::new(),
+            fuel_per_frame: self.fuel,
+            rng_seed: None,
+            failed: HashSet::new(),
+            fallback: None,
+        };
+
+        if let Some(source) = self.source {
+            let name = source.component_name();
+            runtime.order.push(name.clone());
+            if let Err(e) = runtime.instantiate_source(&name, &source) {
+                if !fallback {
+                    return Err(e);
+                }
+                eprintln!("[WASM] 组件 {} 不可用，以空逻辑启动: {}", name, e);
+                runtime.failed.insert(name);
+            }
+        }
+        Ok(runtime)
+    }
+}
+
+impl WasmRuntime {
+    /// 同步读取、编译并实例化 source；name 须已在 order 中
+    pub(crate) fn instantiate_source(&mut self, name: &str, source: &WasmSource) -> Result<(), WasmInitError> {
+        let bytes = source.bytes()?;
+        let component = Component::new(&self.engine, &bytes).map_err(WasmInitError::Parse)?;
+        let namespace = if self.order.len() > 1 { Some(name.to_string()) } else { None };
+        let mut host_state = HostState::new(namespace);
+        host_state.capabilities = self.profile(name);
+        let instance = instantiate_precompiled(&self.engine, &self.linker, host_state, &component)
+            .map_err(WasmInitError::Instantiation)?;
+        self.instances.insert(name.to_string(), instance);
+        Ok(())
+    }
+}
+
+/// 创建空运行时：Engine + 注册好 bevy-api 的 Linker，不含任何组件实例
+pub(crate) fn new_runtime(config: &Config, order: Vec<String>) -> wasmtime::Result<WasmRuntime> {
+    let engine = Engine::new(config)?;
+    let linker = new_linker(&engine)?;
+
+    Ok(WasmRuntime {
+        engine,
+        linker,
+        handles: HashMap::new(),
+        instances: HashMap::new(),
+        order,
+        profiles: HashMap::new(),
+        fuel_per_frame: None,
+        rng_seed: None,
+        failed: HashSet::new(),
+        fallback: None,
+    })
+}
+
+/// 构建 Linker：注册 bevy-api import 实现（所有组件共用）
+pub(crate) fn new_linker(engine: &Engine) -> wasmtime::Result<Linker<HostState>> {
+    let mut linker: Linker<HostState> = Linker::new(engine);
+
+    // bindgen! 生成的函数：将 HostState 的 Host impl 注册到 Linker
+    GameWorld::add_to_linker(&mut linker, |state: &mut HostState| state)?;
+    Ok(linker)
+}
+
+/// 从内存字节同步构建只含一个组件（game_logic）的运行时
+/// 不经过 AssetServer / 文件系统，供无窗口测试等场景直接喂入 wasm 字节
+pub(crate) fn build_runtime(wasm_bytes: &[u8], config: &Config) -> wasmtime::Result<WasmRuntime> {
+    let name = "game_logic".to_string();
+    let mut runtime = new_runtime(config, vec![name.clone()])?;
+    let instance = instantiate_component(&runtime.engine, &runtime.linker, HostState::new(None), wasm_bytes)?;
+    runtime.instances.insert(name, instance);
+    Ok(runtime)
+}
+
+/// 编译并实例化一个 WASM Component
+pub(crate) fn instantiate_component(
+    engine: &Engine,
+    linker: &Linker<HostState>,
+    host_state: HostState,
+    wasm_bytes: &[u8],
+) -> wasmtime::Result<ComponentInstance> {
+    // 加载 WASM Component（TypeScript 编译产物）
+    let component = Component::new(engine, wasm_bytes)?;
+    instantiate_precompiled(engine, linker, host_state, &component)
+}
+
+/// 实例化已编译的 Component；编译是主要开销，复用 Component 可跳过
+pub(crate) fn instantiate_precompiled(
+    engine: &Engine,
+    linker: &Linker<HostState>,
+    host_state: HostState,
+    component: &Component,
+) -> wasmtime::Result<ComponentInstance> {
+    let mut store = Store::new(engine, host_state);
+    // epoch 只由退出看门狗推进，正常运行期间不会到达该截止点
+    store.set_epoch_deadline(1);
+    // 开启 fuel 计量时实例化（含 start 函数）不受限制，之后由 wasm_tick 每帧重设；
+    // 未开启时 set_fuel 返回错误，忽略即可
+    let _ = store.set_fuel(u64::MAX);
+
+    // 实例化：WIT 生成的 GameWorld::instantiate 替代手动 linker.instantiate
+    let (game_world, instance) = GameWorld::instantiate(&mut store, component, linker)?;
+
+    // 接口版本不一致时 instantiate 仍可能成功，之后才在调用时出现类型错乱，因此先比对哈希
+    let guest_hash = game_world.interface0.call_wit_hash(&mut store)?;
+    if guest_hash != WIT_HASH {
+        return Err(WitHashMismatch::new(&guest_hash).into());
+    }
+
+    let hot_reload = HotReloadExports::find(&mut store, &instance);
+    Ok(ComponentInstance { store, game_world, hot_reload, component: component.clone() })
+}
+
+/// 宿主编译时 wit/game.wit 的 SHA-256（build.rs 计算）
+pub const WIT_HASH: &str = env!("GAME_WIT_HASH");
+
+/// 组件导出的 wit-hash 与 WIT_HASH 不一致，说明哪一侧需要重新构建
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WitHashMismatch(pub(crate) String);
+
+impl WitHashMismatch {
+    /// 源码树中的 game.wit 视为最新版本：与哪一侧一致，另一侧就是过期的；
+    /// 读不到源码（如发布版）时只报告两侧不同
+    pub(crate) fn new(guest_hash: &str) -> Self {
+        use sha2::{Digest, Sha256};
+
+        let current: Option<String> = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/wit/game.wit"))
+            .ok()
+            .map(|wit| Sha256::digest(wit).iter().map(|byte| format!("{:02x}", byte)).collect());
+        let short = |hash: &str| hash.chars().take(12).collect::<String>();
+        let (guest, host) = (short(guest_hash), short(WIT_HASH));
+        WitHashMismatch(match current.as_deref() {
+            Some(current) if current == WIT_HASH => format!(
+                "组件基于旧版 game.wit 构建（组件 {}，当前 {}），请在 game-logic 目录重新执行 npm run build",
+                guest, host,
+            ),
+            Some(current) if current == guest_hash => format!(
+                "宿主基于旧版 game.wit 编译（宿主 {}，当前 {}），请重新执行 cargo build",
+                host, guest,
+            ),
+            _ => format!(
+                "组件与宿主基于不同版本的 game.wit（组件 {}，宿主 {}），请用同一份 game.wit 重新构建两侧",
+                guest, host,
+            ),
+        })
+    }
+}
+
+impl std::fmt::Display for WitHashMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for WitHashMismatch {}
+
+/// Startup：通过 AssetServer 发起所有组件的异步加载
+pub(crate) fn load_wasm_components(
+    asset_server: Res<AssetServer>,
+    wasm_config: Res<WasmConfig>,
+    mut wasm: NonSendMut<WasmRuntime>,
+) {
+    // 构建时同步加载的组件（WasmRuntimeBuilder::source）不在配置里，排在最后
+    let mut order = wasm_config.tick_order();
+    let preloaded: Vec<String> = wasm.order.iter().filter(|name| !order.contains(name)).cloned().collect();
+    order.extend(preloaded);
+    wasm.order = order;
+
+    for interface in &wasm_config.components {
+        let handle = asset_server.load(interface.path.clone());
+        wasm.handles.insert(interface.name.clone(), handle);
+        wasm.profiles.insert(interface.name.clone(), interface.capabilities);
+    }
+}
+
+/// 监听 wasm 资源加载完成：首次加载时实例化，之后（文件变化）走热重载。
+/// 清单要求的游戏版本高于 GameVersion 时两者都不执行，改为发出 WasmCompatibilityError；
+/// 首次实例化因 wit-hash 不一致失败时同样发出该事件
+pub(crate) fn instantiate_loaded_components(
+    mut events: EventReader<AssetEvent<WasmComponentAsset>>,
+    assets: Res<Assets<WasmComponentAsset>>,
+    mut wasm: NonSendMut<WasmRuntime>,
+    game_version: Res<GameVersion>,
+    mut incompatible: EventWriter<WasmCompatibilityError>,
+    mut resources: ResMut<WasmRuntimeResources>,
+) {
+    let WasmRuntime { ref engine, ref linker, ref handles, ref order, ref profiles, ref mut instances, ref mut failed, rng_seed, .. } = *wasm;
+
+    for event in events.read() {
+        let AssetEvent::LoadedWithDependencies { id } = event else { continue; };
+        let Some(name) = order.iter().find(|name| handles.get(*name).map(|h| h.id()) == Some(*id)) else {
+            continue;
+        };
+        let Some(asset) = assets.get(*id) else { continue; };
+
+        if let Some(meta) = &asset.meta {
+            if let Err(message) = meta.check_compatibility(name, *game_version) {
+                eprintln!("[WASM] {}", message);
+                if !instances.contains_key(name) {
+                    failed.insert(name.clone());
+                }
+                incompatible.send(WasmCompatibilityError { component: name.clone(), message });
+                continue;
+            }
+        }
+
+        // 编译结果只在实例化成功后缓存，WasmRuntimeResources 始终对应正在运行的版本
+        match instances.get_mut(name) {
+            Some(instance) => {
+                let reloaded = Component::new(engine, &asset.bytes).and_then(|component| {
+                    reload_instance(engine, linker, name, instance, &component)?;
+                    Ok(component)
+                });
+                match reloaded {
+                    Ok(component) => {
+                        resources.components.insert(name.clone(), component);
+                        println!("[热重载] 组件已重载: {}", name);
+                    }
+                    Err(e) => eprintln!("[热重载] 重载失败 [{}]，继续使用旧实例: {}", name, e),
+                }
+            }
+            None => {
+                let namespace = if order.len() > 1 { Some(name.clone()) } else { None };
+                let mut host_state = HostState::new(namespace);
+                host_state.capabilities = profiles.get(name).copied().unwrap_or_default();
+                if let Some(seed) = rng_seed {
+                    host_state.rng = WasmRng::seeded(seed, name);
+                }
+                let instantiated = Component::new(engine, &asset.bytes).and_then(|component| {
+                    let instance = instantiate_precompiled(engine, linker, host_state, &component)?;
+                    Ok((instance, component))
+                });
+                match instantiated {
+                    Ok((instance, component)) => {
+                        instances.insert(name.clone(), instance);
+                        resources.components.insert(name.clone(), component);
+                        failed.remove(name);
+                        println!("[WASM] Component Model 初始化完成: {}", name);
+                    }
+                    Err(e) => {
+                        eprintln!("[WASM] Component 实例化失败 [{}]: {}", name, e);
+                        failed.insert(name.clone());
+                        if let Some(mismatch) = e.downcast_ref::<WitHashMismatch>() {
+                            incompatible.send(WasmCompatibilityError { component: name.clone(), message: mismatch.0.clone() });
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
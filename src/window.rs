@@ -0,0 +1,97 @@
+// ─── 窗口 ─────────────────────────────────────────────────────────────────────
+//
+// 窗口只能在主线程修改：wasm_tick 把请求转存到 PendingWindowTitle / PendingWindowIcon，
+// apply_window_title 改写 Window::title，apply_window_icon 通过 WinitWindows 设置平台图标。
+// 主窗口的焦点变化由 notify_wasm_window_focus 转存到 WindowFocusQueue，尺寸变化由 wasm_tick 经
+// WindowSizeDebounce 去重，二者都在下一次 wasm_tick 转给 Guest。
+
+use super::*;
+use bevy::winit::WinitWindows;
+
+/// 多组件同帧请求时，按 tick 顺序最后一个生效
+#[derive(Resource, Default)]
+pub(crate) struct PendingWindowTitle(pub(crate) Option<String>);
+
+/// 图标路径，相对 assets/ 目录
+#[derive(Resource, Default)]
+pub(crate) struct PendingWindowIcon(pub(crate) Option<String>);
+
+/// 主窗口的焦点变化（true 为获得焦点），notify_wasm_window_focus 写入、wasm_tick 转给 Guest 后清空
+#[derive(Resource, Default, Debug)]
+pub(crate) struct WindowFocusQueue(pub(crate) Vec<bool>);
+
+/// 把主窗口的 WindowFocused 事件转存到 WindowFocusQueue。
+/// Bevy 没有单独的失焦事件，失去焦点同样是 WindowFocused { focused: false }；
+/// 同一帧内连续相同的状态只保留一次
+pub(crate) fn notify_wasm_window_focus(
+    mut events: EventReader<WindowFocused>,
+    primary: Query<Entity, With<PrimaryWindow>>,
+    mut queue: ResMut<WindowFocusQueue>,
+) {
+    let primary = primary.get_single().ok();
+    for event in events.read() {
+        if Some(event.window) != primary || queue.0.last() == Some(&event.focused) {
+            continue;
+        }
+        queue.0.push(event.focused);
+    }
+}
+
+/// 拖动窗口边缘时 WindowResized 每帧可能有多个，且常带小数；
+/// 只在取整后的尺寸与上次通知不同时才通知 Guest
+#[derive(Default)]
+pub(crate) struct WindowSizeDebounce(pub(crate) Option<UVec2>);
+
+impl WindowSizeDebounce {
+    pub(crate) fn update<'a>(&mut self, events: impl IntoIterator<Item = &'a WindowResized>) -> Option<UVec2> {
+        let last = events.into_iter().last()?;
+        let size = UVec2::new(last.width.round() as u32, last.height.round() as u32);
+        if self.0 == Some(size) {
+            return None;
+        }
+        self.0 = Some(size);
+        Some(size)
+    }
+}
+
+/// 应用 set-window-title 请求
+pub(crate) fn apply_window_title(
+    mut pending: ResMut<PendingWindowTitle>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Some(title) = pending.0.take() else { return; };
+    let Ok(mut window) = windows.get_single_mut() else {
+        warn!("[窗口] set-window-title 失败：找不到主窗口");
+        return;
+    };
+    window.title = title;
+}
+
+/// 应用 set-window-icon 请求：解码 PNG 后交给 winit；Bevy 的 Window 组件没有图标字段。
+/// 未使用 WinitPlugin 的宿主（如无窗口测试）没有 WinitWindows，请求按找不到主窗口处理
+pub(crate) fn apply_window_icon(
+    mut pending: ResMut<PendingWindowIcon>,
+    windows: Query<Entity, With<PrimaryWindow>>,
+    winit_windows: Option<NonSend<WinitWindows>>,
+) {
+    let Some(path) = pending.0.take() else { return; };
+    let window = windows.get_single().ok().zip(winit_windows.as_deref())
+        .and_then(|(entity, winit_windows)| winit_windows.get_window(entity));
+    let Some(window) = window else {
+        warn!("[窗口] set-window-icon 失败：找不到主窗口");
+        return;
+    };
+    let full_path = Path::new("assets").join(&path);
+    let image = match image::open(&full_path) {
+        Ok(image) => image.into_rgba8(),
+        Err(e) => {
+            warn!("[窗口] set-window-icon 失败：读取 {} 出错: {}", full_path.display(), e);
+            return;
+        }
+    };
+    let (width, height) = image.dimensions();
+    match winit::window::Icon::from_rgba(image.into_raw(), width, height) {
+        Ok(icon) => window.set_window_icon(Some(icon)),
+        Err(e) => warn!("[窗口] set-window-icon 失败：{} 不是有效图标: {}", path, e),
+    }
+}
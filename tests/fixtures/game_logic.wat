@@ -0,0 +1,232 @@
+;; ─── 测试夹具：game-logic 的最小实现 ─────────────────────────────────────────
+;;
+;; build.rs 把 @WIT_HASH@ 替换为当前 wit/game.wit 的哈希，再按 game-world-reloadable 编码为组件；
+;; 去掉 @HOT_RELOAD_BEGIN / @HOT_RELOAD_END 之间的导出后，另按 game-world 编码一份最小夹具。
+;; 本文件只是 core module，导入 / 导出使用 canonical ABI 展平后的签名。
+;; 行为与 game-logic/src/index.ts 的核心规则一致，供 tests/ 与单元测试驱动真实的 Guest 调用：
+;;   - process-keyboard 原样返回输入
+;;   - update-game 按速度与 delta 移动，x 钳制在 ±300、y 钳制在 ±200
+;;   - on-ui-event("toggle_panel")：首次创建 main_panel（-120, 20, 260×200）与标题文字，
+;;     之后在隐藏 / 显示之间切换（set-visible），不重复创建
+;;   - on-ui-event("flaky")：共享变量 flaky_failures 大于 0 时减一并 trap，否则写入 flaky_done = 1；
+;;     计数保存在宿主侧，重新实例化后仍然有效，供重试测试使用
+;;   - on-scene-load 重置面板状态（旧场景的 UI 已被宿主销毁）
+;;   - hot-reload 保存 / 恢复面板的两个标志 [created, visible]
+;;
+;; 内存布局：
+;;   0x0100  wit-hash（64 字节）
+;;   0x0400  字符串常量
+;;   0x0800  返回区（多值返回时写入结果，返回其地址）
+;;   0x0840  save-state 输出的状态字节
+;;   0x0900  spawn-panel 的 panel-config（参数超过 16 个展平值，按内存布局传指针）
+;;   0x10000 起为 cabi_realloc 的 bump 堆，只分配不释放
+
+(module
+  ;; ── 导入：用到的 bevy-api 函数 ─────────────────────────────────────────────
+  (import "game:logic/bevy-api@0.1.0" "spawn-panel" (func $spawn_panel (param i32)))
+  ;; text-config 恰好展平为 16 个值：key、parent-key、text（各 ptr+len）、font-size、rgb、
+  ;; font 与 font-path（各为 option<string>：判别值 + ptr + len）
+  (import "game:logic/bevy-api@0.1.0" "spawn-text"
+    (func $spawn_text (param i32 i32 i32 i32 i32 i32 f32 f32 f32 f32 i32 i32 i32 i32 i32 i32)))
+  (import "game:logic/bevy-api@0.1.0" "set-visible" (func $set_visible (param i32 i32 i32)))
+  (import "game:logic/bevy-api@0.1.0" "set-var" (func $set_var (param i32 i32 f64)))
+  ;; option<float64> 展平为两个值，经最后一个参数指向的返回区写回：判别值(0) 与 f64(8)
+  (import "game:logic/bevy-api@0.1.0" "get-var" (func $get_var (param i32 i32 i32)))
+
+  (memory (export "memory") 2)
+
+  (global $heap (mut i32) (i32.const 0x10000))
+  (global $panel_created (mut i32) (i32.const 0))
+  (global $panel_visible (mut i32) (i32.const 0))
+
+  (data (i32.const 0x100) "@WIT_HASH@")
+  (data (i32.const 0x400) "main_panel")
+  (data (i32.const 0x410) "main_panel.title")
+  (data (i32.const 0x430) "title")
+  (data (i32.const 0x440) "toggle_panel")
+  (data (i32.const 0x450) "flaky")
+  (data (i32.const 0x460) "flaky_failures")
+  (data (i32.const 0x470) "flaky_done")
+
+  ;; ── 内存分配：宿主向 Guest 传字符串 / 列表时调用 ───────────────────────────
+  (func $cabi_realloc (export "cabi_realloc")
+    (param $old i32) (param $old_size i32) (param $align i32) (param $new_size i32) (result i32)
+    (local $ptr i32)
+    (local $end i32)
+    ;; 按 align 向上取整
+    (local.set $ptr
+      (i32.and
+        (i32.add (global.get $heap) (i32.sub (local.get $align) (i32.const 1)))
+        (i32.sub (i32.const 0) (local.get $align))))
+    (local.set $end (i32.add (local.get $ptr) (local.get $new_size)))
+    ;; 超出当前内存时按页扩容
+    (if (i32.gt_u (local.get $end) (i32.shl (memory.size) (i32.const 16)))
+      (then
+        (if (i32.eq
+              (memory.grow
+                (i32.shr_u
+                  (i32.add (i32.sub (local.get $end) (i32.shl (memory.size) (i32.const 16))) (i32.const 0xffff))
+                  (i32.const 16)))
+              (i32.const -1))
+          (then unreachable))))
+    (global.set $heap (local.get $end))
+    ;; 重新分配时保留旧内容
+    (if (local.get $old)
+      (then
+        (memory.copy
+          (local.get $ptr)
+          (local.get $old)
+          (select (local.get $old_size) (local.get $new_size)
+            (i32.lt_u (local.get $old_size) (local.get $new_size))))))
+    (local.get $ptr))
+
+  ;; ── 辅助函数 ───────────────────────────────────────────────────────────────
+  (func $str_eq (param $a i32) (param $a_len i32) (param $b i32) (param $b_len i32) (result i32)
+    (local $i i32)
+    (if (i32.ne (local.get $a_len) (local.get $b_len))
+      (then (return (i32.const 0))))
+    (block $done
+      (loop $next
+        (br_if $done (i32.eq (local.get $i) (local.get $a_len)))
+        (if (i32.ne
+              (i32.load8_u (i32.add (local.get $a) (local.get $i)))
+              (i32.load8_u (i32.add (local.get $b) (local.get $i))))
+          (then (return (i32.const 0))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (i32.const 1))
+
+  (func $clamp (param $value f32) (param $bound f32) (result f32)
+    (f32.max (f32.neg (local.get $bound)) (f32.min (local.get $bound) (local.get $value))))
+
+  ;; 与 index.ts 的 UiManager.showPanel 相同的面板与标题
+  (func $spawn_main_panel
+    ;; panel-config：key(0) parent-key(8) x..color-a(20..48) z-index(52) border(60) corner-radius(84)，共 92 字节
+    (i32.store offset=0 (i32.const 0x900) (i32.const 0x400))
+    (i32.store offset=4 (i32.const 0x900) (i32.const 10))
+    (i32.store8 offset=8 (i32.const 0x900) (i32.const 0))
+    (f32.store offset=20 (i32.const 0x900) (f32.const -120))
+    (f32.store offset=24 (i32.const 0x900) (f32.const 20))
+    (f32.store offset=28 (i32.const 0x900) (f32.const 260))
+    (f32.store offset=32 (i32.const 0x900) (f32.const 200))
+    (f32.store offset=36 (i32.const 0x900) (f32.const 0.05))
+    (f32.store offset=40 (i32.const 0x900) (f32.const 0.05))
+    (f32.store offset=44 (i32.const 0x900) (f32.const 0.15))
+    (f32.store offset=48 (i32.const 0x900) (f32.const 0.92))
+    (i32.store8 offset=52 (i32.const 0x900) (i32.const 0))
+    (i32.store8 offset=60 (i32.const 0x900) (i32.const 0))
+    (i32.store8 offset=84 (i32.const 0x900) (i32.const 0))
+    (call $spawn_panel (i32.const 0x900))
+    (call $spawn_text
+      (i32.const 0x410) (i32.const 16)
+      (i32.const 0x400) (i32.const 10)
+      (i32.const 0x430) (i32.const 5)
+      (f32.const 20) (f32.const 1) (f32.const 1) (f32.const 1)
+      (i32.const 0) (i32.const 0) (i32.const 0)
+      (i32.const 0) (i32.const 0) (i32.const 0)))
+
+  (func $toggle_panel
+    (if (global.get $panel_visible)
+      (then
+        (call $set_visible (i32.const 0x400) (i32.const 10) (i32.const 0))
+        (global.set $panel_visible (i32.const 0))
+        (return)))
+    (if (global.get $panel_created)
+      (then
+        (call $set_visible (i32.const 0x400) (i32.const 10) (i32.const 1))
+        (global.set $panel_visible (i32.const 1))
+        (return)))
+    (call $spawn_main_panel)
+    (global.set $panel_created (i32.const 1))
+    (global.set $panel_visible (i32.const 1)))
+
+  (func $flaky
+    (call $get_var (i32.const 0x460) (i32.const 14) (i32.const 0x800))
+    (if (i32.and
+          (i32.load8_u (i32.const 0x800))
+          (f64.gt (f64.load offset=8 (i32.const 0x800)) (f64.const 0)))
+      (then
+        (call $set_var
+          (i32.const 0x460) (i32.const 14)
+          (f64.sub (f64.load offset=8 (i32.const 0x800)) (f64.const 1)))
+        unreachable))
+    (call $set_var (i32.const 0x470) (i32.const 10) (f64.const 1)))
+
+  ;; ── 导出：game-logic ───────────────────────────────────────────────────────
+  (func (export "game:logic/game-logic@0.1.0#process-keyboard")
+    (param $right i32) (param $left i32) (param $up i32) (param $down i32) (result i32)
+    (i32.store8 offset=0 (i32.const 0x800) (local.get $right))
+    (i32.store8 offset=1 (i32.const 0x800) (local.get $left))
+    (i32.store8 offset=2 (i32.const 0x800) (local.get $up))
+    (i32.store8 offset=3 (i32.const 0x800) (local.get $down))
+    (i32.const 0x800))
+
+  (func (export "game:logic/game-logic@0.1.0#update-game")
+    (param $right i32) (param $left i32) (param $up i32) (param $down i32)
+    (param $x f32) (param $y f32) (param $speed f32) (param $delta f32) (result i32)
+    (local $vx f32)
+    (local $vy f32)
+    (local.set $vx
+      (f32.sub
+        (select (local.get $speed) (f32.const 0) (local.get $right))
+        (select (local.get $speed) (f32.const 0) (local.get $left))))
+    (local.set $vy
+      (f32.sub
+        (select (local.get $speed) (f32.const 0) (local.get $up))
+        (select (local.get $speed) (f32.const 0) (local.get $down))))
+    (f32.store offset=0 (i32.const 0x800)
+      (call $clamp (f32.add (local.get $x) (f32.mul (local.get $vx) (local.get $delta))) (f32.const 300)))
+    (f32.store offset=4 (i32.const 0x800)
+      (call $clamp (f32.add (local.get $y) (f32.mul (local.get $vy) (local.get $delta))) (f32.const 200)))
+    (i32.const 0x800))
+
+  (func (export "game:logic/game-logic@0.1.0#on-collision") (param f32 f32))
+
+  (func (export "game:logic/game-logic@0.1.0#on-ui-event") (param $ptr i32) (param $len i32)
+    (if (call $str_eq (local.get $ptr) (local.get $len) (i32.const 0x440) (i32.const 12))
+      (then (call $toggle_panel)))
+    (if (call $str_eq (local.get $ptr) (local.get $len) (i32.const 0x450) (i32.const 5))
+      (then (call $flaky))))
+
+  (func (export "game:logic/game-logic@0.1.0#on-animation-complete") (param i32 i32))
+
+  (func (export "game:logic/game-logic@0.1.0#on-entity-clicked") (param i32 i32))
+
+  ;; text-input：typed-chars(2) backspaces enter focused-key(3)
+  (func (export "game:logic/game-logic@0.1.0#on-text-input") (param i32 i32 i32 i32 i32 i32 i32))
+
+  (func (export "game:logic/game-logic@0.1.0#on-resize") (param i32 i32))
+
+  (func (export "game:logic/game-logic@0.1.0#on-window-focus-changed") (param i32))
+
+  (func (export "game:logic/game-logic@0.1.0#on-focus-changed") (param i32 i32 i32 i32 i32 i32))
+
+  (func (export "game:logic/game-logic@0.1.0#on-scene-load") (param i32 i32)
+    (global.set $panel_created (i32.const 0))
+    (global.set $panel_visible (i32.const 0)))
+
+  (func (export "game:logic/game-logic@0.1.0#on-event") (param i32 i32 i32 i32))
+
+  (func (export "game:logic/game-logic@0.1.0#on-shutdown"))
+
+  (func (export "game:logic/game-logic@0.1.0#wit-hash") (result i32)
+    (i32.store offset=0 (i32.const 0x800) (i32.const 0x100))
+    (i32.store offset=4 (i32.const 0x800) (i32.const 64))
+    (i32.const 0x800))
+
+  ;; ── 导出：hot-reload（可选）─────────────────────────────────────────────────
+  ;; @HOT_RELOAD_BEGIN
+  (func (export "game:logic/hot-reload@0.1.0#save-state") (result i32)
+    (i32.store8 offset=0 (i32.const 0x840) (global.get $panel_created))
+    (i32.store8 offset=1 (i32.const 0x840) (global.get $panel_visible))
+    (i32.store offset=0 (i32.const 0x800) (i32.const 0x840))
+    (i32.store offset=4 (i32.const 0x800) (i32.const 2))
+    (i32.const 0x800))
+
+  (func (export "game:logic/hot-reload@0.1.0#restore-state") (param $ptr i32) (param $len i32)
+    (if (i32.ge_u (local.get $len) (i32.const 2))
+      (then
+        (global.set $panel_created (i32.load8_u offset=0 (local.get $ptr)))
+        (global.set $panel_visible (i32.load8_u offset=1 (local.get $ptr))))))
+  ;; @HOT_RELOAD_END
+)
@@ -0,0 +1,161 @@
+// 无窗口集成测试：用 build.rs 生成的夹具组件驱动真实的 Guest 调用，不启动渲染与窗口。
+
+use bevy::prelude::{KeyCode, Transform, Vec2};
+use bevy_xpbd_2d::prelude::LinearVelocity;
+use test_game::test_support::{
+    fixture_runtime, keys, player_position, player_speed, press_key, register_wasm_tick, run_player_teleport, set_game_state_health,
+    set_game_state_position, spawn_player, tick_app, BevyApiHost, PlayerState,
+};
+use test_game::{GamePaused, SharedState, WasmRuntime};
+
+#[test]
+fn update_game_follows_scripted_input() {
+    let mut runtime = fixture_runtime();
+    let mut guest = runtime.guest("game_logic");
+
+    // (输入, 期望位置)：速度 200，delta 0.1 → 每帧 20 像素
+    let script = [
+        (keys(true, false, false, false), (20.0, 0.0)),
+        (keys(true, false, false, false), (40.0, 0.0)),
+        (keys(false, false, true, false), (40.0, 20.0)),
+        (keys(false, true, false, true),  (20.0, 0.0)),
+        (keys(false, false, false, false), (20.0, 0.0)),
+    ];
+
+    let mut x = 0.0;
+    let mut y = 0.0;
+    for (frame, (input, (expected_x, expected_y))) in script.into_iter().enumerate() {
+        let keyboard = guest.process_keyboard(input).expect("process-keyboard 调用失败");
+        let result = guest
+            .update_game(keyboard, PlayerState { x, y, speed: 200.0 }, 0.1)
+            .expect("update-game 调用失败");
+        assert!(
+            (result.x - expected_x).abs() < 1e-3 && (result.y - expected_y).abs() < 1e-3,
+            "第 {} 帧位置不符：期望 ({}, {})，实际 ({}, {})",
+            frame, expected_x, expected_y, result.x, result.y,
+        );
+        x = result.x;
+        y = result.y;
+    }
+}
+
+#[test]
+fn update_game_clamps_to_boundary() {
+    let mut runtime = fixture_runtime();
+    let result = runtime
+        .guest("game_logic")
+        .update_game(keys(true, false, true, false), PlayerState { x: 290.0, y: 190.0, speed: 200.0 }, 1.0)
+        .expect("update-game 调用失败");
+    assert_eq!((result.x, result.y), (300.0, 200.0));
+}
+
+#[test]
+fn paused_game_ignores_movement_input() {
+    let mut app = tick_app(fixture_runtime());
+    press_key(&mut app, KeyCode::ArrowRight);
+    app.world.insert_resource(GamePaused(true));
+
+    let start = player_position(&app);
+    let tick = register_wasm_tick(&mut app);
+    for _ in 0..3 {
+        app.world.run_system(tick).unwrap();
+        assert_eq!(player_position(&app), start);
+    }
+
+    // 恢复后按夹具规则移动：速度 200，每帧 0.1 秒
+    app.world.insert_resource(GamePaused(false));
+    app.world.run_system(tick).unwrap();
+    assert_eq!(player_position(&app), start + Vec2::new(20.0, 0.0));
+}
+
+#[test]
+fn native_player_move_is_visible_to_guest_next_tick() {
+    let mut app = tick_app(fixture_runtime());
+    let tick = register_wasm_tick(&mut app);
+    app.world.run_system(tick).unwrap();
+
+    // 宿主原生瞬移（如复活点），Guest 下一次 tick 读到新位置
+    set_game_state_position(&mut app, Vec2::new(120.0, -40.0));
+    app.world.run_system(tick).unwrap();
+    let mut runtime = app.world.non_send_resource_mut::<WasmRuntime>();
+    let mut guest = runtime.guest("game_logic");
+    let host = guest.host_mut();
+    let position = host.get_player_position().unwrap();
+    assert_eq!((position.x, position.y), (120.0, -40.0));
+
+    // Guest 瞬移：本帧末尾同时写入 GameState 与玩家 Transform
+    host.set_player_position(-5.0, 8.0).unwrap();
+    assert!(host.set_player_position(f32::NAN, 0.0).is_err());
+    drop(runtime);
+    let player = spawn_player(&mut app);
+    app.world.run_system(tick).unwrap();
+    run_player_teleport(&mut app);
+    assert_eq!(player_position(&app), Vec2::new(-5.0, 8.0));
+    assert_eq!(app.world.get::<Transform>(player).unwrap().translation.truncate(), Vec2::new(-5.0, 8.0));
+    assert_eq!(app.world.get::<LinearVelocity>(player).unwrap().0, Vec2::ZERO);
+}
+
+#[test]
+fn set_player_speed_persists_in_game_state() {
+    let mut app = tick_app(fixture_runtime());
+    let tick = register_wasm_tick(&mut app);
+
+    let mut runtime = app.world.non_send_resource_mut::<WasmRuntime>();
+    let mut guest = runtime.guest("game_logic");
+    guest.host_mut().set_player_speed(400.0).unwrap();
+    assert!(guest.host_mut().set_player_speed(-1.0).is_err());
+    drop(runtime);
+
+    app.world.run_system(tick).unwrap();
+    assert_eq!(player_speed(&app), 400.0);
+    let mut runtime = app.world.non_send_resource_mut::<WasmRuntime>();
+    assert_eq!(runtime.guest("game_logic").host_mut().get_player_speed().unwrap(), 400.0);
+    drop(runtime);
+
+    // 之后的 update-game 按新速度移动
+    press_key(&mut app, KeyCode::ArrowRight);
+    let start = player_position(&app);
+    app.world.run_system(tick).unwrap();
+    assert_eq!(player_position(&app), start + Vec2::new(40.0, 0.0));
+}
+
+#[test]
+fn query_game_state_reports_native_health() {
+    let mut app = tick_app(fixture_runtime());
+    let tick = register_wasm_tick(&mut app);
+
+    // 原生系统扣血后，下一次 tick 开始时写入 HostState，UI 回调中即可读到
+    set_game_state_health(&mut app, 35.0);
+    app.world.run_system(tick).unwrap();
+    let mut runtime = app.world.non_send_resource_mut::<WasmRuntime>();
+    let state = runtime.guest("game_logic").host_mut().query_game_state().unwrap();
+    assert_eq!(state.health, 35.0);
+    assert_eq!(state.speed, 200.0);
+}
+
+#[test]
+fn shared_state_round_trips_between_guest_and_native() {
+    let mut app = tick_app(fixture_runtime());
+    let tick = register_wasm_tick(&mut app);
+
+    let mut runtime = app.world.non_send_resource_mut::<WasmRuntime>();
+    let mut guest = runtime.guest("game_logic");
+    let host = guest.host_mut();
+    assert!(host.set_var(String::new(), 1.0).is_err());
+    host.set_var("boss_phase".to_string(), 2.0).unwrap();
+    assert_eq!(host.get_var("boss_phase".to_string()).unwrap(), Some(2.0));
+    drop(runtime);
+
+    // 原生系统在 wasm_tick 之后读到 Guest 的写入，并写入自己的变量
+    app.world.run_system(tick).unwrap();
+    let mut shared = app.world.resource_mut::<SharedState>();
+    assert_eq!(shared.get("boss_phase"), Some(2.0));
+    shared.set("music_intensity", 0.75);
+
+    app.world.run_system(tick).unwrap();
+    let mut runtime = app.world.non_send_resource_mut::<WasmRuntime>();
+    let mut guest = runtime.guest("game_logic");
+    let host = guest.host_mut();
+    assert_eq!(host.get_var("music_intensity".to_string()).unwrap(), Some(0.75));
+    assert_eq!(host.get_var("missing".to_string()).unwrap(), None);
+}
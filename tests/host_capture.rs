@@ -0,0 +1,47 @@
+// Host 捕获测试：Guest 调用 bevy-api 后直接检查 HostState 中入队的 UI 命令，不运行 Bevy 系统。
+
+use test_game::test_support::fixture_runtime;
+
+#[test]
+fn toggle_panel_enqueues_spawn_panel() {
+    let mut runtime = fixture_runtime();
+    let mut guest = runtime.guest("game_logic");
+
+    guest.on_ui_event("toggle_panel").expect("on-ui-event 调用失败");
+
+    let host = guest.host();
+    assert_eq!(host.captured_panel("main_panel"), Some((-120.0, 20.0, 260.0, 200.0)));
+    let texts = host.captured_text_keys("main_panel");
+    assert!(texts.contains(&"main_panel.title"), "缺少标题文字，实际: {:?}", texts);
+    assert!(texts.iter().all(|k| k.starts_with("main_panel.")), "子节点 key 必须以父 key. 为前缀: {:?}", texts);
+    assert_eq!(host.captured_mutation_count(), 0);
+}
+
+#[test]
+fn second_toggle_hides_instead_of_respawning() {
+    let mut runtime = fixture_runtime();
+    let mut guest = runtime.guest("game_logic");
+
+    guest.on_ui_event("toggle_panel").expect("on-ui-event 调用失败");
+    guest.host_mut().clear_captured();
+    guest.on_ui_event("toggle_panel").expect("on-ui-event 调用失败");
+
+    let host = guest.host();
+    assert_eq!(host.captured_spawn_count(), 0, "隐藏时不应重新创建面板");
+    assert_eq!(host.captured_visibility(), [("main_panel", false)]);
+}
+
+#[test]
+fn third_toggle_shows_existing_panel() {
+    let mut runtime = fixture_runtime();
+    let mut guest = runtime.guest("game_logic");
+
+    guest.on_ui_event("toggle_panel").expect("on-ui-event 调用失败");
+    guest.on_ui_event("toggle_panel").expect("on-ui-event 调用失败");
+    guest.host_mut().clear_captured();
+    guest.on_ui_event("toggle_panel").expect("on-ui-event 调用失败");
+
+    let host = guest.host();
+    assert_eq!(host.captured_spawn_count(), 0);
+    assert_eq!(host.captured_visibility(), [("main_panel", true)]);
+}
@@ -0,0 +1,42 @@
+// 热重载：旧实例 save-state 导出的字节经 reload 交给新实例 restore-state；
+// hot-reload 是可选导出，任一侧缺少时以全新状态启动。
+
+use test_game::test_support::{fixture_bytes, fixture_runtime, minimal_fixture_bytes, reload_component};
+
+#[test]
+fn reload_restores_saved_state_in_new_instance() {
+    let mut runtime = fixture_runtime();
+    let mut guest = runtime.guest("game_logic");
+    assert!(guest.has_hot_reload());
+
+    // 面板已创建、当前隐藏
+    guest.restore_state(&[1, 0]).expect("restore-state 调用失败");
+    assert_eq!(guest.save_state().expect("save-state 调用失败"), [1, 0]);
+
+    reload_component(&mut runtime, "game_logic", &fixture_bytes()).expect("热重载失败");
+
+    let mut guest = runtime.guest("game_logic");
+    assert_eq!(guest.save_state().expect("save-state 调用失败"), [1, 0]);
+    // 恢复后的状态决定行为：已创建的面板只需显示，不重复创建
+    guest.on_ui_event("toggle_panel").expect("on-ui-event 调用失败");
+    assert_eq!(guest.host().captured_spawn_count(), 0);
+    assert_eq!(guest.host().captured_visibility(), [("main_panel", true)]);
+}
+
+#[test]
+fn component_without_hot_reload_loads_and_reloads_from_clean_state() {
+    let mut runtime = fixture_runtime();
+    runtime.guest("game_logic").on_ui_event("toggle_panel").expect("on-ui-event 调用失败");
+
+    // 新组件未导出 hot-reload：仍能加载，旧状态被丢弃
+    reload_component(&mut runtime, "game_logic", &minimal_fixture_bytes()).expect("热重载失败");
+    let mut guest = runtime.guest("game_logic");
+    assert!(!guest.has_hot_reload());
+    guest.on_ui_event("toggle_panel").expect("on-ui-event 调用失败");
+    assert_eq!(guest.host().captured_panel("main_panel"), Some((-120.0, 20.0, 260.0, 200.0)));
+
+    // 旧组件未导出 hot-reload：新实例同样从全新状态开始
+    reload_component(&mut runtime, "game_logic", &fixture_bytes()).expect("热重载失败");
+    let mut guest = runtime.guest("game_logic");
+    assert_eq!(guest.save_state().expect("save-state 调用失败"), [0, 0]);
+}
@@ -0,0 +1,37 @@
+// 多组件并存：每个组件有独立的 Store 与 HostState，wasm_tick 按顺序逐个 tick，
+// 共享同一个 PendingUiCommands，UI key 以组件名为前缀互不冲突。
+
+use bevy::prelude::KeyCode;
+use test_game::test_support::{fixture_runtime_with, pending_spawn_keys, press_key, register_wasm_tick, tick_app};
+
+#[test]
+fn wasm_tick_advances_every_component() {
+    let mut app = tick_app(fixture_runtime_with(&["game_logic", "ui_logic"]));
+    let tick = register_wasm_tick(&mut app);
+
+    for _ in 0..3 {
+        app.world.run_system(tick).unwrap();
+    }
+
+    let mut runtime = app.world.non_send_resource_mut::<test_game::WasmRuntime>();
+    assert_eq!(runtime.guest("game_logic").frame_count(), 3);
+    assert_eq!(runtime.guest("ui_logic").frame_count(), 3);
+}
+
+#[test]
+fn ui_keys_are_namespaced_per_component() {
+    let mut app = tick_app(fixture_runtime_with(&["game_logic", "ui_logic"]));
+    let tick = register_wasm_tick(&mut app);
+
+    // 默认 UiEventConfig：E → toggle_panel，两个组件都创建同名的 main_panel
+    press_key(&mut app, KeyCode::KeyE);
+    app.world.run_system(tick).unwrap();
+
+    let keys = pending_spawn_keys(&app);
+    assert_eq!(keys, [
+        "game_logic/main_panel",
+        "game_logic/main_panel.title",
+        "ui_logic/main_panel",
+        "ui_logic/main_panel.title",
+    ]);
+}
@@ -0,0 +1,56 @@
+// 输入录制 / 回放：录像中的按键与帧间隔驱动夹具组件，复现录制时的玩家轨迹。
+#![cfg(feature = "replay")]
+
+use bevy::prelude::{App, ButtonInput, KeyCode, Time, Vec2};
+use std::time::Duration;
+use test_game::test_support::{
+    fixture_runtime, player_position, register_wasm_tick, tick_app, ReplayMode, ReplayPlayer, ReplayRecorder,
+    REPLAY_RNG_SEED,
+};
+use test_game::FrameCounter;
+
+/// 每帧只按下 live_keys 中的一个键并 tick 一次，返回每帧后的玩家位置
+fn run(app: &mut App, live_keys: &[KeyCode]) -> Vec<Vec2> {
+    let tick = register_wasm_tick(app);
+    live_keys.iter().map(|&key| {
+        let mut input = app.world.resource_mut::<ButtonInput<KeyCode>>();
+        input.release_all();
+        input.press(key);
+        app.world.run_system(tick).unwrap();
+        player_position(app)
+    }).collect()
+}
+
+#[test]
+fn input_replay_reproduces_recorded_positions() {
+    let path = std::env::temp_dir().join(format!("test_game_input_replay_{}.bin", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let presses = [KeyCode::ArrowRight, KeyCode::ArrowRight, KeyCode::ArrowUp, KeyCode::ArrowLeft, KeyCode::ArrowDown];
+
+    let mut runtime = fixture_runtime();
+    runtime.seed_wasm_rng(REPLAY_RNG_SEED);
+    let mut app = tick_app(runtime);
+    app.insert_resource(ReplayRecorder::create(&path).unwrap());
+    let recorded = run(&mut app, &presses);
+    drop(app);
+
+    // 回放时的实时按键与帧间隔都与录制不同，只有录像中的值能复现相同的轨迹
+    let mut runtime = fixture_runtime();
+    runtime.seed_wasm_rng(REPLAY_RNG_SEED);
+    let mut app = tick_app(runtime);
+    app.world.resource_mut::<Time>().advance_by(Duration::from_millis(250));
+    app.insert_resource(ReplayPlayer::open_with_mode(&path, ReplayMode::Input).unwrap());
+    let replayed = run(&mut app, &[KeyCode::ArrowLeft; 5]);
+    std::fs::remove_file(&path).ok();
+
+    // 夹具规则：速度 200，每帧 0.1 秒
+    assert_eq!(recorded, [
+        Vec2::new(20.0, 0.0),
+        Vec2::new(40.0, 0.0),
+        Vec2::new(40.0, 20.0),
+        Vec2::new(20.0, 20.0),
+        Vec2::new(20.0, 0.0),
+    ]);
+    assert_eq!(replayed, recorded);
+    assert_eq!(app.world.resource::<FrameCounter>().0, presses.len() as u64);
+}
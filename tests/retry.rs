@@ -0,0 +1,34 @@
+// 失败调用的重试：Guest trap 后实例失效，wasm_tick 在重试前重新实例化组件，
+// 宿主侧状态（共享变量等）沿用，重试在新实例上成功执行。
+
+use bevy::prelude::{Events, KeyCode};
+use test_game::test_support::{bind_ui_event, clear_input, fixture_runtime, press_key, register_wasm_tick, tick_app};
+use test_game::{RetryQueue, SharedState, WasmCallFailed};
+
+#[test]
+fn trapped_ui_event_is_retried_on_reinstantiated_component() {
+    let mut app = tick_app(fixture_runtime());
+    let tick = register_wasm_tick(&mut app);
+    // 夹具的 flaky 事件首次 trap，之后成功
+    app.world.resource_mut::<SharedState>().set("flaky_failures", 1.0);
+    bind_ui_event(&mut app, KeyCode::KeyF, "flaky");
+
+    press_key(&mut app, KeyCode::KeyF);
+    app.world.run_system(tick).unwrap();
+    clear_input(&mut app);
+    assert_eq!(app.world.resource::<RetryQueue>().entries.len(), 1);
+    assert_eq!(app.world.resource::<SharedState>().get("flaky_failures"), Some(0.0));
+    assert_eq!(app.world.resource::<SharedState>().get("flaky_done"), None);
+
+    // 等过第一次退避（50ms）；不重新实例化时这次重试会因实例已失效而失败
+    std::thread::sleep(std::time::Duration::from_millis(60));
+    app.world.run_system(tick).unwrap();
+    assert!(app.world.resource::<RetryQueue>().entries.is_empty());
+    assert_eq!(app.world.resource::<SharedState>().get("flaky_done"), Some(1.0));
+    assert!(app.world.resource::<Events<WasmCallFailed>>().is_empty());
+
+    // 新实例之后的调用照常进行，帧计数随 HostState 延续
+    app.world.run_system(tick).unwrap();
+    let mut runtime = app.world.non_send_resource_mut::<test_game::WasmRuntime>();
+    assert_eq!(runtime.guest("game_logic").frame_count(), 3);
+}
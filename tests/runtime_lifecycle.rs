@@ -0,0 +1,153 @@
+// 运行时生命周期：构建、实例池、重置与退出清理，均使用 build.rs 生成的夹具组件。
+
+use bevy::app::{App, AppExit};
+use std::time::Instant;
+use test_game::test_support::{
+    cache_component, component_config, fixture_bytes, fixture_runtime, fixture_runtime_with, keys, register_wasm_tick,
+    shutdown_app, tick_app, BevyApiHost, PlayerState, FIXTURE_PATH, SHUTDOWN_TIMEOUT,
+};
+use test_game::{
+    PoolReleaseError, WasmComponentPool, WasmInitError, WasmRuntime, WasmRuntimeBuilder, WasmRuntimeResources, WasmSource,
+};
+
+#[test]
+fn on_shutdown_completes_within_timeout() {
+    let mut runtime = fixture_runtime();
+
+    let start = Instant::now();
+    runtime.shutdown_guest("game_logic").expect("on-shutdown 调用失败");
+    assert!(start.elapsed() < SHUTDOWN_TIMEOUT, "on-shutdown 耗时 {:?}", start.elapsed());
+}
+
+#[test]
+fn pool_rejects_runtime_with_undrained_queues() {
+    let mut pool = WasmComponentPool::new(&component_config(), "mod", &fixture_bytes(), 2).expect("创建实例池失败");
+    let first = pool.acquire().expect("池中应有空闲运行时");
+    let mut second = pool.acquire().expect("池中应有空闲运行时");
+    assert!(pool.acquire().is_none());
+
+    pool.release(first).unwrap_or_else(|e| panic!("{}", e));
+    assert_eq!(pool.idle_count(), 1);
+
+    // Guest 入队的命令同样会阻止归还
+    second.guest("mod").on_ui_event("toggle_panel").expect("on-ui-event 调用失败");
+    second.guest("mod").host_mut().despawn("leftover".to_string()).unwrap();
+    let Err(PoolReleaseError::UndrainedQueues { instance, runtime }) = pool.release(second) else {
+        panic!("未清空队列的运行时不应被接受");
+    };
+    assert_eq!(instance, "mod");
+    assert_eq!(pool.idle_count(), 1);
+
+    let mut runtime = *runtime;
+    runtime.guest("mod").host_mut().clear_captured();
+    pool.release(runtime).unwrap_or_else(|e| panic!("{}", e));
+    assert_eq!(pool.idle_count(), 2);
+}
+
+#[test]
+fn builder_reports_missing_file_and_loads_fixture() {
+    let missing = WasmRuntimeBuilder::new().wasm_path("assets/no_such_logic.wasm").build();
+    assert!(matches!(missing, Err(WasmInitError::FileNotFound { .. })));
+
+    let mut runtime = WasmRuntimeBuilder::new()
+        .wasm_path(FIXTURE_PATH)
+        .fuel(10_000_000)
+        .build()
+        .expect("构建运行时失败");
+    assert_eq!(runtime.tick_order(), ["game_logic"]);
+
+    // 与 wasm_tick 相同，每帧先重设 fuel；Guest 调用按执行量扣减
+    let mut guest = runtime.guest("game_logic");
+    guest.set_fuel(10_000_000).unwrap();
+    let state = PlayerState { x: 0.0, y: 0.0, speed: 100.0 };
+    let result = guest.update_game(keys(true, false, false, false), state, 0.1).expect("update-game 调用失败");
+    assert_eq!((result.x, result.y), (10.0, 0.0));
+    assert!(guest.fuel_remaining().unwrap() < 10_000_000);
+}
+
+#[test]
+fn builder_loads_embedded_bytes() {
+    // 模拟 include_bytes! 得到的 'static 切片
+    let embedded: &'static [u8] = Box::leak(fixture_bytes().into_boxed_slice());
+    let mut runtime = WasmRuntimeBuilder::new()
+        .source(WasmSource::Embedded(embedded))
+        .build()
+        .expect("从内嵌字节构建运行时失败");
+    let mut guest = runtime.guest("game_logic");
+    let keyboard = guest.process_keyboard(keys(true, false, false, false)).expect("process-keyboard 调用失败");
+    let result = guest
+        .update_game(keyboard, PlayerState { x: 0.0, y: 0.0, speed: 100.0 }, 0.1)
+        .expect("update-game 调用失败");
+    assert_eq!((result.x, result.y), (10.0, 0.0));
+}
+
+#[test]
+fn seeded_runtimes_produce_identical_random_sequences() {
+    let sample = |seed: u64| -> Vec<f32> {
+        let mut runtime = fixture_runtime();
+        runtime.seed_wasm_rng(seed);
+        let mut guest = runtime.guest("game_logic");
+        let host = guest.host_mut();
+        (0..8).map(|i| if i % 2 == 0 {
+            host.get_random_f32().unwrap()
+        } else {
+            host.get_random_range(-5.0, 5.0).unwrap()
+        }).collect()
+    };
+
+    let first = sample(42);
+    assert_eq!(first, sample(42));
+    assert_ne!(first, sample(43));
+    assert!(first.iter().step_by(2).all(|v| (0.0..1.0).contains(v)));
+    assert!(first.iter().skip(1).step_by(2).all(|v| (-5.0..5.0).contains(v)));
+}
+
+#[test]
+fn reset_reinstantiates_from_cached_component() {
+    let runtime = fixture_runtime();
+    let mut resources = WasmRuntimeResources::new(&runtime);
+    cache_component(&mut resources, "game_logic", &fixture_bytes());
+
+    // 旧实例积累帧计数、未转存的命令、订阅与 Guest 内部状态
+    let mut app = tick_app(runtime);
+    let tick = register_wasm_tick(&mut app);
+    app.world.run_system(tick).unwrap();
+    let mut runtime = app.world.non_send_resource_mut::<WasmRuntime>();
+    let mut guest = runtime.guest("game_logic");
+    guest.on_ui_event("toggle_panel").expect("on-ui-event 调用失败");
+    guest.host_mut().subscribe_to_bevy_event("scene-loaded".to_string()).unwrap();
+    assert_eq!(guest.frame_count(), 1);
+    assert_eq!(guest.save_state().unwrap(), [1, 1]);
+
+    assert!(resources.reset_all(&mut runtime).is_empty());
+    let mut guest = runtime.guest("game_logic");
+    assert_eq!(guest.frame_count(), 0);
+    assert_eq!(guest.host().captured_spawn_count(), 0);
+    assert!(!guest.host().is_subscribed("scene-loaded"));
+    // 新实例的 Guest 状态同样从头开始，且可以正常调用
+    assert_eq!(guest.save_state().unwrap(), [0, 0]);
+    guest
+        .update_game(keys(false, false, false, false), PlayerState { x: 0.0, y: 0.0, speed: 200.0 }, 0.1)
+        .expect("update-game 调用失败");
+}
+
+#[test]
+fn app_exit_calls_on_shutdown_once_per_instance() {
+    let mut app = shutdown_app(fixture_runtime_with(&["game_logic", "ui_logic"]));
+    let calls = |app: &mut App| {
+        let mut runtime = app.world.non_send_resource_mut::<WasmRuntime>();
+        (runtime.guest("game_logic").shutdown_calls(), runtime.guest("ui_logic").shutdown_calls())
+    };
+
+    app.update();
+    assert_eq!(calls(&mut app), (0, 0));
+
+    // 同一帧内两个 AppExit、下一帧再来一个，每个实例都只触发一次 on-shutdown
+    app.world.send_event(AppExit);
+    app.world.send_event(AppExit);
+    app.update();
+    assert_eq!(calls(&mut app), (1, 1));
+    app.world.send_event(AppExit);
+    app.update();
+    assert_eq!(calls(&mut app), (1, 1));
+}